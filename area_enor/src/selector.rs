@@ -28,7 +28,7 @@ use jiff::{Timestamp as JiffTimestamp, Zoned, tz::TimeZone};
 use runway_plugin_api::{
     AirportSelectionRequest, AirportSelectionResult, ParsedMetar, RunwayInfo,
     RunwaySelectionsRequest, RunwaySelectionsResponse, RunwayUse, RunwayUseEntry, SelectionSource,
-    SelectionTag, Tag, WeatherDescriptor, helpers::best_headwind, tags,
+    SelectionTag, Tag, helpers::best_headwind, tags,
 };
 use runway_selector_area_config::AreaConfig;
 use thiserror::Error;
@@ -36,7 +36,7 @@ use tracing::{debug, warn};
 
 const HEADWIND_MARGIN_KT: i32 = 2;
 const ENZV_CROSSWIND_SWITCH_KT: i32 = 15;
-const ENGM_LVP_CEILING_HUNDREDS_FT: i32 = 15;
+const ENGM_LVP_CEILING_FT: u32 = 1500;
 const ENGM_LOW_VISIBILITY_METERS: u32 = 5000;
 const ENGM_POSSIBLE_DEICE_TEMP_C: i32 = 5;
 
@@ -402,7 +402,9 @@ fn engm_low_visibility_procedures_apply(metar: Option<&ParsedMetar>) -> bool {
         return false;
     }
 
-    if has_ceiling_below_1500(m) {
+    // The host worst-cases unreadable layers: unknown coverage counts as a
+    // ceiling and an unknown height is reported as 0 ft.
+    if m.ceiling_ft.is_some_and(|ft| ft < ENGM_LVP_CEILING_FT) {
         return true;
     }
     if !m.rvr.is_empty() {
@@ -416,7 +418,7 @@ fn engm_low_visibility_procedures_apply(metar: Option<&ParsedMetar>) -> bool {
     if m.vertical_visibility_hundreds_ft.is_some() {
         return true;
     }
-    if has_freezing(m) {
+    if m.freezing_weather {
         return true;
     }
     if possible_deice(m) {
@@ -426,31 +428,6 @@ fn engm_low_visibility_procedures_apply(metar: Option<&ParsedMetar>) -> bool {
     false
 }
 
-fn has_ceiling_below_1500(m: &ParsedMetar) -> bool {
-    use runway_plugin_api::CloudCoverage::{Broken, Overcast};
-    m.clouds.iter().any(|cloud| {
-        // coverage None → "///"; treat as broken/overcast (worst-case)
-        let coverage_qualifies = cloud
-            .coverage
-            .map(|c| matches!(c, Broken | Overcast))
-            .unwrap_or(true);
-        if !coverage_qualifies {
-            return false;
-        }
-        // height None → "///"; treat as below 1500 ft
-        cloud
-            .height_hundreds_ft
-            .map(|h| h < ENGM_LVP_CEILING_HUNDREDS_FT)
-            .unwrap_or(true)
-    })
-}
-
-fn has_freezing(m: &ParsedMetar) -> bool {
-    m.weather_phenomena
-        .iter()
-        .any(|pw| pw.descriptors.contains(&WeatherDescriptor::Freezing))
-}
-
 fn possible_deice(m: &ParsedMetar) -> bool {
     const QUALIFYING: [&str; 10] = ["DZ", "RA", "SN", "SG", "PL", "GR", "GS", "UP", "BR", "FG"];
 
@@ -474,8 +451,8 @@ fn possible_deice(m: &ParsedMetar) -> bool {
 mod tests {
     use super::*;
     use runway_plugin_api::{
-        CloudCoverage, CloudData, CrosswindDirection, MetarData, RvrData, WeatherIntensity,
-        WeatherPhenomenonData,
+        CloudCoverage, CloudData, CrosswindDirection, MetarData, RvrData, WeatherDescriptor,
+        WeatherIntensity, WeatherPhenomenonData,
     };
 
     fn enor_selector() -> EnorSelector {
@@ -494,25 +471,23 @@ mod tests {
         RunwayInfo {
             identifier: identifier.into(),
             heading: 0,
+            heading_reference: None,
             headwind_kt: Some(headwind),
             tailwind_kt: Some((-headwind).max(0)),
             crosswind_kt: Some(crosswind),
             crosswind_direction: Some(CrosswindDirection::Left),
+            true_bearing_deg: None,
+            length_m: None,
         }
     }
 
     fn empty_parsed() -> ParsedMetar {
         ParsedMetar {
             is_cavok: true,
-            wind: None,
-            visibility_meters: None,
-            rvr: vec![],
-            clouds: vec![],
-            vertical_visibility_hundreds_ft: None,
-            weather_phenomena: vec![],
             temperature_c: Some(10),
             dew_point_c: Some(5),
             qnh_hpa: Some(1013),
+            ..Default::default()
         }
     }
 
@@ -581,6 +556,7 @@ mod tests {
             icao: "ENBR".into(),
             runways: vec![runway("17", 8, 0), runway("35", 0, 0)],
             metar: None,
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert!(!out.handled);
@@ -594,6 +570,7 @@ mod tests {
             icao: "ENBR".into(),
             runways: vec![runway("17", 5, 0), runway("35", 4, 0)],
             metar: Some(empty_metar("ENBR")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert!(!out.handled);
@@ -606,6 +583,7 @@ mod tests {
             icao: "ENBR".into(),
             runways: vec![runway("17", 12, 0), runway("35", -12, 0)],
             metar: Some(empty_metar("ENBR")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert!(out.handled);
@@ -625,6 +603,7 @@ mod tests {
                 runway("19R", -10, 0),
             ],
             metar: Some(empty_metar("ENGM")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
 
@@ -647,6 +626,7 @@ mod tests {
                 runway("19R", -10, 0),
             ],
             metar: Some(empty_metar("ENGM")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(23, 0));
 
@@ -667,6 +647,7 @@ mod tests {
                 runway("19R", -10, 0),
             ],
             metar: Some(empty_metar("ENGM")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(5, 0));
 
@@ -683,21 +664,28 @@ mod tests {
                 RunwayInfo {
                     identifier: "01L".into(),
                     heading: 7,
+                    heading_reference: None,
                     headwind_kt: None,
                     tailwind_kt: None,
                     crosswind_kt: None,
                     crosswind_direction: None,
+                    true_bearing_deg: None,
+                    length_m: None,
                 },
                 RunwayInfo {
                     identifier: "01R".into(),
                     heading: 7,
+                    heading_reference: None,
                     headwind_kt: None,
                     tailwind_kt: None,
                     crosswind_kt: None,
                     crosswind_direction: None,
+                    true_bearing_deg: None,
+                    length_m: None,
                 },
             ],
             metar: Some(empty_metar("ENGM")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert!(out.handled);
@@ -716,6 +704,7 @@ mod tests {
             icao: "ENGM".into(),
             runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
             metar: Some(metar),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert_eq!(uses(&out), vec![RunwayUse::Departing, RunwayUse::Arriving]);
@@ -731,6 +720,7 @@ mod tests {
             icao: "ENGM".into(),
             runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
             metar: Some(metar),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert!(uses(&out).contains(&RunwayUse::Departing));
@@ -745,12 +735,14 @@ mod tests {
                 descriptors: vec![WeatherDescriptor::Freezing],
                 phenomena: vec!["DZ".into()],
             }],
+            freezing_weather: true,
             ..empty_parsed()
         });
         let airport = AirportSelectionRequest {
             icao: "ENGM".into(),
             runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
             metar: Some(metar),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         let u = uses(&out);
@@ -772,6 +764,24 @@ mod tests {
             icao: "ENGM".into(),
             runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
             metar: Some(metar),
+            atis: vec![],
+        };
+        let out = select(&sel, airport, &oslo_zoned(12, 0));
+        assert_eq!(uses(&out), vec![RunwayUse::Departing, RunwayUse::Arriving]);
+    }
+
+    #[test]
+    fn engm_segregated_during_day_when_ceiling_below_1500_ft() {
+        let sel = enor_selector();
+        let metar = described(ParsedMetar {
+            ceiling_ft: Some(1200),
+            ..empty_parsed()
+        });
+        let airport = AirportSelectionRequest {
+            icao: "ENGM".into(),
+            runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
+            metar: Some(metar),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert_eq!(uses(&out), vec![RunwayUse::Departing, RunwayUse::Arriving]);
@@ -792,6 +802,7 @@ mod tests {
             icao: "ENGM".into(),
             runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
             metar: Some(metar),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert!(uses(&out).iter().all(|u| *u == RunwayUse::Both));
@@ -828,6 +839,7 @@ mod tests {
                 runway("28", -2, 8),
             ],
             metar: Some(empty_metar("ENZV")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert_eq!(ids(&out), vec!["18"]);
@@ -848,6 +860,7 @@ mod tests {
                 runway("28", 10, 5),
             ],
             metar: Some(empty_metar("ENZV")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert_eq!(ids(&out), vec!["28"]);
@@ -866,6 +879,7 @@ mod tests {
                 runway("28", 10, 20),
             ],
             metar: Some(empty_metar("ENZV")),
+            atis: vec![],
         };
         let out = select(&sel, airport, &oslo_zoned(12, 0));
         assert_eq!(ids(&out), vec!["36"]);
@@ -882,6 +896,7 @@ mod tests {
                 icao: "ENGM".into(),
                 runways: vec![runway("01L", 10, 0), runway("01R", 10, 0)],
                 metar: Some(empty_metar("ENGM")),
+                atis: vec![],
             }],
         };
         let resp = sel.select_runways(&request).unwrap();
@@ -915,10 +930,114 @@ mod tests {
                 icao: "ENBR".into(),
                 runways: vec![runway("17", 12, 0), runway("35", -12, 0)],
                 metar: Some(empty_metar("ENBR")),
+                atis: vec![],
             }],
         };
         let resp = sel.select_runways(&request).unwrap();
         assert_eq!(resp.results.len(), 1);
         assert_eq!(resp.results[0].runway_uses[0].runway, "17");
     }
+
+    /// A selection request computed by the host from a METAR, so the wind
+    /// components come from the same code as in production.
+    fn request_from_metar(
+        metar: metar_decoder::metar::Metar,
+        runways: &[(&str, u16)],
+    ) -> AirportSelectionRequest {
+        use runway_selector_core::{Airport, Runway, RunwayDirection};
+
+        let direction = |(identifier, degrees): (&str, u16)| RunwayDirection {
+            degrees,
+            identifier: identifier.to_string(),
+            threshold: None,
+            true_bearing: None,
+        };
+        let airport = Airport {
+            icao: metar.icao.clone(),
+            metar: Some(metar),
+            elevation_ft: None,
+            reference_point: None,
+            tower_frequency: None,
+            airspace_class: None,
+            magnetic_variation: None,
+            runways: runways
+                .chunks(2)
+                .map(|pair| Runway::new([direction(pair[0]), direction(pair[1])]))
+                .collect(),
+            runways_in_use: indexmap::IndexMap::new(),
+            atis: indexmap::IndexMap::new(),
+            controllers: Vec::new(),
+            selection_tags: Vec::new(),
+        };
+        runway_selector_core::plugin_convert::airport_to_request(&airport)
+    }
+
+    fn tailwind_kt(airport: &AirportSelectionRequest, identifier: &str) -> i32 {
+        airport
+            .runways
+            .iter()
+            .find(|r| r.identifier == identifier)
+            .and_then(|r| r.tailwind_kt)
+            .unwrap()
+    }
+
+    fn assert_least_tailwind(
+        metar: metar_decoder::metar::Metar,
+        runways: &[(&str, u16)],
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        let airport = request_from_metar(metar, runways);
+        let out = select(&enor_selector(), airport.clone(), &oslo_zoned(12, 0));
+        if out.source == SelectionSource::Metar {
+            let best = airport
+                .runways
+                .iter()
+                .filter_map(|r| r.tailwind_kt)
+                .min()
+                .unwrap();
+            for selected in ids(&out) {
+                proptest::prop_assert!(tailwind_kt(&airport, selected) <= best);
+            }
+        }
+        Ok(())
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn enbr_never_picks_more_tailwind_than_needed(
+            metar in metar_decoder::arbitrary::metar_for("ENBR".to_string()),
+        ) {
+            assert_least_tailwind(metar, &[("17", 169), ("35", 349)])?;
+        }
+
+        #[test]
+        fn engm_never_picks_more_tailwind_than_needed(
+            metar in metar_decoder::arbitrary::metar_for("ENGM".to_string()),
+        ) {
+            assert_least_tailwind(
+                metar,
+                &[("01L", 12), ("19R", 192), ("01R", 12), ("19L", 192)],
+            )?;
+        }
+
+        #[test]
+        fn enzv_only_leaves_the_main_runway_for_less_crosswind(
+            metar in metar_decoder::arbitrary::metar_for("ENZV".to_string()),
+        ) {
+            let airport = request_from_metar(
+                metar,
+                &[("10", 104), ("28", 284), ("18", 177), ("36", 357)],
+            );
+            let out = select(&enor_selector(), airport.clone(), &oslo_zoned(12, 0));
+            let selected = ids(&out)[0];
+            if selected == "10" || selected == "28" {
+                let main = ["18", "36"]
+                    .into_iter()
+                    .map(|id| crosswind_kt(&airport.runways, id))
+                    .min()
+                    .unwrap();
+                proptest::prop_assert!(main >= ENZV_CROSSWIND_SWITCH_KT);
+                proptest::prop_assert!(crosswind_kt(&airport.runways, selected) < main);
+            }
+        }
+    }
 }
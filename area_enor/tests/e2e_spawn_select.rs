@@ -65,10 +65,13 @@ fn runway(identifier: &str, heading: u16, headwind: i32, crosswind: i32) -> Runw
     RunwayInfo {
         identifier: identifier.into(),
         heading,
+        heading_reference: None,
         headwind_kt: Some(headwind),
         tailwind_kt: Some((-headwind).max(0)),
         crosswind_kt: Some(crosswind),
         crosswind_direction: Some(CrosswindDirection::Left),
+        true_bearing_deg: None,
+        length_m: None,
     }
 }
 
@@ -98,17 +101,13 @@ async fn spawns_real_plugin_and_gets_selections_over_http() {
                     raw: "ENGM 311050Z 01010KT CAVOK 15/05 Q1013".into(),
                     parsed: Some(runway_plugin_api::ParsedMetar {
                         is_cavok: true,
-                        wind: None,
-                        visibility_meters: None,
-                        rvr: vec![],
-                        clouds: vec![],
-                        vertical_visibility_hundreds_ft: None,
-                        weather_phenomena: vec![],
                         temperature_c: Some(15),
                         dew_point_c: Some(5),
                         qnh_hpa: Some(1013),
+                        ..Default::default()
                     }),
                 }),
+                atis: vec![],
             },
             // Generic airport with a clear headwind winner.
             AirportSelectionRequest {
@@ -118,12 +117,14 @@ async fn spawns_real_plugin_and_gets_selections_over_http() {
                     raw: "ENBR 311050Z 17012KT CAVOK 14/06 Q1014".into(),
                     parsed: None,
                 }),
+                atis: vec![],
             },
             // No METAR: the plugin must defer with handled=false.
             AirportSelectionRequest {
                 icao: "ENVA".into(),
                 runways: vec![runway("09", 81, 0, 0)],
                 metar: None,
+                atis: vec![],
             },
         ],
     };
@@ -12,7 +12,10 @@ use runway_selector_areas::{
 };
 use tracing::info;
 
-use crate::config::es_runway_selector_project_dir;
+use crate::{
+    area_runtime::{self, InstalledArea},
+    config::es_runway_selector_project_dir,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum AreaCommand {
@@ -109,6 +112,19 @@ pub fn resolved_install_dir(cfg: &TopLevelConfig) -> PathBuf {
         .unwrap_or_else(|| es_runway_selector_project_dir().data_dir().join("areas"))
 }
 
+/// The installed areas, or none when there is no top-level config to say
+/// where they are installed.
+pub fn load_installed_areas() -> Result<Vec<InstalledArea>> {
+    match load_top_level_config() {
+        Ok(top_level) => {
+            let install_dir = resolved_install_dir(&top_level);
+            area_runtime::load_installed_areas(&install_dir)
+                .with_context(|| format!("Loading installed areas from {}", install_dir.display()))
+        }
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
 fn print_installed(install_dir: &Path) -> Result<()> {
     let installed = list_installed_areas(install_dir)
         .with_context(|| format!("Listing installed areas in {}", install_dir.display()))?;
@@ -95,6 +95,8 @@ mod tests {
                 default_runways: IndexMap::new(),
                 time_zone: None,
                 sector_file_prefix: Some(sct_prefix.into()),
+                runway_source: Default::default(),
+                atis: Default::default(),
             },
         }
     }
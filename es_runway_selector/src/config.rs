@@ -16,6 +16,7 @@ use jiff::{
     tz::TimeZone,
 };
 use regex::Regex;
+use runway_selector_core::data_source::DataSourceConfig;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use sysinfo::{ProcessesToUpdate, System};
@@ -29,10 +30,53 @@ pub(crate) fn es_runway_selector_project_dir() -> ProjectDirs {
         .expect("Failed to get project directories")
 }
 
+/// One sector package: the folder it lives in and the file stem its `.sct`,
+/// `.ese` and `.rwy` files share, e.g. `ENOR-Norway-NC_20250612121259-241301-0006`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct SectorFile {
+    folder: PathBuf,
+    prefix: String,
+}
+
+impl SectorFile {
+    /// Where the `.rwy` of an area without a sector package is written:
+    /// `folder/<prefix>.rwy`.
+    pub fn new(folder: PathBuf, prefix: String) -> Self {
+        Self { folder, prefix }
+    }
+
+    fn from_sct_path(path: &Path) -> Option<Self> {
+        Some(Self {
+            folder: path.parent()?.to_owned(),
+            prefix: path.file_stem()?.to_string_lossy().to_string(),
+        })
+    }
+
+    pub fn sct_path(&self) -> PathBuf {
+        self.folder.join(format!("{}.sct", self.prefix))
+    }
+
+    pub fn ese_path(&self) -> PathBuf {
+        self.folder.join(format!("{}.ese", self.prefix))
+    }
+
+    pub fn rwy_path(&self) -> PathBuf {
+        self.folder.join(format!("{}.rwy", self.prefix))
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ESConfig {
-    euroscope_config_folder: PathBuf,
-    sector_file_prefix: String,
+    /// `None` only when no sector file was required and none was found.
+    euroscope_config_folder: Option<PathBuf>,
+    sector_file_prefix: Option<String>,
+    /// The newest sector file of each installed area, one per area, the
+    /// primary one (`euroscope_config_folder` / `sector_file_prefix`) first.
+    sector_files: Vec<SectorFile>,
     #[allow(dead_code)] // used in tests
     config_file_path: PathBuf,
     config: Configurable,
@@ -45,6 +89,7 @@ struct Configurable {
     euroscope_config_folder: Option<PathBuf>,
     euroscope_executable_path: Option<IndexMap<String, PathBuf>>,
     es_main_window_delay_ms: Option<u64>,
+    data_source: Option<DataSourceConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
@@ -69,44 +114,81 @@ impl ESConfig {
     /// the newest match wins. Pass an empty slice when no areas are installed
     /// — the search then accepts any `.sct` so the wizard has something to
     /// suggest an area for.
+    ///
+    /// Without `sector_file_required`, as when every installed area reads
+    /// its runways from OurAirports, a missing sector file is not an error
+    /// and the user is not asked for a folder.
     pub fn find_euroscope_config_folder(
         clean_config: bool,
         sector_file_prefixes: &[String],
+        sector_file_required: bool,
     ) -> Option<Self> {
         let (mut config, config_file_path) = setup_configuration(clean_config).unwrap_or_log();
-        let (sct_path, sector_file_prefix) = search_for_newest_sct_file(sector_file_prefixes)
-            .or_else(|| config.find_from_config(sector_file_prefixes))
-            .or_else(|| {
-                query_user_euroscope_config_folder(
-                    &mut config,
-                    &config_file_path,
-                    sector_file_prefixes,
-                )
-            })?;
+        let found = search_for_newest_sct_file(sector_file_prefixes)
+            .or_else(|| config.find_from_config(sector_file_prefixes));
+        let found = match found {
+            Some(found) => Some(found),
+            None if sector_file_required => Some(query_user_euroscope_config_folder(
+                &mut config,
+                &config_file_path,
+                sector_file_prefixes,
+            )?),
+            None => None,
+        };
 
         let app_launchers = get_app_launchers(&config_file_path);
+        let Some((sct_path, sector_file_prefix)) = found else {
+            return Some(Self {
+                euroscope_config_folder: config.euroscope_config_folder.clone(),
+                sector_file_prefix: None,
+                sector_files: Vec::new(),
+                config,
+                config_file_path,
+                app_launchers,
+            });
+        };
+
+        let primary = SectorFile {
+            folder: sct_path,
+            prefix: sector_file_prefix,
+        };
+        let mut folders = euroscope_folders();
+        folders.extend(config.euroscope_config_folder.clone());
+        folders.push(primary.folder.clone());
+        let sector_files = sector_files_primary_first(primary, &folders, sector_file_prefixes);
+        let SectorFile { folder, prefix } = sector_files[0].clone();
 
         Some(Self {
-            euroscope_config_folder: sct_path,
-            sector_file_prefix,
+            euroscope_config_folder: Some(folder),
+            sector_file_prefix: Some(prefix),
+            sector_files,
             config,
             config_file_path,
             app_launchers,
         })
     }
 
-    pub fn get_sct_file_path(&self) -> PathBuf {
-        self.euroscope_config_folder
-            .join(format!("{}.sct", self.sector_file_prefix))
+    pub fn get_sector_file_prefix(&self) -> Option<&str> {
+        self.sector_file_prefix.as_deref()
+    }
+
+    /// The folder of the primary sector file, or the configured
+    /// `euroscope_config_folder` when there is none.
+    pub fn euroscope_config_folder(&self) -> Option<&Path> {
+        self.euroscope_config_folder.as_deref()
     }
 
-    pub fn get_rwy_file_path(&self) -> PathBuf {
-        self.euroscope_config_folder
-            .join(format!("{}.rwy", self.sector_file_prefix))
+    /// Where METARs, ATISes and online controllers are read from; the live
+    /// VATSIM feeds unless config.toml says otherwise.
+    pub fn data_source(&self) -> DataSourceConfig {
+        self.config.data_source.clone().unwrap_or_default()
     }
 
-    pub fn get_sector_file_prefix(&self) -> &str {
-        &self.sector_file_prefix
+    /// Every sector file to select runways for: the primary one, then the
+    /// newest sector file of each other installed area. Empty when no sector
+    /// file was required and none was found.
+    pub fn sector_files(&self) -> &[SectorFile] {
+        &self.sector_files
     }
 
     pub async fn run_apps(&self, euroscope_ready: bool) -> Vec<tokio::task::JoinHandle<()>> {
@@ -140,7 +222,7 @@ impl ESConfig {
                         continue;
                     }
                 };
-                let prf_path = self.euroscope_config_folder.clone();
+                let prf_path = self.euroscope_config_folder.clone().unwrap_or_default();
                 let es = app.name == "EuroScope";
                 let pre_wait = if es && first_euroscope_started {
                     true
@@ -446,7 +528,8 @@ fn setup_configuration(clean_config: bool) -> Result<(Configurable, PathBuf), Co
     }
 }
 
-fn search_for_newest_sct_file(prefixes: &[String]) -> Option<(PathBuf, String)> {
+/// The folders EuroScope keeps sector packages in, where they exist.
+fn euroscope_folders() -> Vec<PathBuf> {
     let bd = BaseDirs::new();
     let ud = UserDirs::new();
     let mut possibilities = [
@@ -465,15 +548,76 @@ fn search_for_newest_sct_file(prefixes: &[String]) -> Option<(PathBuf, String)>
     .collect_vec();
 
     possibilities.retain(|p| p.exists() && p.is_dir());
+    possibilities
+}
 
-    search_for_sct_with_possibilities(&possibilities, prefixes)
+fn search_for_newest_sct_file(prefixes: &[String]) -> Option<(PathBuf, String)> {
+    search_for_sct_with_possibilities(&euroscope_folders(), prefixes)
 }
 
 fn search_for_sct_with_possibilities<P: AsRef<Path>>(
     possibilities: &[P],
     prefixes: &[String],
 ) -> Option<(PathBuf, String)> {
-    let sct_files = possibilities
+    let file = find_sct_files(possibilities, prefixes)
+        .into_iter()
+        .max_by_key(get_sector_file_name_time)?;
+    let sector_file = SectorFile::from_sct_path(&file)?;
+    Some((sector_file.folder, sector_file.prefix))
+}
+
+/// The newest sector file for each of `prefixes`, in prefix order. Prefixes
+/// without a sector file are left out.
+fn newest_sct_file_per_prefix<P: AsRef<Path>>(
+    possibilities: &[P],
+    prefixes: &[String],
+) -> Vec<SectorFile> {
+    let files = find_sct_files(possibilities, prefixes);
+    prefixes
+        .iter()
+        .unique()
+        .filter_map(|prefix| {
+            files
+                .iter()
+                .filter(|file| {
+                    get_sector_file_name(file).is_some_and(|name| name.starts_with(prefix))
+                })
+                .max_by_key(get_sector_file_name_time)
+                .and_then(|file| SectorFile::from_sct_path(file))
+        })
+        .unique()
+        .collect()
+}
+
+/// One sector file per area: the newest of each of `prefixes` in
+/// `possibilities`. The primary's area comes first, and a newer file of that
+/// area found elsewhere replaces the primary.
+fn sector_files_primary_first<P: AsRef<Path>>(
+    primary: SectorFile,
+    possibilities: &[P],
+    prefixes: &[String],
+) -> Vec<SectorFile> {
+    let mut sector_files = newest_sct_file_per_prefix(possibilities, prefixes);
+    let primary_area = prefixes
+        .iter()
+        .find(|prefix| primary.prefix.starts_with(prefix.as_str()));
+    let same_area = primary_area.and_then(|area| {
+        sector_files
+            .iter()
+            .position(|file| file.prefix.starts_with(area.as_str()))
+    });
+    let primary = match same_area {
+        Some(index) => sector_files.remove(index),
+        None => primary,
+    };
+    sector_files.retain(|file| *file != primary);
+    sector_files.insert(0, primary);
+    sector_files
+}
+
+/// `.sct` files directly inside any of `possibilities`.
+fn find_sct_files<P: AsRef<Path>>(possibilities: &[P], prefixes: &[String]) -> Vec<PathBuf> {
+    possibilities
         .iter()
         .flat_map(|p| {
             WalkDir::new(p)
@@ -499,15 +643,8 @@ fn search_for_sct_with_possibilities<P: AsRef<Path>>(
                 })
                 .map(|e| e.path().to_path_buf())
         })
-        .collect::<Vec<_>>();
-    let file = sct_files.iter().max_by_key(get_sector_file_name_time)?;
-    Some((
-        file.parent()?.to_owned(),
-        Path::new(file.file_name()?)
-            .file_stem()?
-            .to_string_lossy()
-            .to_string(),
-    ))
+        .unique()
+        .collect()
 }
 
 fn get_sector_file_name<P: AsRef<Path>>(path: &P) -> Option<String> {
@@ -556,6 +693,68 @@ mod tests {
         assert_eq!(dt, target);
     }
 
+    #[test]
+    fn finds_the_newest_sector_file_of_each_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "ENOR-Norway-NC_20250101120000-250101-0001.sct",
+            "ENOR-Norway-NC_20250612121259-241301-0006.sct",
+            "ESAA-Sweden_20250301120000-250301-0002.sct",
+            "EKDK-Denmark_20250701120000-250701-0001.sct",
+            "ESAA-Sweden_20250301120000-250301-0002.ese",
+        ] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let prefixes = ["ENOR".to_string(), "ESAA".to_string(), "EFIN".to_string()];
+        let found = newest_sct_file_per_prefix(&[dir.path(), dir.path()], &prefixes);
+        let stems: Vec<_> = found.iter().map(SectorFile::prefix).collect();
+        assert_eq!(
+            stems,
+            [
+                "ENOR-Norway-NC_20250612121259-241301-0006",
+                "ESAA-Sweden_20250301120000-250301-0002",
+            ]
+        );
+        assert_eq!(
+            found[1].rwy_path(),
+            dir.path()
+                .join("ESAA-Sweden_20250301120000-250301-0002.rwy")
+        );
+    }
+
+    #[test]
+    fn a_newer_file_of_the_primary_area_replaces_the_primary() {
+        let found = tempfile::tempdir().unwrap();
+        let configured = tempfile::tempdir().unwrap();
+        let older = "ENOR-Norway-NC_20250101120000-250101-0001";
+        let newer = "ENOR-Norway-NC_20250612121259-241301-0006";
+        fs::write(found.path().join(format!("{older}.sct")), "").unwrap();
+        fs::write(configured.path().join(format!("{newer}.sct")), "").unwrap();
+        fs::write(
+            found
+                .path()
+                .join("ESAA-Sweden_20250301120000-250301-0002.sct"),
+            "",
+        )
+        .unwrap();
+        let primary = SectorFile {
+            folder: found.path().to_path_buf(),
+            prefix: older.to_string(),
+        };
+        let prefixes = ["ESAA".to_string(), "ENOR".to_string()];
+
+        let sector_files =
+            sector_files_primary_first(primary, &[found.path(), configured.path()], &prefixes);
+        assert_eq!(
+            sector_files
+                .iter()
+                .map(SectorFile::prefix)
+                .collect::<Vec<_>>(),
+            [newer, "ESAA-Sweden_20250301120000-250301-0002"]
+        );
+        assert_eq!(sector_files[0].folder, configured.path());
+    }
+
     // [[executable]]
     // name = "Euroscope"
     // prf = "enor_rads.prf"
@@ -1,10 +1,14 @@
 pub(crate) mod area_cli;
 pub(crate) mod area_runtime;
 pub(crate) mod config;
+pub(crate) mod metar_cli;
 pub(crate) mod plugin_runner;
+pub(crate) mod replay_cli;
+pub(crate) mod sct_cli;
 pub(crate) mod wizard;
 
 use std::{
+    borrow::Cow,
     fs::File,
     path::{Path, PathBuf},
     sync::Arc,
@@ -14,9 +18,16 @@ use std::{
 use anyhow::{Context, Result, anyhow};
 use clap::Parser;
 use config::ESConfig;
-use indexmap::{IndexMap, IndexSet};
-use jiff::{Zoned, tz::TimeZone};
-use runway_selector_core::{Airports, output::write_runways_to_rwy_file};
+use indexmap::IndexSet;
+use jiff::{Timestamp, Zoned, tz::TimeZone};
+use runway_selector_core::{
+    Airports, AreaConfig, RunwaySource,
+    atis::AtisGrammar,
+    bundle::{Bundle, BundleManifest, RunwayData},
+    data_source::{DataSource, DataSourceConfig, RecordingSource},
+    ese,
+    output::{active_runway_lines, write_runways_to_rwy_file},
+};
 use self_update::{
     Status::{UpToDate, Updated},
     cargo_crate_version,
@@ -40,6 +51,16 @@ struct Cli {
     log_level: Option<String>,
     #[clap(long, hide = true)]
     previous_log_path: Option<PathBuf>,
+    #[clap(long)]
+    /// Where METARs, ATISes and online controllers are read from: `http`,
+    /// `local:<path>` (a folder with metars.txt and vatsim-data.json, or one
+    /// such file) or `fixture:<path>` (a recorded run). Overrides
+    /// `data_source` in config.toml.
+    data_source: Option<DataSourceConfig>,
+    #[clap(long)]
+    /// Also save everything each sector file's selection read, and what it
+    /// selected, as a bundle in this folder for `replay`.
+    capture: Option<PathBuf>,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -49,6 +70,16 @@ enum Command {
         #[command(subcommand)]
         cmd: area_cli::AreaCommand,
     },
+    /// Decode a METAR into plain English, by ICAO code or from the report text
+    Metar(metar_cli::MetarArgs),
+    /// Check the sector file for runway data problems
+    Sct {
+        #[command(subcommand)]
+        cmd: sct_cli::SctCommand,
+    },
+    /// Re-run a selection captured with `--capture` offline and compare it
+    /// with what was selected then
+    Replay(replay_cli::ReplayArgs),
 }
 
 fn get_target() -> &'static str {
@@ -98,6 +129,8 @@ fn update() -> Result<bool> {
 struct PreparedStartup {
     config: Arc<ESConfig>,
     installed_areas: Vec<area_runtime::InstalledArea>,
+    data_source: Box<dyn DataSource>,
+    capture: Option<PathBuf>,
 }
 
 fn prepare_startup(cli: &Cli) -> Result<PreparedStartup> {
@@ -113,32 +146,53 @@ fn prepare_startup(cli: &Cli) -> Result<PreparedStartup> {
         None => Vec::new(),
     };
     let installed_prefixes = area_runtime::installed_sector_file_prefixes(&installed_areas);
+    // Areas that read their runways from OurAirports need no sector package.
+    let sector_file_required = installed_areas.is_empty()
+        || installed_areas
+            .iter()
+            .any(|area| area.config.runway_source == RunwaySource::SectorFile);
 
     let config = Arc::new(
-        ESConfig::find_euroscope_config_folder(cli.clean_config, &installed_prefixes).ok_or_else(
-            || {
-                anyhow!(
-                    "Could not locate a EuroScope sector file (looked for prefixes: {:?}). \
+        ESConfig::find_euroscope_config_folder(
+            cli.clean_config,
+            &installed_prefixes,
+            sector_file_required,
+        )
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not locate a EuroScope sector file (looked for prefixes: {:?}). \
                      Install an area with `es_runway_selector area install <name>` or set \
                      `euroscope_config_folder` in your config.toml.",
-                    installed_prefixes
-                )
-            },
-        )?,
+                installed_prefixes
+            )
+        })?,
     );
 
     // First-run wizard: tell the user what to install if they haven't yet.
     // Always informational — never blocks the main flow.
     if let Some(dir) = install_dir.as_deref() {
-        match wizard::detect_setup_state(dir, Some(config.get_sector_file_prefix())) {
+        match wizard::detect_setup_state(dir, config.get_sector_file_prefix()) {
             Ok(state) => wizard::print_setup_state(&state),
             Err(e) => warn!(error = ?e, "Setup-state detection failed"),
         }
     }
 
+    let data_source_config = cli
+        .data_source
+        .clone()
+        .unwrap_or_else(|| config.data_source());
+    if data_source_config != DataSourceConfig::Http {
+        info!(data_source = %data_source_config, "Reading live data offline");
+    }
+    let data_source = data_source_config
+        .open()
+        .with_context(|| format!("Opening data source {data_source_config}"))?;
+
     Ok(PreparedStartup {
         config,
         installed_areas,
+        data_source,
+        capture: cli.capture.clone(),
     })
 }
 
@@ -146,35 +200,10 @@ async fn run(prepared: PreparedStartup) -> Result<()> {
     let PreparedStartup {
         config,
         installed_areas,
+        data_source,
+        capture,
     } = prepared;
 
-    // Host-side configuration (METAR feeds, ignore list, defaults) comes from
-    // the area whose sector_file_prefix owns this sector file. Runway
-    // *selection* below runs through every installed area plugin.
-    let active_area =
-        area_runtime::match_area_for_prefix(&installed_areas, config.get_sector_file_prefix());
-    if active_area.is_none() {
-        warn!(
-            sector_file_prefix = config.get_sector_file_prefix(),
-            "No installed area declares a sector_file_prefix that matches; \
-             selections will use defaults only"
-        );
-    }
-
-    // Anything area-derived comes from the active area's area.toml, never
-    // from host config.toml.
-    static EMPTY_IGNORE: std::sync::OnceLock<IndexSet<String>> = std::sync::OnceLock::new();
-    static EMPTY_DEFAULTS: std::sync::OnceLock<IndexMap<String, u8>> = std::sync::OnceLock::new();
-    let ignore_airports = active_area
-        .map(|a| &a.config.ignore_airports)
-        .unwrap_or_else(|| EMPTY_IGNORE.get_or_init(IndexSet::new));
-    let default_runways = active_area
-        .map(|a| &a.config.default_runways)
-        .unwrap_or_else(|| EMPTY_DEFAULTS.get_or_init(IndexMap::new));
-    let metar_urls: Vec<&str> = active_area
-        .map(|a| a.config.metar_urls.iter().map(String::as_str).collect())
-        .unwrap_or_default();
-
     let config_task1 = config.clone();
     let task1 = tokio::spawn(async move {
         let handles = config_task1.run_apps(false).await;
@@ -182,45 +211,175 @@ async fn run(prepared: PreparedStartup) -> Result<()> {
             handle.await.unwrap();
         }
     });
-    let mut airports = Airports::new();
-    let sct_path = config.get_sct_file_path();
-    let mut sct_file = File::open(&sct_path)
-        .with_context(|| format!("Opening sector file {}", sct_path.display()))?;
-    airports
-        .load_airports_from_sector_file(&mut sct_file, ignore_airports)
-        .with_context(|| format!("Parsing sector file {}", sct_path.display()))?;
-    if metar_urls.is_empty() {
-        warn!("Active area declares no METAR URLs; skipping METAR fetch");
-    } else if let Err(e) = airports.add_metars(&metar_urls, ignore_airports).await {
-        warn!(error = ?e, "METAR fetch failed; continuing without METAR-derived selections");
+
+    // Each sector file gets its own `.rwy`, selected with its own area's
+    // configuration. The report combines them; an airport in several
+    // sector files is reported with the selection made for the first. A
+    // failing sector file is skipped; the run fails only if all of them do.
+    let mut combined = Airports::new();
+    let mut selected_any = false;
+    let mut first_error = None;
+    let targets = selection_targets(
+        config.sector_files(),
+        &installed_areas,
+        config.euroscope_config_folder(),
+    );
+    for (sector_file, active_area) in &targets {
+        match select_runways_for_sector_file(
+            sector_file,
+            *active_area,
+            &installed_areas,
+            &*data_source,
+            capture.as_deref(),
+        )
+        .await
+        {
+            Ok(airports) => {
+                combined.absorb(airports);
+                selected_any = true;
+            }
+            Err(e) => {
+                warn!(
+                    sector_file = sector_file.prefix(),
+                    error = ?e,
+                    "Skipping sector file; its runway file is left unchanged"
+                );
+                first_error.get_or_insert(e);
+            }
+        }
     }
-    if let Err(e) = airports.read_atis_and_apply_runways().await {
-        warn!(error = ?e, "ATIS fetch failed; continuing without ATIS-derived selections");
+    if !selected_any && let Some(e) = first_error {
+        return Err(e.context("Selecting runways failed for every sector file"));
     }
 
+    let task2 = tokio::spawn(async move {
+        let handles = config.run_apps(true).await;
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+
+    let tasks = [task1, task2];
+
+    combined.sort();
+    combined
+        .make_runway_report_html()
+        .context("Generating HTML runway report")?;
+
+    for task in tasks {
+        task.await.context("Joining background app-launcher task")?;
+    }
+
+    Ok(())
+}
+
+/// What to select runways for: each sector file with the area that owns it,
+/// then each OurAirports area without a sector file. Those write their
+/// `.rwy` to `output_folder`, or to the area directory when there is none.
+fn selection_targets<'a>(
+    sector_files: &[config::SectorFile],
+    installed_areas: &'a [area_runtime::InstalledArea],
+    output_folder: Option<&Path>,
+) -> Vec<(config::SectorFile, Option<&'a area_runtime::InstalledArea>)> {
+    let mut targets: Vec<_> = sector_files
+        .iter()
+        .map(|sector_file| {
+            let area = area_runtime::match_area_for_prefix(installed_areas, sector_file.prefix());
+            (sector_file.clone(), area)
+        })
+        .collect();
+    for area in installed_areas {
+        let has_sector_file = targets
+            .iter()
+            .any(|(_, owner)| owner.is_some_and(|owner| owner.manifest.name == area.manifest.name));
+        if has_sector_file || !matches!(area.config.runway_source, RunwaySource::OurAirports { .. })
+        {
+            continue;
+        }
+        let folder = output_folder.map_or_else(|| area.area_dir.clone(), Path::to_path_buf);
+        let prefix = area
+            .config
+            .sector_file_prefix
+            .clone()
+            .unwrap_or_else(|| area.manifest.name.clone());
+        targets.push((config::SectorFile::new(folder, prefix), Some(area)));
+    }
+    targets
+}
+
+/// Loads one sector file's airports, selects their runways and writes its
+/// `.rwy` file. With `capture`, everything the selection read is also saved
+/// to that folder as a replay bundle.
+///
+/// Host-side configuration (METAR feeds, ignore list, defaults) comes from
+/// `active_area`, the area that owns this sector file. Runway *selection*
+/// runs through every installed area plugin.
+async fn select_runways_for_sector_file(
+    sector_file: &config::SectorFile,
+    active_area: Option<&area_runtime::InstalledArea>,
+    installed_areas: &[area_runtime::InstalledArea],
+    data_source: &dyn DataSource,
+    capture: Option<&Path>,
+) -> Result<Airports> {
+    if active_area.is_none() {
+        warn!(
+            sector_file_prefix = sector_file.prefix(),
+            "No installed area declares a sector_file_prefix that matches; \
+             selections will use defaults only"
+        );
+    }
+    let area_config = active_area.map(|a| &a.config);
+
+    let runway_data = match active_area.map(|a| (&a.area_dir, &a.config.runway_source)) {
+        Some((
+            area_dir,
+            RunwaySource::OurAirports {
+                airports_csv,
+                runways_csv,
+                ..
+            },
+        )) => {
+            let airports_path = area_dir.join(airports_csv);
+            let runways_path = area_dir.join(runways_csv);
+            RunwayData::read_ourairports(&airports_path, &runways_path).with_context(|| {
+                format!(
+                    "Reading {} and {}",
+                    airports_path.display(),
+                    runways_path.display()
+                )
+            })?
+        }
+        Some((_, RunwaySource::SectorFile)) | None => {
+            let sct_path = sector_file.sct_path();
+            RunwayData::read_sector_file(&sct_path)
+                .with_context(|| format!("Reading sector file {}", sct_path.display()))?
+        }
+    };
+
+    let now_utc = Timestamp::now();
+    let recording_source = RecordingSource::new(data_source);
+    let mut airports = airports_with_live_data(area_config, &runway_data, &recording_source)
+        .await
+        .context("Loading runways")?;
+
     // Hand selection off to the installed area plugins. ATIS-derived runways
     // are already applied host-side; plugins only see the remaining airports.
     // Failures degrade to defaults and are surfaced to the user.
-    let statuses = plugin_runner::run_area_selections(&mut airports, &installed_areas).await;
+    let statuses =
+        plugin_runner::run_area_selections(&mut airports, installed_areas, now_utc).await;
     for status in &statuses {
         if matches!(status.outcome, plugin_runner::AreaRunOutcome::Failed(_)) {
             eprintln!("WARNING: {}", status.user_message());
         }
     }
 
-    airports.apply_default_runways(default_runways);
+    if let Some(config) = area_config {
+        airports.apply_default_runways(&config.default_runways);
+    }
     airports.sort();
-    let rwy_path = config.get_rwy_file_path();
+    let rwy_path = sector_file.rwy_path();
     write_runways_to_rwy_file(&rwy_path, &airports)
         .with_context(|| format!("Writing runway file {}", rwy_path.display()))?;
-    let task2 = tokio::spawn(async move {
-        let handles = config.run_apps(true).await;
-        for handle in handles {
-            handle.await.unwrap();
-        }
-    });
-
-    let tasks = [task1, task2];
 
     let no_runways_in_use = airports.airports_without_runway_config();
     for airport in no_runways_in_use {
@@ -228,15 +387,126 @@ async fn run(prepared: PreparedStartup) -> Result<()> {
             warn!(airport.icao, metar = "No METAR / unparsable metar", ?airport.runways, "No runway selected for:")
         }
     }
-    airports
-        .make_runway_report_html()
-        .context("Generating HTML runway report")?;
+    log_procedures_for_selected_runways(&sector_file.ese_path(), &airports);
+
+    if let Some(capture_dir) = capture {
+        let bundle = Bundle {
+            manifest: BundleManifest {
+                host_version: cargo_crate_version!().to_string(),
+                captured_at: now_utc,
+                sector_file_prefix: sector_file.prefix().to_string(),
+                area: active_area.map(|a| a.manifest.name.clone()),
+            },
+            area_config: area_config.cloned(),
+            runway_data,
+            recording: recording_source.into_recording(),
+            plugins: statuses
+                .into_iter()
+                .filter_map(|status| status.exchange)
+                .collect(),
+            selection: active_runway_lines(&airports),
+        };
+        // A failed capture must not fail the run it was meant to record.
+        match save_bundle(capture_dir, &bundle) {
+            Ok(path) => info!(path = %path.display(), "Captured replay bundle"),
+            Err(e) => warn!(error = ?e, "Could not write replay bundle"),
+        }
+    }
+    Ok(airports)
+}
 
-    for task in tasks {
-        task.await.context("Joining background app-launcher task")?;
+/// Loads the airports in `runway_data` and applies what `data_source` has
+/// for them: METARs and ATIS-derived runways. Runway selection by the area
+/// plugins and the defaults is left to the caller.
+pub(crate) async fn airports_with_live_data(
+    area_config: Option<&AreaConfig>,
+    runway_data: &RunwayData,
+    data_source: &dyn DataSource,
+) -> Result<Airports> {
+    // Anything area-derived comes from the active area's area.toml, never
+    // from host config.toml.
+    static EMPTY_IGNORE: std::sync::OnceLock<IndexSet<String>> = std::sync::OnceLock::new();
+    let ignore_airports = area_config
+        .map(|c| &c.ignore_airports)
+        .unwrap_or_else(|| EMPTY_IGNORE.get_or_init(IndexSet::new));
+    let metar_urls: Vec<&str> = area_config
+        .map(|c| c.metar_urls.iter().map(String::as_str).collect())
+        .unwrap_or_default();
+    let icao_prefixes = match area_config.map(|c| &c.runway_source) {
+        Some(RunwaySource::OurAirports { icao_prefixes, .. }) => icao_prefixes.as_slice(),
+        _ => &[],
+    };
+
+    let mut airports = Airports::new();
+    runway_data.load_into(&mut airports, icao_prefixes, ignore_airports)?;
+    for issue in airports.runway_issues() {
+        warn!(%issue, "Sector file runway issue; wind components may be off");
+    }
+    if metar_urls.is_empty() {
+        warn!("Active area declares no METAR URLs; skipping METAR fetch");
+    } else if let Err(e) = airports
+        .add_metars(data_source, &metar_urls, ignore_airports)
+        .await
+    {
+        warn!(error = ?e, "METAR fetch failed; continuing without METAR-derived selections");
     }
+    // The area's own ATIS phrases extend the built-in grammar; a broken
+    // phrase falls back to the built-in grammar alone.
+    let atis_grammar = match area_config.map(|c| AtisGrammar::extended(&c.atis)) {
+        Some(Ok(grammar)) => Cow::Owned(grammar),
+        Some(Err(e)) => {
+            warn!(error = ?e, "Area ATIS phrases are invalid; using the built-in grammar");
+            Cow::Borrowed(AtisGrammar::builtin())
+        }
+        None => Cow::Borrowed(AtisGrammar::builtin()),
+    };
+    if let Err(e) = airports
+        .read_atis_and_apply_runways(data_source, &atis_grammar)
+        .await
+    {
+        warn!(error = ?e, "ATIS fetch failed; continuing without ATIS-derived selections");
+    }
+    if let Err(e) = airports.read_online_controllers(data_source).await {
+        warn!(error = ?e, "Controller fetch failed; the report will not list online positions");
+    }
+    Ok(airports)
+}
 
-    Ok(())
+/// Writes `bundle` into `dir` under its own file name, and returns the path.
+fn save_bundle(dir: &Path, bundle: &Bundle) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Creating {}", dir.display()))?;
+    let path = dir.join(bundle.file_name());
+    bundle
+        .write(&path)
+        .with_context(|| format!("Writing {}", path.display()))?;
+    Ok(path)
+}
+
+/// Lists the SIDs and STARs for each selected runway and warns about
+/// selected runways the `.ese` has no procedures for. Sector packages
+/// without an `.ese` file are skipped silently.
+fn log_procedures_for_selected_runways(ese_path: &Path, airports: &Airports) {
+    let Ok(mut ese_file) = File::open(ese_path) else {
+        return;
+    };
+    let ese = match ese::load_ese_file(&mut ese_file) {
+        Ok(ese) => ese,
+        Err(e) => {
+            warn!(error = ?e, path = %ese_path.display(), "Could not parse sector extension file");
+            return;
+        }
+    };
+    for procedures in ese.selected_runway_procedures(airports) {
+        if procedures.is_missing() {
+            warn!(
+                airport = procedures.icao,
+                runway = procedures.runway,
+                "No SIDs or STARs for selected runway; check for a typo or a closed runway"
+            );
+        } else if procedures.airport_has_procedures {
+            info!("{}", ese::describe(&procedures));
+        }
+    }
 }
 
 fn setup_logging(cli: &Cli) -> std::io::Result<(PathBuf, WorkerGuard)> {
@@ -367,6 +637,15 @@ fn main() -> Result<()> {
         Some(Command::Area { cmd }) => runtime
             .block_on(area_cli::run_area_command(cmd))
             .context("Running area subcommand")?,
+        Some(Command::Metar(args)) => runtime
+            .block_on(metar_cli::run_metar_command(args))
+            .context("Running metar subcommand")?,
+        Some(Command::Sct { cmd }) => {
+            sct_cli::run_sct_command(cmd).context("Running sct subcommand")?
+        }
+        Some(Command::Replay(args)) => runtime
+            .block_on(replay_cli::run_replay_command(args))
+            .context("Running replay subcommand")?,
         None => {
             // Config discovery may open a folder-picker dialog; run it before
             // entering the runtime so blocking UI cannot freeze the reactor
@@ -379,3 +658,107 @@ fn main() -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use runway_selector_area_config::{AreaManifest, Runtime};
+    use runway_selector_core::data_source::{AtisText, FixtureSource, Recording};
+    use semver::Version;
+
+    use super::*;
+    use crate::{area_runtime::InstalledArea, config::SectorFile};
+
+    const AIRPORTS_CSV: &str = concat!(
+        "\"id\",\"ident\",\"type\",\"name\",\"latitude_deg\",\"longitude_deg\",\"elevation_ft\"\n",
+        "2502,\"ENGM\",\"large_airport\",\"Oslo Airport, Gardermoen\",60.193901,11.1004,681\n",
+    );
+    const RUNWAYS_CSV: &str = concat!(
+        "\"id\",\"airport_ref\",\"airport_ident\",\"closed\",\"le_ident\",\"le_latitude_deg\",\"le_longitude_deg\",\"le_heading_degT\",\"he_ident\",\"he_latitude_deg\",\"he_longitude_deg\",\"he_heading_degT\"\n",
+        "235400,2502,\"ENGM\",0,\"01L\",60.185,11.073744,16,\"19R\",60.216067,11.091664,196\n",
+    );
+
+    fn area(name: &str, area_dir: PathBuf, runway_source: RunwaySource) -> InstalledArea {
+        InstalledArea {
+            area_dir,
+            manifest: AreaManifest {
+                name: name.into(),
+                version: Version::new(0, 1, 0),
+                display_name: name.into(),
+                description: None,
+                runtime: Runtime::Rust,
+                entry: name.into(),
+                supported_icaos: vec![],
+                min_core_version: None,
+            },
+            config: AreaConfig {
+                sector_file_prefix: Some(name.to_ascii_uppercase()),
+                runway_source,
+                ..AreaConfig::default()
+            },
+        }
+    }
+
+    fn ourairports() -> RunwaySource {
+        RunwaySource::OurAirports {
+            airports_csv: "airports.csv".into(),
+            runways_csv: "runways.csv".into(),
+            icao_prefixes: vec!["EN".to_string()],
+        }
+    }
+
+    #[test]
+    fn ourairports_areas_without_a_sector_file_are_selected_too() {
+        let areas = [
+            area("enor", "/areas/enor".into(), ourairports()),
+            area("esaa", "/areas/esaa".into(), RunwaySource::SectorFile),
+            area("ekdk", "/areas/ekdk".into(), ourairports()),
+        ];
+        let sector_files = [SectorFile::new("/es".into(), "ESAA-Sweden".into())];
+
+        let targets = selection_targets(&sector_files, &areas, None);
+        let described: Vec<_> = targets
+            .iter()
+            .map(|(file, area)| (file.rwy_path(), area.map(|a| a.manifest.name.as_str())))
+            .collect();
+        assert_eq!(
+            described,
+            [
+                (PathBuf::from("/es/ESAA-Sweden.rwy"), Some("esaa")),
+                (PathBuf::from("/areas/enor/ENOR.rwy"), Some("enor")),
+                (PathBuf::from("/areas/ekdk/EKDK.rwy"), Some("ekdk")),
+            ]
+        );
+
+        let targets = selection_targets(&[], &areas, Some(Path::new("/es")));
+        assert_eq!(targets[0].0.rwy_path(), PathBuf::from("/es/ENOR.rwy"));
+    }
+
+    #[tokio::test]
+    async fn selects_from_ourairports_data_without_a_sector_file() {
+        let area_dir = tempfile::tempdir().unwrap();
+        std::fs::write(area_dir.path().join("airports.csv"), AIRPORTS_CSV).unwrap();
+        std::fs::write(area_dir.path().join("runways.csv"), RUNWAYS_CSV).unwrap();
+        let areas = [area("enor", area_dir.path().to_path_buf(), ourairports())];
+        let source = FixtureSource::new(Recording {
+            atis: vec![AtisText {
+                callsign: "ENGM_ATIS".to_string(),
+                frequency: "126.130".to_string(),
+                lines: vec!["RUNWAY 19R IN USE".to_string()],
+            }],
+            ..Recording::default()
+        });
+
+        let targets = selection_targets(&[], &areas, None);
+        let [(sector_file, active_area)] = targets.as_slice() else {
+            panic!("expected one target, got {}", targets.len());
+        };
+        let airports =
+            select_runways_for_sector_file(sector_file, *active_area, &areas, &source, None)
+                .await
+                .unwrap();
+
+        assert_eq!(airports.airports.keys().collect::<Vec<_>>(), ["ENGM"]);
+        let rwy = std::fs::read_to_string(area_dir.path().join("ENOR.rwy")).unwrap();
+        assert_eq!(rwy, "ACTIVE_RUNWAY:ENGM:19R:1\nACTIVE_RUNWAY:ENGM:19R:0\n");
+    }
+}
@@ -0,0 +1,86 @@
+//! `es_runway_selector metar …`: decodes a METAR into plain English. The
+//! argument is either an ICAO code, fetched from the VATSIM METAR feed, or
+//! the text of a report.
+
+use anyhow::{Context, Result, anyhow};
+use indexmap::IndexSet;
+use metar_decoder::{metar::Metar, plain_language::UnitSystem};
+use runway_selector_core::{data_source::HttpSource, metar::get_metars};
+use tracing::warn;
+
+const VATSIM_METAR_URL: &str = "https://metar.vatsim.net";
+
+#[derive(clap::Args, Debug)]
+pub struct MetarArgs {
+    /// ICAO code to fetch from VATSIM, or the METAR text itself.
+    report: String,
+    #[clap(long)]
+    /// Show visibility, temperature and pressure in imperial units.
+    imperial: bool,
+}
+
+pub async fn run_metar_command(args: MetarArgs) -> Result<()> {
+    let metar = if is_icao(&args.report) {
+        fetch_metar(&args.report.to_ascii_uppercase()).await?
+    } else {
+        parse_metar(args.report.trim())?
+    };
+    let units = if args.imperial {
+        UnitSystem::Imperial
+    } else {
+        UnitSystem::Metric
+    };
+
+    println!("{}", metar.raw);
+    println!();
+    for sentence in metar.plain_language(units) {
+        println!("{sentence}");
+    }
+    Ok(())
+}
+
+fn is_icao(report: &str) -> bool {
+    report.len() == 4 && report.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+async fn fetch_metar(icao: &str) -> Result<Metar> {
+    let url = format!("{VATSIM_METAR_URL}/{icao}");
+    get_metars(&HttpSource::new(), &[&url], &IndexSet::new())
+        .await
+        .with_context(|| format!("Fetching METAR for {icao}"))?
+        .into_iter()
+        .find(|metar| metar.icao == icao)
+        .ok_or_else(|| anyhow!("VATSIM has no METAR for {icao}"))
+}
+
+fn parse_metar(report: &str) -> Result<Metar> {
+    let parsed = Metar::parse_lenient(report).context("Parsing METAR")?;
+    if !parsed.unparsed.is_empty() {
+        let skipped = parsed
+            .unparsed
+            .iter()
+            .map(|span| &span.text)
+            .collect::<Vec<_>>();
+        warn!(?skipped, "Skipped unknown METAR groups");
+    }
+    Ok(parsed.metar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icao_arguments_are_fetched_and_reports_are_parsed() {
+        assert!(is_icao("ENGM"));
+        assert!(is_icao("engm"));
+        assert!(!is_icao("ENGM 161220Z 01008KT CAVOK 08/04 Q1026"));
+        assert!(!is_icao("EN-M"));
+    }
+
+    #[test]
+    fn parses_report_text() {
+        let metar = parse_metar("ENGM 161220Z 01008KT CAVOK 08/04 Q1026").unwrap();
+        assert_eq!(metar.icao, "ENGM");
+    }
+}
@@ -14,14 +14,19 @@
 //! A missing, crashed, or erroring plugin never breaks the run: the failure
 //! is logged, reported in the returned [`AreaRunStatus`], and the host falls
 //! back to built-in defaults for that area's airports.
+//!
+//! Each status keeps the request and response as a [`PluginExchange`] for
+//! capture bundles; a replay can apply a recorded response with
+//! [`apply_recorded_exchange`] when its area is not installed.
 
 use std::collections::HashSet;
 
 use indexmap::IndexMap;
 use jiff::{Timestamp, Zoned, tz::TimeZone};
-use runway_plugin_api::RunwaySelectionsRequest;
+use runway_plugin_api::{RunwaySelectionsRequest, RunwaySelectionsResponse};
 use runway_selector_core::{
     Airports, RunwayInUseSource,
+    bundle::PluginExchange,
     plugin_convert::{airport_to_request, runway_use_from_wire, selection_source_from_wire},
     runway::RunwayUse,
 };
@@ -43,6 +48,9 @@ fn host_version() -> Version {
 pub struct AreaRunStatus {
     pub area_name: String,
     pub outcome: AreaRunOutcome,
+    /// What was sent to the plugin and what came back; `None` when nothing
+    /// was sent.
+    pub exchange: Option<PluginExchange>,
 }
 
 pub enum AreaRunOutcome {
@@ -74,13 +82,14 @@ impl AreaRunStatus {
 
 /// Run runway selection through every installed area plugin.
 ///
-/// Returns one status per area so the caller can surface plugin failures to
-/// the user. Never returns an error: plugin problems degrade to defaults.
+/// Plugins are asked for selections at `now_utc`. Returns one status per
+/// area so the caller can surface plugin failures to the user. Never returns
+/// an error: plugin problems degrade to defaults.
 pub async fn run_area_selections(
     airports: &mut Airports,
     areas: &[InstalledArea],
+    now_utc: Timestamp,
 ) -> Vec<AreaRunStatus> {
-    let now_utc = Timestamp::now();
     let ownership = assign_airport_ownership(areas);
 
     let mut statuses = Vec::with_capacity(areas.len());
@@ -145,67 +154,102 @@ async fn run_single_area(
         return AreaRunStatus {
             area_name: name,
             outcome: AreaRunOutcome::NothingToDo,
+            exchange: None,
         };
     }
 
-    match drive_plugin(airports, area, &eligible, now_utc).await {
-        Ok((handled, deferred)) => AreaRunStatus {
-            area_name: name,
-            outcome: AreaRunOutcome::Ok { handled, deferred },
-        },
+    let request = RunwaySelectionsRequest {
+        timestamp_utc: format_rfc3339_utc(now_utc),
+        area_timezone: area
+            .config
+            .time_zone
+            .clone()
+            .unwrap_or_else(|| "UTC".to_string()),
+        airports: eligible
+            .iter()
+            .filter_map(|icao| airports.airports.get(icao))
+            .map(airport_to_request)
+            .collect(),
+    };
+    let mut exchange = PluginExchange {
+        area: name.clone(),
+        version: area.manifest.version.to_string(),
+        request,
+        response: None,
+        error: None,
+    };
+
+    let outcome = match drive_plugin(area, &exchange.request).await {
+        Ok(response) => {
+            exchange.response = Some(response.clone());
+            let (handled, deferred) = apply_results(airports, &eligible, response.results, &name);
+            AreaRunOutcome::Ok { handled, deferred }
+        }
         Err(e) => {
             warn!(area = %name, error = %e, "Area plugin failed; falling back to defaults");
-            AreaRunStatus {
-                area_name: name,
-                outcome: AreaRunOutcome::Failed(e),
-            }
+            exchange.error = Some(e.clone());
+            AreaRunOutcome::Failed(e)
         }
+    };
+    AreaRunStatus {
+        area_name: name,
+        outcome,
+        exchange: Some(exchange),
     }
 }
 
 async fn drive_plugin(
-    airports: &mut Airports,
     area: &InstalledArea,
-    eligible: &[String],
-    now_utc: Timestamp,
-) -> Result<(usize, usize), String> {
+    request: &RunwaySelectionsRequest,
+) -> Result<RunwaySelectionsResponse, String> {
     info!(
         name = %area.manifest.name,
         version = %area.manifest.version,
-        airports = eligible.len(),
+        airports = request.airports.len(),
         "Spawning area plugin"
     );
     let handle = spawn_plugin(&area.manifest, &area.area_dir, &host_version())
         .await
         .map_err(|e| e.to_string())?;
 
-    let request = RunwaySelectionsRequest {
-        timestamp_utc: format_rfc3339_utc(now_utc),
-        area_timezone: area
-            .config
-            .time_zone
-            .clone()
-            .unwrap_or_else(|| "UTC".to_string()),
-        airports: eligible
-            .iter()
-            .filter_map(|icao| airports.airports.get(icao))
-            .map(airport_to_request)
-            .collect(),
-    };
-
-    let result = handle.select_runways(&request).await;
+    let result = handle.select_runways(request).await;
 
     if let Err(e) = handle.shutdown().await {
         warn!(area = %area.manifest.name, error = %e, "Plugin shutdown returned an error");
     }
 
-    let response = result.map_err(|e| e.to_string())?;
-    Ok(apply_results(
-        airports,
-        eligible,
-        response.results,
-        &area.manifest.name,
-    ))
+    result.map_err(|e| e.to_string())
+}
+
+/// Apply the response of a recorded exchange as if its plugin had just
+/// answered, for replaying a bundle whose area is not installed.
+pub fn apply_recorded_exchange(
+    airports: &mut Airports,
+    exchange: &PluginExchange,
+) -> AreaRunStatus {
+    let outcome = match &exchange.response {
+        Some(response) => {
+            let eligible: Vec<String> = exchange
+                .request
+                .airports
+                .iter()
+                .map(|airport| airport.icao.clone())
+                .collect();
+            let (handled, deferred) = apply_results(
+                airports,
+                &eligible,
+                response.results.clone(),
+                &exchange.area,
+            );
+            AreaRunOutcome::Ok { handled, deferred }
+        }
+        None => AreaRunOutcome::Failed(exchange.error.clone().unwrap_or_default()),
+    };
+    AreaRunStatus {
+        area_name: exchange.area.clone(),
+        outcome,
+        exchange: Some(exchange.clone()),
+    }
 }
 
 /// Write plugin selections back onto `airports`. Returns
@@ -266,7 +310,9 @@ fn format_rfc3339_utc(ts: Timestamp) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use runway_plugin_api::{AirportSelectionResult, RunwayUseEntry, SelectionSource};
     use runway_selector_area_config::{AreaConfig, AreaManifest, Runtime};
+    use runway_selector_core::Airport;
     use std::path::PathBuf;
 
     fn area(name: &str, icaos: &[&str]) -> InstalledArea {
@@ -298,6 +344,50 @@ mod tests {
         assert_eq!(ownership.get("ENZV").map(String::as_str), Some("enor"));
     }
 
+    #[test]
+    fn recorded_exchange_applies_only_to_the_airports_it_asked_about() {
+        let mut airports = Airports::new();
+        airports.add_airport(Airport::new("ENGM".into()));
+        airports.add_airport(Airport::new("ENZV".into()));
+        let result = |icao: &str, runway: &str| AirportSelectionResult {
+            icao: icao.into(),
+            handled: true,
+            source: SelectionSource::Metar,
+            runway_uses: vec![RunwayUseEntry {
+                runway: runway.into(),
+                use_: runway_plugin_api::RunwayUse::Both,
+            }],
+            tags: Vec::new(),
+        };
+        let exchange = PluginExchange {
+            area: "enor".into(),
+            version: "0.1.0".into(),
+            request: RunwaySelectionsRequest {
+                timestamp_utc: "2026-10-17T14:20:00Z".into(),
+                area_timezone: "Europe/Oslo".into(),
+                airports: vec![airport_to_request(&airports.airports["ENGM"])],
+            },
+            response: Some(RunwaySelectionsResponse {
+                results: vec![result("ENGM", "19R"), result("ENZV", "18")],
+            }),
+            error: None,
+        };
+
+        let status = apply_recorded_exchange(&mut airports, &exchange);
+        assert!(matches!(
+            status.outcome,
+            AreaRunOutcome::Ok {
+                handled: 1,
+                deferred: 0
+            }
+        ));
+        assert_eq!(
+            airports.airports["ENGM"].runways_in_use[&RunwayInUseSource::Metar]["19R"],
+            RunwayUse::Both
+        );
+        assert!(airports.airports["ENZV"].runways_in_use.is_empty());
+    }
+
     #[test]
     fn timestamp_formats_as_rfc3339_utc() {
         let ts: Timestamp = "2026-05-31T21:00:00Z".parse().unwrap();
@@ -0,0 +1,90 @@
+//! `es_runway_selector replay <bundle>`: re-runs a selection captured with
+//! `--capture` offline, from the bundle's runways, METARs, ATISes, area
+//! configuration and time, and compares it with what was selected then.
+//!
+//! Installed area plugins are asked again, so a fixed plugin can be checked
+//! against the run it got wrong. Areas that are no longer installed answer
+//! with their recorded response.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use runway_selector_core::{
+    bundle::{Bundle, diff_selections},
+    data_source::FixtureSource,
+    output::active_runway_lines,
+};
+
+use crate::{area_cli, plugin_runner};
+
+#[derive(clap::Args, Debug)]
+pub struct ReplayArgs {
+    /// A bundle written by `--capture`.
+    bundle: PathBuf,
+}
+
+pub async fn run_replay_command(args: ReplayArgs) -> Result<()> {
+    let bundle = Bundle::read(&args.bundle)
+        .with_context(|| format!("Reading replay bundle {}", args.bundle.display()))?;
+    let manifest = &bundle.manifest;
+    println!(
+        "Replaying {} as selected at {} by version {}",
+        manifest.sector_file_prefix, manifest.captured_at, manifest.host_version
+    );
+
+    let installed_areas = area_cli::load_installed_areas()?;
+    let data_source = FixtureSource::new(bundle.recording.clone());
+    let mut airports = crate::airports_with_live_data(
+        bundle.area_config.as_ref(),
+        &bundle.runway_data,
+        &data_source,
+    )
+    .await?;
+
+    let mut statuses =
+        plugin_runner::run_area_selections(&mut airports, &installed_areas, manifest.captured_at)
+            .await;
+    for exchange in &bundle.plugins {
+        match installed_areas
+            .iter()
+            .find(|area| area.manifest.name == exchange.area)
+        {
+            Some(area) if area.manifest.version.to_string() != exchange.version => println!(
+                "area {}: recorded with version {}, replayed with {}",
+                exchange.area, exchange.version, area.manifest.version
+            ),
+            Some(_) => {}
+            None => {
+                println!(
+                    "area {}: not installed; using its recorded response",
+                    exchange.area
+                );
+                statuses.push(plugin_runner::apply_recorded_exchange(
+                    &mut airports,
+                    exchange,
+                ));
+            }
+        }
+    }
+    for status in &statuses {
+        println!("{}", status.user_message());
+    }
+
+    if let Some(config) = &bundle.area_config {
+        airports.apply_default_runways(&config.default_runways);
+    }
+    airports.sort();
+
+    let diffs = diff_selections(&bundle.selection, &active_runway_lines(&airports));
+    if diffs.is_empty() {
+        println!("The replay selected the same runways as the recorded run");
+        return Ok(());
+    }
+    for diff in &diffs {
+        println!("  {diff}");
+    }
+    match diffs.len() {
+        1 => bail!("1 airport selected differently"),
+        count => bail!("{count} airports selected differently"),
+    }
+}
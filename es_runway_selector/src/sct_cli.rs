@@ -0,0 +1,98 @@
+//! `es_runway_selector sct …`: diagnostics for the sector file the runway
+//! selector reads.
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Subcommand;
+use runway_selector_core::Airports;
+
+use crate::{
+    area_cli,
+    area_runtime::{self, InstalledArea},
+    config::{ESConfig, SectorFile},
+};
+
+#[derive(Debug, Subcommand)]
+pub enum SctCommand {
+    /// Check the sector file's runways for placeholder headings, headings
+    /// that disagree with each other or the identifier, duplicates and
+    /// zero-length runways. Fails when any are found.
+    Check {
+        /// The `.sct` file to check. Defaults to every sector file the
+        /// runway selector would read.
+        path: Option<PathBuf>,
+    },
+}
+
+/// Runs synchronously: locating the default sector file may open a
+/// folder-picker dialog.
+pub fn run_sct_command(cmd: SctCommand) -> Result<()> {
+    match cmd {
+        SctCommand::Check { path } => check(path),
+    }
+}
+
+fn check(path: Option<PathBuf>) -> Result<()> {
+    let installed_areas = area_cli::load_installed_areas()?;
+    let paths = match path {
+        Some(path) => vec![path],
+        None => {
+            let prefixes = area_runtime::installed_sector_file_prefixes(&installed_areas);
+            ESConfig::find_euroscope_config_folder(false, &prefixes, true)
+                .ok_or_else(|| anyhow!("Could not locate a EuroScope sector file"))?
+                .sector_files()
+                .iter()
+                .map(SectorFile::sct_path)
+                .collect()
+        }
+    };
+
+    let mut issue_count = 0;
+    for path in &paths {
+        issue_count += check_sector_file(path, &installed_areas)?;
+    }
+    match issue_count {
+        0 => {
+            println!("No runway issues found");
+            Ok(())
+        }
+        1 => bail!("1 runway issue found"),
+        count => bail!("{count} runway issues found"),
+    }
+}
+
+/// Prints the runway issues in one sector file and returns how many there
+/// were.
+fn check_sector_file(path: &Path, installed_areas: &[InstalledArea]) -> Result<usize> {
+    // Airports the owning area ignores are left out, as in a normal run.
+    let prefix = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ignored = area_runtime::match_area_for_prefix(installed_areas, &prefix)
+        .map(|area| area.config.ignore_airports.clone())
+        .unwrap_or_default();
+
+    let mut airports = Airports::new();
+    let mut sct_file =
+        File::open(path).with_context(|| format!("Opening sector file {}", path.display()))?;
+    airports
+        .load_airports_from_sector_file(&mut sct_file, &ignored)
+        .with_context(|| format!("Parsing sector file {}", path.display()))?;
+
+    let runways: usize = airports.airports.values().map(|a| a.runways.len()).sum();
+    println!(
+        "{}: {} airports, {runways} runways",
+        path.display(),
+        airports.airports.len()
+    );
+    let issues = airports.runway_issues();
+    for issue in &issues {
+        println!("  {issue}");
+    }
+    Ok(issues.len())
+}
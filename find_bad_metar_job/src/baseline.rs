@@ -0,0 +1,150 @@
+//! Stored decoder outcomes for a corpus, and what changed since.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::failures::{FailureClass, Outcome};
+
+/// The outcome of every report in a corpus, keyed by report.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub reports: BTreeMap<String, Outcome>,
+}
+
+impl Baseline {
+    pub fn read(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_reader(io::BufReader::new(File::open(
+            path,
+        )?))?)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    pub fn failure_classes(&self) -> BTreeSet<&FailureClass> {
+        self.reports
+            .values()
+            .filter_map(|outcome| match outcome {
+                Outcome::Failed(class) => Some(class),
+                Outcome::Parsed(_) => None,
+            })
+            .collect()
+    }
+
+    /// Compares `current` outcomes with the baseline. Reports the baseline
+    /// does not know are only counted as new.
+    pub fn compare<'a>(
+        &'a self,
+        current: impl IntoIterator<Item = (&'a str, &'a Outcome)>,
+    ) -> Comparison<'a> {
+        let mut comparison = Comparison::default();
+        for (report, now) in current {
+            let Some(before) = self.reports.get(report) else {
+                comparison.unknown += 1;
+                continue;
+            };
+            match (before, now) {
+                (Outcome::Parsed(_), Outcome::Failed(class)) => {
+                    comparison.newly_failing.push((report, class));
+                }
+                (Outcome::Parsed(before), Outcome::Parsed(now)) if before != now => {
+                    comparison.decoded_differently.push((report, before, now));
+                }
+                (Outcome::Failed(_), Outcome::Parsed(_)) => comparison.fixed.push(report),
+                (Outcome::Failed(before), Outcome::Failed(now)) if before != now => {
+                    comparison.failing_differently.push((report, before, now));
+                }
+                _ => {}
+            }
+        }
+        comparison
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Comparison<'a> {
+    /// Reports that decoded in the baseline and fail now.
+    pub newly_failing: Vec<(&'a str, &'a FailureClass)>,
+    /// Reports whose canonical text changed: `(report, before, now)`.
+    pub decoded_differently: Vec<(&'a str, &'a str, &'a str)>,
+    pub fixed: Vec<&'a str>,
+    /// Reports that still fail, but somewhere else.
+    pub failing_differently: Vec<(&'a str, &'a FailureClass, &'a FailureClass)>,
+    /// Reports missing from the baseline.
+    pub unknown: usize,
+}
+
+impl Comparison<'_> {
+    /// Changes that need a look before the baseline is rewritten: reports
+    /// that stopped decoding or now decode to something else.
+    pub fn regressions(&self) -> usize {
+        self.newly_failing.len() + self.decoded_differently.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failures::outcome;
+
+    #[test]
+    fn compare_sorts_changes() {
+        let reports = [
+            "ENGM 161220Z 01008KT CAVOK 08/04 Q1026",
+            "ENBR 161220Z 27012KT CAVOK 12/10 Q0998",
+            "ENZV 161220Z 27012KT CAVOK 12/10 0998",
+            "ENVA 161220Z 27012KT CAVOK 12/10",
+        ];
+        let now: Vec<_> = reports.iter().map(|report| outcome(report)).collect();
+        let mut baseline = Baseline::default();
+        for (report, outcome) in reports.iter().zip(&now) {
+            baseline.reports.insert(report.to_string(), outcome.clone());
+        }
+        let mut current: Vec<_> = reports.iter().copied().zip(&now).collect();
+        let class = FailureClass {
+            expected: "pressure".to_string(),
+            token: "Qnnnn".to_string(),
+        };
+        let regressed = Outcome::Failed(class.clone());
+        let fixed = Outcome::Parsed("ENZV 161220Z 27012KT CAVOK 12/10 Q0998".to_string());
+        let redecoded = Outcome::Parsed("ENGM 161220Z 01008KT CAVOK 08/04 Q1025".to_string());
+        current[0].1 = &redecoded;
+        current[1].1 = &regressed;
+        current[2].1 = &fixed;
+        current.push(("ENAL 161220Z 1013", &regressed));
+
+        let comparison = baseline.compare(current);
+        assert_eq!(comparison.newly_failing, [(reports[1], &class)]);
+        assert_eq!(comparison.decoded_differently.len(), 1);
+        assert_eq!(comparison.fixed, [reports[2]]);
+        assert!(comparison.failing_differently.is_empty());
+        assert_eq!(comparison.unknown, 1);
+        assert_eq!(comparison.regressions(), 2);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let mut baseline = Baseline::default();
+        for report in [
+            "ENGM 161220Z 01008KT CAVOK 08/04 Q1026",
+            "ENGM 161220Z 01008KT CAVOK 08/04 1013",
+        ] {
+            baseline.reports.insert(report.to_string(), outcome(report));
+        }
+        baseline.write(&path).unwrap();
+        assert_eq!(Baseline::read(&path).unwrap(), baseline);
+        assert_eq!(baseline.failure_classes().len(), 1);
+    }
+}
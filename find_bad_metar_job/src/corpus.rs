@@ -0,0 +1,149 @@
+//! Reports to check: corpus files, directories of them, or the VATSIM feed.
+
+use std::{
+    fs::{self, File},
+    io,
+    path::Path,
+    sync::LazyLock,
+};
+
+use futures::future::try_join_all;
+use indexmap::{IndexMap, IndexSet};
+use walkdir::WalkDir;
+
+/// Stations whose reports are not worth decoding.
+static IGNORE_AIRPORTS: LazyLock<IndexSet<&str>> = LazyLock::new(|| IndexSet::from(["EQYS"]));
+
+pub const VATSIM_URLS: [&str; 2] = ["https://metar.vatsim.net/E", "https://metar.vatsim.net/L"];
+
+/// Distinct reports in the order they were first seen, each with the place
+/// it was first seen (`path:line`, or the URL it was fetched from).
+#[derive(Debug, Default)]
+pub struct Corpus {
+    reports: IndexMap<String, String>,
+}
+
+impl Corpus {
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.reports
+            .iter()
+            .map(|(report, origin)| (report.as_str(), origin.as_str()))
+    }
+
+    pub fn reports(&self) -> impl Iterator<Item = &str> {
+        self.reports.keys().map(String::as_str)
+    }
+
+    /// Adds one report per line of `text`. Blank lines and ignored stations
+    /// are skipped.
+    pub fn add_text(&mut self, text: &str, origin: &str) {
+        for (number, line) in text.lines().enumerate() {
+            self.add(line, || format!("{origin}:{}", number + 1));
+        }
+    }
+
+    /// Adds a file, or every file below a directory. `.json` files are read
+    /// as an array of reports, the format `fetch` writes failures in; any
+    /// other file holds one report per line.
+    pub fn add_path(&mut self, path: &Path) -> io::Result<()> {
+        if !path.is_dir() {
+            return self.add_file(path);
+        }
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            let entry = entry?;
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if entry.file_type().is_file() && !hidden {
+                self.add_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_file(&mut self, path: &Path) -> io::Result<()> {
+        let origin = path.display().to_string();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            let reports: Vec<String> = serde_json::from_reader(File::open(path)?)?;
+            for (index, report) in reports.iter().enumerate() {
+                self.add(report, || format!("{origin}[{index}]"));
+            }
+        } else {
+            self.add_text(&fs::read_to_string(path)?, &origin);
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, report: &str, origin: impl FnOnce() -> String) {
+        let report = report.trim();
+        let station = report.get(..4).unwrap_or(report);
+        if report.is_empty() || IGNORE_AIRPORTS.contains(station) {
+            return;
+        }
+        if !self.reports.contains_key(report) {
+            self.reports.insert(report.to_string(), origin());
+        }
+    }
+}
+
+/// The current reports for stations with ICAO prefixes E and L.
+pub async fn fetch_vatsim() -> reqwest::Result<Corpus> {
+    let pages = try_join_all(VATSIM_URLS.iter().map(
+        async |&url| -> reqwest::Result<(&str, String)> {
+            Ok((url, reqwest::get(url).await?.text().await?))
+        },
+    ))
+    .await?;
+    let mut corpus = Corpus::default();
+    for (url, page) in pages {
+        corpus.add_text(&page, url);
+    }
+    Ok(corpus)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn reads_directories_and_json_once_per_report() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("a.txt"),
+            "ENGM 161220Z 01008KT CAVOK 08/04 Q1026\n\nEQYS 161220Z NIL\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(
+            dir.path().join("nested/failed.json"),
+            r#"["ENGM 161220Z 01008KT CAVOK 08/04 Q1026", "ENBR 161220Z 1013"]"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join(".hidden"), "ENZV 161220Z 1013").unwrap();
+
+        let mut corpus = Corpus::default();
+        corpus.add_path(dir.path()).unwrap();
+        let origins: Vec<_> = corpus
+            .iter()
+            .map(|(report, origin)| (report, origin.strip_prefix(&*dir.path().to_string_lossy())))
+            .collect();
+        assert_eq!(
+            origins,
+            [
+                ("ENGM 161220Z 01008KT CAVOK 08/04 Q1026", Some("/a.txt:1")),
+                ("ENBR 161220Z 1013", Some("/nested/failed.json[1]")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Decoding reports, grouping the failures and shrinking them to examples
+//! small enough to turn into unit tests.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use metar_decoder::{error::MetarParseError, metar::MetarReport};
+use serde::{Deserialize, Serialize};
+
+/// What the decoder made of one report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// The canonical text of the decoded report, which changes whenever the
+    /// decoder reads the report differently.
+    Parsed(String),
+    Failed(FailureClass),
+}
+
+/// Failures that stopped at the same kind of group: what the decoder
+/// expected, and the first unparsed token with its digits replaced by `n`,
+/// so `1013` and `0998` both fall into `pressure at 'nnnn'`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FailureClass {
+    pub expected: String,
+    pub token: String,
+}
+
+impl FailureClass {
+    pub fn of(error: &MetarParseError) -> Self {
+        FailureClass {
+            expected: error.expected.to_string(),
+            token: error
+                .token()
+                .chars()
+                .map(|c| if c.is_ascii_digit() { 'n' } else { c })
+                .collect(),
+        }
+    }
+
+    /// A snake_case name for a test covering this class.
+    fn test_name(&self) -> String {
+        let token = match self.token.as_str() {
+            "" => "end_of_report",
+            token => token,
+        };
+        format!("{}_{token}", self.expected)
+            .to_ascii_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .join("_")
+    }
+}
+
+impl Display for FailureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.token.as_str() {
+            "" => write!(f, "{} expected at end of report", self.expected),
+            token => write!(f, "{} expected at '{token}'", self.expected),
+        }
+    }
+}
+
+pub fn decode(report: &str) -> Result<Outcome, MetarParseError> {
+    MetarReport::from_str(report).map(|decoded| {
+        Outcome::Parsed(match decoded {
+            MetarReport::Observation(metar) => metar.to_string(),
+            MetarReport::Nil(nil) => nil.to_string(),
+        })
+    })
+}
+
+pub fn outcome(report: &str) -> Outcome {
+    decode(report).unwrap_or_else(|error| Outcome::Failed(FailureClass::of(&error)))
+}
+
+/// Failing reports grouped by class, in corpus order within each class.
+pub fn group<'a>(
+    outcomes: impl IntoIterator<Item = (&'a str, &'a Outcome)>,
+) -> BTreeMap<&'a FailureClass, Vec<&'a str>> {
+    let mut classes: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (report, outcome) in outcomes {
+        if let Outcome::Failed(class) = outcome {
+            classes.entry(class).or_default().push(report);
+        }
+    }
+    classes
+}
+
+/// Drops whole groups from `report` for as long as what is left still fails
+/// in `class`. The first group is kept so the example still names a station.
+pub fn minimise(report: &str, class: &FailureClass) -> String {
+    let mut tokens: Vec<&str> = report.split_whitespace().collect();
+    let still_fails = |tokens: &[&str]| {
+        decode(&tokens.join(" ")).is_err_and(|error| FailureClass::of(&error) == *class)
+    };
+    loop {
+        let before = tokens.len();
+        let mut index = 1;
+        while index < tokens.len() {
+            let mut candidate = tokens.clone();
+            candidate.remove(index);
+            if still_fails(&candidate) {
+                tokens = candidate;
+            } else {
+                index += 1;
+            }
+        }
+        if tokens.len() == before {
+            return tokens.join(" ");
+        }
+    }
+}
+
+/// The smallest minimised report of the class, minimising the three
+/// shortest members.
+pub fn minimal_example(class: &FailureClass, reports: &[&str]) -> String {
+    reports
+        .iter()
+        .sorted_by_key(|report| report.len())
+        .take(3)
+        .map(|report| minimise(report, class))
+        .min_by_key(|example| (example.len(), example.clone()))
+        .unwrap_or_default()
+}
+
+/// `#[test]` cases for the tests module of `metar_decoder::metar`, one per
+/// class, asserting that the minimal example decodes.
+pub fn test_cases<'a>(
+    classes: impl IntoIterator<Item = (&'a FailureClass, &'a str, usize)>,
+) -> String {
+    let mut names = BTreeMap::<String, usize>::new();
+    classes
+        .into_iter()
+        .map(|(class, example, count)| {
+            let mut name = format!("test_parsable_{}", class.test_name());
+            let seen = names.entry(name.clone()).or_default();
+            *seen += 1;
+            if *seen > 1 {
+                name = format!("{name}_{seen}");
+            }
+            format!(
+                "    #[test]\n    #[traced_test]\n    fn {name}() {{\n        \
+                 // {class} ({} in the corpus)\n        \
+                 let input = {example:?};\n        \
+                 Metar::from_str(input).unwrap();\n    }}\n",
+                report_count(count)
+            )
+        })
+        .join("\n")
+}
+
+pub fn report_count(count: usize) -> String {
+    match count {
+        1 => "1 report".to_string(),
+        count => format!("{count} reports"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(report: &str) -> FailureClass {
+        match outcome(report) {
+            Outcome::Failed(class) => class,
+            Outcome::Parsed(canonical) => panic!("{report} parsed as {canonical}"),
+        }
+    }
+
+    #[test]
+    fn classes_ignore_digits() {
+        let qnh = class("ENGM 161220Z 01008KT CAVOK 08/04 1013");
+        assert_eq!(qnh, class("ENBR 161220Z 27012KT 9999 FEW020 12/10 0998"));
+        assert_eq!(qnh.to_string(), "pressure expected at 'nnnn'");
+        assert_eq!(qnh.test_name(), "pressure_nnnn");
+        let truncated = FailureClass {
+            expected: "temperature/dew point".to_string(),
+            token: String::new(),
+        };
+        assert_eq!(truncated.test_name(), "temperature_dew_point_end_of_report");
+    }
+
+    #[test]
+    fn minimise_keeps_what_the_failure_needs() {
+        let report = "ENGM 161220Z 01008KT 350V050 9999 -RA FEW012 BKN020 08/04 1013 NOSIG";
+        let class = class(report);
+        assert_eq!(
+            minimise(report, &class),
+            "ENGM 161220Z 01008KT 9999 BKN020 08/04 1013"
+        );
+        assert_eq!(
+            minimal_example(&class, &[report, "ENBR 161220Z 27012KT CAVOK 12/10 0998"]),
+            "ENBR 161220Z 27012KT CAVOK 12/10 0998"
+        );
+    }
+
+    #[test]
+    fn emits_uniquely_named_tests() {
+        let class = class("ENGM 161220Z 01008KT CAVOK 08/04 1013");
+        let cases = test_cases([
+            (&class, "ENGM 161220Z 00000KT CAVOK 08/04 1013", 2),
+            (&class, "ENBR 161220Z 00000KT CAVOK 08/04 0998", 1),
+        ]);
+        assert!(cases.contains("fn test_parsable_pressure_nnnn() {"));
+        assert!(cases.contains("fn test_parsable_pressure_nnnn_2() {"));
+        assert!(cases.contains("// pressure expected at 'nnnn' (2 reports in the corpus)"));
+        assert!(cases.contains("let input = \"ENBR 161220Z 00000KT CAVOK 08/04 0998\";"));
+    }
+}
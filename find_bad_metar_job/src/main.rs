@@ -1,18 +1,78 @@
-use std::{fs::File, path::Path, str::FromStr, sync::LazyLock};
+//! Runs METAR corpora through `metar_decoder`.
+//!
+//! ```text
+//! # Fetch the VATSIM feed and keep the failures in failed_metars.json
+//! cargo run -p find_bad_metar_job
+//!
+//! # Check the bundled corpora against a stored baseline
+//! cargo run -p find_bad_metar_job -- check metar_decoder/all_metars \
+//!     metar_decoder/na_metars.txt --baseline corpus_baseline.json
+//! ```
+//!
+//! Failures are grouped by the first token the decoder could not read and
+//! each group is shrunk to a minimal example, which `--emit-tests` turns
+//! into `#[test]` cases for `metar_decoder`.
 
-use futures::future::try_join_all;
-use indexmap::IndexSet;
-use metar_decoder::metar::Metar;
+mod baseline;
+mod corpus;
+mod failures;
 
-async fn get_metars_text() -> reqwest::Result<String> {
-    let urls = ["https://metar.vatsim.net/E", "https://metar.vatsim.net/L"];
-    // let urls = ["https://metar.vatsim.net/*"];
-    let pages =
-        try_join_all(urls.iter().map(async |&url| -> reqwest::Result<String> {
-            reqwest::get(url).await?.text().await
-        }))
-        .await?;
-    Ok(pages.join("\n"))
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
+
+use crate::{
+    baseline::{Baseline, Comparison},
+    corpus::Corpus,
+    failures::{FailureClass, Outcome},
+};
+
+#[derive(clap::Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Fetch the VATSIM feed, recheck earlier failures and store the ones
+    /// that still fail (the default)
+    Fetch {
+        #[clap(long, default_value = "failed_metars.json")]
+        out: PathBuf,
+    },
+    /// Decode reports from files and directories
+    Check {
+        /// Files with one report per line, JSON arrays of reports, or
+        /// directories of either
+        paths: Vec<PathBuf>,
+        /// Also check the current VATSIM feed
+        #[clap(long)]
+        vatsim: bool,
+        /// Compare against this baseline and fail on regressions
+        #[clap(long)]
+        baseline: Option<PathBuf>,
+        /// Store the current outcomes as the new baseline
+        #[clap(long, requires = "baseline")]
+        write_baseline: bool,
+        /// Write `#[test]` cases for failure classes missing from the
+        /// baseline (all classes without one) to this file, `-` for stdout
+        #[clap(long)]
+        emit_tests: Option<PathBuf>,
+        /// List every failing report, not just the minimal example
+        #[clap(long, short)]
+        verbose: bool,
+    },
 }
 
 fn get_already_failed_metars(path: &Path) -> IndexSet<String> {
@@ -22,37 +82,218 @@ fn get_already_failed_metars(path: &Path) -> IndexSet<String> {
         .unwrap_or_default()
 }
 
-fn write_failed_metars(path: &Path, failed: &IndexSet<String>) {
-    if let Ok(file) = File::create(path) {
-        let _ = serde_json::to_writer_pretty(file, failed);
-    }
+fn write_failed_metars(path: &Path, failed: &IndexSet<&str>) -> io::Result<()> {
+    Ok(serde_json::to_writer_pretty(File::create(path)?, failed)?)
+}
+
+fn decode_all(corpus: &Corpus) -> IndexMap<&str, Outcome> {
+    corpus
+        .reports()
+        .map(|report| (report, failures::outcome(report)))
+        .collect()
 }
 
-fn find_fail_parsed_metars(metars: &str, path: &Path) {
-    static IGNORE_AIRPORTS: LazyLock<IndexSet<&str>> = LazyLock::new(|| IndexSet::from(["EQYS"]));
+/// Prints each failure class with its minimal example and returns the
+/// examples.
+fn print_failures<'a>(
+    corpus: &Corpus,
+    outcomes: &'a IndexMap<&str, Outcome>,
+    verbose: bool,
+) -> BTreeMap<&'a FailureClass, (String, usize)> {
+    let classes = failures::group(outcomes.iter().map(|(report, outcome)| (*report, outcome)));
+    let failed: usize = classes.values().map(Vec::len).sum();
+    println!(
+        "{} reports: {} decoded, {failed} failed in {} classes",
+        corpus.len(),
+        corpus.len() - failed,
+        classes.len()
+    );
+    let origins: IndexMap<_, _> = corpus.iter().collect();
+    classes
+        .into_iter()
+        .sorted_by_key(|(_, reports)| std::cmp::Reverse(reports.len()))
+        .map(|(class, reports)| {
+            let example = failures::minimal_example(class, &reports);
+            println!("\n{class} ({})", failures::report_count(reports.len()));
+            if let Err(error) = failures::decode(&example) {
+                println!("{}", error.render());
+            }
+            if verbose {
+                for report in &reports {
+                    println!("  {}: {report}", origins[report]);
+                }
+            }
+            (class, (example, reports.len()))
+        })
+        .collect()
+}
 
-    let mut failed = IndexSet::new();
-    let mut to_test = get_already_failed_metars(path);
-    to_test.extend(metars.lines().map(str::to_owned));
-    for line in to_test {
-        if line.trim().is_empty() {
-            continue;
+fn print_comparison(path: &Path, comparison: &Comparison) {
+    println!("\nagainst {}:", path.display());
+    if !comparison.newly_failing.is_empty() {
+        println!("  {} newly failing:", comparison.newly_failing.len());
+        for (report, class) in &comparison.newly_failing {
+            println!("    {report}\n      {class}");
+        }
+    }
+    if !comparison.decoded_differently.is_empty() {
+        println!(
+            "  {} decoded differently:",
+            comparison.decoded_differently.len()
+        );
+        for (report, before, now) in &comparison.decoded_differently {
+            println!("    {report}\n      was {before}\n      now {now}");
+        }
+    }
+    if !comparison.failing_differently.is_empty() {
+        println!(
+            "  {} failing differently:",
+            comparison.failing_differently.len()
+        );
+        for (report, before, now) in &comparison.failing_differently {
+            println!("    {report}\n      was {before}\n      now {now}");
+        }
+    }
+    if !comparison.fixed.is_empty() {
+        println!("  {} fixed:", comparison.fixed.len());
+        for report in &comparison.fixed {
+            println!("    {report}");
         }
-        if IGNORE_AIRPORTS.contains(&&line[0..4]) {
-            continue;
+    }
+    if comparison.unknown > 0 {
+        println!("  {} reports not in the baseline", comparison.unknown);
+    }
+}
+
+async fn fetch(out: &Path) -> Result<ExitCode> {
+    let mut corpus = corpus::fetch_vatsim().await?;
+    corpus.add_text(
+        &get_already_failed_metars(out).into_iter().join("\n"),
+        &out.display().to_string(),
+    );
+    let outcomes = decode_all(&corpus);
+    print_failures(&corpus, &outcomes, true);
+    let failed: IndexSet<&str> = outcomes
+        .iter()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+        .map(|(report, _)| *report)
+        .collect();
+    write_failed_metars(out, &failed).with_context(|| format!("writing {}", out.display()))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn check(
+    paths: &[PathBuf],
+    vatsim: bool,
+    baseline_path: Option<&Path>,
+    write_baseline: bool,
+    emit_tests: Option<&Path>,
+    verbose: bool,
+) -> Result<ExitCode> {
+    let mut corpus = if vatsim {
+        corpus::fetch_vatsim().await?
+    } else {
+        Corpus::default()
+    };
+    for path in paths {
+        corpus
+            .add_path(path)
+            .with_context(|| format!("reading {}", path.display()))?;
+    }
+    if corpus.is_empty() {
+        return Err(anyhow!("no reports to check"));
+    }
+
+    let outcomes = decode_all(&corpus);
+    let examples = print_failures(&corpus, &outcomes, verbose);
+
+    let baseline = match baseline_path {
+        Some(path) if path.exists() || !write_baseline => {
+            Some(Baseline::read(path).with_context(|| format!("reading {}", path.display()))?)
         }
-        if Metar::from_str(&line).is_err() {
-            failed.insert(line);
+        _ => None,
+    };
+    let mut regressions = 0;
+    if let (Some(path), Some(baseline)) = (baseline_path, &baseline) {
+        let comparison =
+            baseline.compare(outcomes.iter().map(|(report, outcome)| (*report, outcome)));
+        print_comparison(path, &comparison);
+        regressions = comparison.regressions();
+    }
+
+    if let Some(target) = emit_tests {
+        let known = baseline
+            .as_ref()
+            .map(Baseline::failure_classes)
+            .unwrap_or_default();
+        let cases = failures::test_cases(
+            examples
+                .iter()
+                .filter(|(class, _)| !known.contains(*class))
+                .map(|(class, (example, count))| (*class, example.as_str(), *count)),
+        );
+        if target == Path::new("-") {
+            if !cases.is_empty() {
+                println!("\n{cases}");
+            }
+        } else {
+            fs::write(target, cases).with_context(|| format!("writing {}", target.display()))?;
         }
     }
-    write_failed_metars(path, &failed);
+
+    if let (true, Some(path)) = (write_baseline, baseline_path) {
+        let baseline = Baseline {
+            reports: outcomes
+                .into_iter()
+                .map(|(report, outcome)| (report.to_string(), outcome))
+                .collect(),
+        };
+        baseline
+            .write(path)
+            .with_context(|| format!("writing {}", path.display()))?;
+        println!("\nwrote {}", path.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if regressions > 0 {
+        let noun = if regressions == 1 {
+            "regression"
+        } else {
+            "regressions"
+        };
+        println!("\n{regressions} {noun}; rerun with --write-baseline to accept");
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
 }
 
 #[tokio::main]
-async fn main() -> reqwest::Result<()> {
+async fn main() -> Result<ExitCode> {
     tracing_subscriber::fmt::init();
-    let metars = get_metars_text().await?;
-    let p = Path::new("failed_metars.json");
-    find_fail_parsed_metars(&metars, p);
-    Ok(())
+    rustls::crypto::ring::default_provider()
+        .install_default()
+        .map_err(|_| anyhow!("Failed to install ring crypto provider"))?;
+    let cli = Cli::parse();
+    match cli.command.unwrap_or(Command::Fetch {
+        out: PathBuf::from("failed_metars.json"),
+    }) {
+        Command::Fetch { out } => fetch(&out).await,
+        Command::Check {
+            paths,
+            vatsim,
+            baseline,
+            write_baseline,
+            emit_tests,
+            verbose,
+        } => {
+            check(
+                &paths,
+                vatsim,
+                baseline.as_deref(),
+                write_baseline,
+                emit_tests.as_deref(),
+                verbose,
+            )
+            .await
+        }
+    }
 }
@@ -0,0 +1,48 @@
+//! Compares owned and borrowed METAR parsing over the bundled corpora.
+//!
+//! ```text
+//! cargo bench -p metar_decoder --bench parse
+//! ```
+
+use std::{hint::black_box, str::FromStr};
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use metar_decoder::metar::{Metar, MetarRef};
+
+const CORPORA: [(&str, &str); 2] = [
+    ("all_metars", include_str!("../all_metars")),
+    ("na_metars", include_str!("../na_metars.txt")),
+];
+
+fn parse_corpora(c: &mut Criterion) {
+    for (name, corpus) in CORPORA {
+        // Failing reports are diagnosed, which costs the same for both
+        // parsers and would drown out the difference.
+        let reports: Vec<&str> = corpus
+            .lines()
+            .filter(|line| MetarRef::parse(line).is_ok())
+            .collect();
+        let bytes = reports.iter().map(|report| report.len() as u64).sum();
+
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(bytes));
+        group.bench_function("Metar::from_str", |b| {
+            b.iter(|| {
+                for report in &reports {
+                    black_box(Metar::from_str(black_box(report)).unwrap());
+                }
+            })
+        });
+        group.bench_function("MetarRef::parse", |b| {
+            b.iter(|| {
+                for report in &reports {
+                    black_box(MetarRef::parse(black_box(report)).unwrap());
+                }
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse_corpora);
+criterion_main!(benches);
@@ -0,0 +1,283 @@
+//! `proptest` strategies for valid METARs, behind the `proptest` feature.
+//!
+//! Generated reports are built with [`MetarBuilder`], so their
+//! [`Metar::raw`] is the canonical text and parses back to the same value.
+//! The strategies stay within what real reports contain: wind directions in
+//! tens of degrees, gusts at least 10 kt above the mean, cloud layers in
+//! ascending order and dew points at or below the temperature.
+
+use jiff::{SignedDuration, Zoned, tz::TimeZone};
+use proptest::{
+    arbitrary::Arbitrary,
+    collection::vec,
+    option, prop_oneof,
+    strategy::{BoxedStrategy, Just, Strategy},
+};
+
+use crate::{
+    builder::MetarBuilder,
+    metar::Metar,
+    obscuration::{CloudCoverage, PresentWeather, Qualifier, WeatherIntensity, WeatherPhenomenon},
+    optional_data::OptionalData,
+    units::velocity::VelocityUnit,
+    wind::Wind,
+};
+
+/// A wind as the builder calls for it: direction (`None` for `VRB`), mean
+/// speed, gust and variation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindSpec {
+    pub direction: Option<u32>,
+    pub speed: u32,
+    pub gust: Option<u32>,
+    pub varying: Option<(u32, u32)>,
+}
+
+impl WindSpec {
+    pub fn apply(&self, builder: MetarBuilder) -> MetarBuilder {
+        let mut builder = match self.direction {
+            Some(direction) => builder.wind(direction, self.speed),
+            None => builder.variable_wind(self.speed),
+        };
+        if let Some(gust) = self.gust {
+            builder = builder.gust(gust);
+        }
+        if let Some((from, to)) = self.varying {
+            builder = builder.varying(from, to);
+        }
+        builder
+    }
+}
+
+pub fn icao() -> impl Strategy<Value = String> {
+    "[A-Z]{4}"
+}
+
+/// Observation times on the hour or half hour during the past week, which
+/// reparse to the same instant.
+pub fn observed() -> impl Strategy<Value = Zoned> {
+    (1..7 * 48i64).prop_map(|half_hours_ago| {
+        let now = Zoned::now().with_time_zone(TimeZone::UTC);
+        let last_half_hour = now
+            .with()
+            .minute(if now.minute() < 30 { 0 } else { 30 })
+            .second(0)
+            .subsec_nanosecond(0)
+            .build()
+            .expect("truncating the current time");
+        last_half_hour - SignedDuration::from_mins(30 * half_hours_ago)
+    })
+}
+
+/// Winds in knots: calm, variable or from a direction in tens of degrees,
+/// with optional gusts and variation.
+pub fn wind_spec() -> impl Strategy<Value = WindSpec> {
+    let calm = Just(WindSpec {
+        direction: Some(0),
+        speed: 0,
+        gust: None,
+        varying: None,
+    });
+    let variable = (1..=6u32).prop_map(|speed| WindSpec {
+        direction: None,
+        speed,
+        gust: None,
+        varying: None,
+    });
+    let directional = (
+        1..=36u32,
+        1..=60u32,
+        option::of(10..=25u32),
+        option::of(3..=9u32),
+    )
+        .prop_map(|(tens, speed, gust_margin, half_range)| WindSpec {
+            direction: Some(tens * 10),
+            speed,
+            gust: gust_margin.map(|margin| speed + margin),
+            varying: half_range.filter(|_| speed >= 3).map(|half_range| {
+                let from = (tens + 36 - half_range) % 36 * 10;
+                let to = (tens + half_range) % 36 * 10;
+                (
+                    if from == 0 { 360 } else { from },
+                    if to == 0 { 360 } else { to },
+                )
+            }),
+        });
+    prop_oneof![1 => calm, 1 => variable, 8 => directional]
+}
+
+pub fn wind() -> impl Strategy<Value = Wind> {
+    wind_spec().prop_map(|spec| spec.apply(MetarBuilder::new("TEST")).build().wind)
+}
+
+fn cloud_coverage() -> impl Strategy<Value = CloudCoverage> {
+    prop_oneof![
+        Just(CloudCoverage::Few),
+        Just(CloudCoverage::Scattered),
+        Just(CloudCoverage::Broken),
+        Just(CloudCoverage::Overcast),
+    ]
+}
+
+fn present_weather() -> impl Strategy<Value = PresentWeather> {
+    let weather = |intensity, descriptor, phenomenon| PresentWeather {
+        intensity,
+        descriptor,
+        phenomena: vec![OptionalData::Data(phenomenon)],
+    };
+    prop_oneof![
+        Just(weather(
+            Some(WeatherIntensity::Light),
+            None,
+            WeatherPhenomenon::RA
+        )),
+        Just(weather(None, None, WeatherPhenomenon::RA)),
+        Just(weather(
+            Some(WeatherIntensity::Heavy),
+            Some(Qualifier::Showers),
+            WeatherPhenomenon::RA
+        )),
+        Just(weather(None, None, WeatherPhenomenon::BR)),
+        Just(weather(None, None, WeatherPhenomenon::FG)),
+        Just(weather(
+            Some(WeatherIntensity::Light),
+            None,
+            WeatherPhenomenon::SN
+        )),
+        Just(weather(
+            Some(WeatherIntensity::Light),
+            Some(Qualifier::Freezing),
+            WeatherPhenomenon::DZ
+        )),
+    ]
+}
+
+/// Visibility, weather and cloud applied to `builder`: CAVOK, or a
+/// visibility with up to two weather groups and up to three ascending cloud
+/// layers, `NSC` when there are none.
+fn sky(builder: MetarBuilder) -> impl Strategy<Value = MetarBuilder> {
+    let cavok = Just(builder.clone().cavok());
+    let described = (
+        prop_oneof![(0..=199u32).prop_map(|v| v * 50), Just(9999)],
+        vec(present_weather(), 0..=2),
+        vec((cloud_coverage(), 1..=50u32), 0..=3),
+        option::of(prop_oneof![Just("CB"), Just("TCU")]),
+    )
+        .prop_map(move |(visibility, weather, layers, convective)| {
+            let mut builder = builder.clone().visibility(visibility);
+            for weather in weather {
+                builder = builder.weather(weather);
+            }
+            if layers.is_empty() {
+                builder = builder.no_significant_cloud();
+            }
+            let mut height = 0;
+            let last = layers.len().saturating_sub(1);
+            for (index, (coverage, step)) in layers.into_iter().enumerate() {
+                height += step * 100;
+                builder = match convective.filter(|_| index == last) {
+                    Some(kind) => builder.convective_cloud(coverage, height, kind),
+                    None => builder.cloud(coverage, height),
+                };
+            }
+            builder
+        });
+    prop_oneof![1 => cavok, 3 => described]
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PressureSetting {
+    Qnh(u32),
+    Altimeter(u32),
+}
+
+/// Reports from `icao` with arbitrary wind, sky, temperature and pressure.
+pub fn metar_for(icao: String) -> impl Strategy<Value = Metar> {
+    (
+        observed(),
+        wind_spec(),
+        prop_oneof![
+            Just(VelocityUnit::Knots),
+            Just(VelocityUnit::MetersPerSecond)
+        ],
+        -30..=40i32,
+        0..=15i32,
+        prop_oneof![
+            (950..=1050u32).prop_map(PressureSetting::Qnh),
+            (2800..=3100u32).prop_map(PressureSetting::Altimeter),
+        ],
+        proptest::bool::ANY,
+    )
+        .prop_flat_map(
+            move |(observed, wind, unit, temperature, spread, pressure, nosig)| {
+                let mut builder = wind
+                    .apply(MetarBuilder::new(&icao).observed_at(&observed))
+                    .wind_unit(unit)
+                    .temperature(temperature, temperature - spread);
+                builder = match pressure {
+                    PressureSetting::Qnh(qnh) => builder.qnh(qnh),
+                    PressureSetting::Altimeter(altimeter) => builder.altimeter(altimeter),
+                };
+                if nosig {
+                    builder = builder.nosig();
+                }
+                sky(builder).prop_map(MetarBuilder::build)
+            },
+        )
+}
+
+pub fn metar() -> impl Strategy<Value = Metar> {
+    icao().prop_flat_map(metar_for)
+}
+
+impl Arbitrary for Metar {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Metar>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        metar().boxed()
+    }
+}
+
+impl Arbitrary for Wind {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Wind>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        wind().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use proptest::{prelude::any, prop_assert, prop_assert_eq, proptest};
+
+    use super::*;
+    use crate::metar::MetarRef;
+
+    proptest! {
+        #[test]
+        fn generated_reports_parse_back(metar in any::<Metar>()) {
+            prop_assert_eq!(Metar::from_str(&metar.raw), Ok(metar.clone()));
+            prop_assert_eq!(MetarRef::parse(&metar.raw).map(|m| m.to_owned()), Ok(metar));
+        }
+
+        #[test]
+        fn generated_reports_display_as_they_parse(metar in any::<Metar>()) {
+            let text = metar.to_string();
+            let mut reparsed = Metar::from_str(&text).unwrap();
+            prop_assert_eq!(reparsed.to_string(), text);
+            reparsed.raw = metar.raw.clone();
+            prop_assert_eq!(reparsed, metar);
+        }
+
+        #[test]
+        fn knots_keep_gusts_above_the_mean(metar in any::<Metar>()) {
+            let knots = metar.wind.speed.in_knots();
+            prop_assert!(knots.get_max_wind_speed() >= knots.velocity.to_option());
+            prop_assert_eq!(knots.unit, VelocityUnit::Knots);
+        }
+    }
+}
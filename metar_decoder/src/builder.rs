@@ -0,0 +1,350 @@
+//! Typed construction of [`Metar`]s, for tests and tools that need a report
+//! without writing out its text. The builder starts from a calm, CAVOK
+//! report and every setter replaces one group; [`MetarBuilder::build`] fills
+//! in [`Metar::raw`] with the canonical text of the result.
+//!
+//! ```
+//! use metar_decoder::{builder::MetarBuilder, obscuration::CloudCoverage};
+//!
+//! let metar = MetarBuilder::new("ENGM")
+//!     .wind(10, 8)
+//!     .gust(18)
+//!     .visibility(800)
+//!     .cloud(CloudCoverage::Broken, 400)
+//!     .temperature(-2, -4)
+//!     .qnh(1002)
+//!     .build();
+//! assert!(metar.raw.ends_with("01008G18KT 0800 BKN004 M02/M04 Q1002"));
+//! ```
+
+use jiff::{Unit, Zoned, ZonedRound, tz::TimeZone};
+
+use crate::{
+    metar::{Metar, ReportType, missing_groups},
+    obscuration::{
+        Cloud, CloudCoverage, CloudData, DescribedObscuration, Obscuration, PresentWeather, Rvr,
+        VerticalVisibility, Visibility, VisibilityUnit,
+    },
+    optional_data::OptionalData,
+    pressure::{Pressure, PressureSingle, PressureUnit},
+    temperature::TemperatureInfo,
+    units::{
+        altitudes::CloudHeight,
+        timestamp::Timestamp,
+        track::Track,
+        velocity::{VelocityUnit, WindVelocity},
+    },
+    wind::{Wind, WindDirection},
+};
+
+#[derive(Debug, Clone)]
+pub struct MetarBuilder {
+    metar: Metar,
+}
+
+impl MetarBuilder {
+    /// `ICAO ddhhmmZ 00000KT CAVOK 15/10 Q1013`, observed at the current
+    /// minute.
+    pub fn new(icao: &str) -> Self {
+        let now = Zoned::now()
+            .with_time_zone(TimeZone::UTC)
+            .round(ZonedRound::new().smallest(Unit::Minute))
+            .expect("rounding the current time to minutes");
+        MetarBuilder {
+            metar: Metar {
+                raw: String::new(),
+                report_type: ReportType::Metar,
+                icao: icao.to_string(),
+                timestamp: Timestamp::new(now),
+                corrected: false,
+                auto: false,
+                wind: Wind {
+                    dir: WindDirection::Heading(Track(OptionalData::Data(0))),
+                    speed: WindVelocity {
+                        velocity: OptionalData::Data(0),
+                        gust: None,
+                        unit: VelocityUnit::Knots,
+                    },
+                    varying: None,
+                },
+                obscuration: Obscuration::Cavok,
+                temperature: TemperatureInfo {
+                    temp: OptionalData::Data(15),
+                    dew_point: OptionalData::Data(10),
+                },
+                pressure: Pressure {
+                    qnh: Some(PressureSingle {
+                        value: OptionalData::Data(1013),
+                        unit: PressureUnit::Hectopascals,
+                    }),
+                    altimeter: None,
+                },
+                recent_weather: None,
+                wind_shear: Vec::new(),
+                runway_states: Vec::new(),
+                snow_closed: false,
+                nosig: false,
+                sea_surface_indicator: None,
+                trends: Vec::new(),
+                nato_mil_code: None,
+                remarks: None,
+                missing_groups: Vec::new(),
+            },
+        }
+    }
+
+    /// Observation time. Seconds are dropped, as a report cannot carry them.
+    pub fn observed_at(mut self, observed: &Zoned) -> Self {
+        let observed = observed
+            .with_time_zone(TimeZone::UTC)
+            .with()
+            .second(0)
+            .subsec_nanosecond(0)
+            .build()
+            .expect("dropping seconds from a valid time");
+        self.metar.timestamp = Timestamp::new(observed);
+        self
+    }
+
+    pub fn speci(mut self) -> Self {
+        self.metar.report_type = ReportType::Speci;
+        self
+    }
+
+    pub fn corrected(mut self) -> Self {
+        self.metar.corrected = true;
+        self
+    }
+
+    pub fn auto(mut self) -> Self {
+        self.metar.auto = true;
+        self
+    }
+
+    /// Mean wind from `direction` degrees true at `speed` in the current
+    /// wind unit (knots unless [`MetarBuilder::wind_unit`] changed it).
+    pub fn wind(mut self, direction: u32, speed: u32) -> Self {
+        self.metar.wind.dir = WindDirection::Heading(Track(OptionalData::Data(direction)));
+        self.metar.wind.speed.velocity = OptionalData::Data(speed);
+        self
+    }
+
+    /// Variable (`VRB`) wind at `speed`.
+    pub fn variable_wind(mut self, speed: u32) -> Self {
+        self.metar.wind.dir = WindDirection::Variable;
+        self.metar.wind.speed.velocity = OptionalData::Data(speed);
+        self
+    }
+
+    pub fn gust(mut self, gust: u32) -> Self {
+        self.metar.wind.speed.gust = Some(OptionalData::Data(gust));
+        self
+    }
+
+    /// Wind direction varying between `from` and `to`, e.g. `350V050`.
+    pub fn varying(mut self, from: u32, to: u32) -> Self {
+        self.metar.wind.varying = Some((
+            Track(OptionalData::Data(from)),
+            Track(OptionalData::Data(to)),
+        ));
+        self
+    }
+
+    pub fn wind_unit(mut self, unit: VelocityUnit) -> Self {
+        self.metar.wind.speed.unit = unit;
+        self
+    }
+
+    pub fn cavok(mut self) -> Self {
+        self.metar.obscuration = Obscuration::Cavok;
+        self
+    }
+
+    /// Prevailing visibility in metres, `9999` for 10 km or more. Replaces
+    /// CAVOK; weather and cloud groups already added are kept.
+    pub fn visibility(mut self, meters: u32) -> Self {
+        self.described().visibility = Visibility {
+            value: VisibilityUnit::Meters(OptionalData::Data(meters)),
+            direction: None,
+            ndv: false,
+        };
+        self
+    }
+
+    pub fn rvr(mut self, runway: &str, meters: u32) -> Self {
+        self.described().rvr.push(Rvr {
+            runway: runway.to_string(),
+            value: OptionalData::Data(meters),
+            distance_modifier: None,
+            comment: None,
+        });
+        self
+    }
+
+    pub fn weather(mut self, weather: PresentWeather) -> Self {
+        self.described().present_weather.push(weather);
+        self
+    }
+
+    /// A cloud layer at `height_ft` above the aerodrome, rounded down to
+    /// hundreds of feet. Layers are reported in the order they are added.
+    pub fn cloud(self, coverage: CloudCoverage, height_ft: u32) -> Self {
+        self.cloud_layer(coverage, height_ft, None)
+    }
+
+    /// A cloud layer of convective cloud, e.g. `"CB"` or `"TCU"`.
+    pub fn convective_cloud(self, coverage: CloudCoverage, height_ft: u32, kind: &str) -> Self {
+        self.cloud_layer(coverage, height_ft, Some(kind))
+    }
+
+    fn cloud_layer(mut self, coverage: CloudCoverage, height_ft: u32, kind: Option<&str>) -> Self {
+        self.described().clouds.push(Cloud::CloudData(CloudData {
+            coverage: OptionalData::Data(coverage),
+            height: OptionalData::Data(CloudHeight {
+                height: (height_ft / 100) as i32,
+            }),
+            cloud_type: kind.map(|kind| OptionalData::Data(kind.to_string())),
+        }));
+        self
+    }
+
+    /// `NSC`: no cloud below 5000 ft or the highest minimum sector
+    /// altitude, and no CB or TCU.
+    pub fn no_significant_cloud(mut self) -> Self {
+        self.described().clouds.push(Cloud::NSC);
+        self
+    }
+
+    /// Vertical visibility in feet, rounded down to hundreds of feet.
+    pub fn vertical_visibility(mut self, height_ft: u32) -> Self {
+        self.described().vertical_visibility = Some(VerticalVisibility {
+            visibility: OptionalData::Data(height_ft / 100),
+        });
+        self
+    }
+
+    pub fn temperature(mut self, temperature: i32, dew_point: i32) -> Self {
+        self.metar.temperature = TemperatureInfo {
+            temp: OptionalData::Data(temperature),
+            dew_point: OptionalData::Data(dew_point),
+        };
+        self
+    }
+
+    pub fn qnh(mut self, hectopascals: u32) -> Self {
+        self.metar.pressure = Pressure {
+            qnh: Some(PressureSingle {
+                value: OptionalData::Data(hectopascals),
+                unit: PressureUnit::Hectopascals,
+            }),
+            altimeter: None,
+        };
+        self
+    }
+
+    /// Altimeter setting in hundredths of an inch of mercury, e.g. `2992`.
+    pub fn altimeter(mut self, hundredths_inch: u32) -> Self {
+        self.metar.pressure = Pressure {
+            qnh: None,
+            altimeter: Some(PressureSingle {
+                value: OptionalData::Data(hundredths_inch),
+                unit: PressureUnit::InchesOfMercury,
+            }),
+        };
+        self
+    }
+
+    pub fn nosig(mut self) -> Self {
+        self.metar.nosig = true;
+        self
+    }
+
+    /// A report that is not CAVOK and has no cloud group lists the sky
+    /// condition as missing, the same as when it is parsed.
+    pub fn build(mut self) -> Metar {
+        self.metar.missing_groups = missing_groups(true, true, &self.metar.obscuration, true, true);
+        self.metar.raw = self.metar.to_string();
+        self.metar
+    }
+
+    /// The described obscuration, turning CAVOK into 10 km or more without
+    /// weather or cloud.
+    fn described(&mut self) -> &mut DescribedObscuration {
+        if self.metar.obscuration == Obscuration::Cavok {
+            self.metar.obscuration = Obscuration::Described(DescribedObscuration {
+                visibility: Visibility {
+                    value: VisibilityUnit::Meters(OptionalData::Data(9999)),
+                    direction: None,
+                    ndv: false,
+                },
+                direction_visibility: None,
+                rvr: Vec::new(),
+                clouds: Vec::new(),
+                present_weather: Vec::new(),
+                vertical_visibility: None,
+            });
+        }
+        match &mut self.metar.obscuration {
+            Obscuration::Described(described) => described,
+            Obscuration::Cavok => unreachable!("CAVOK was replaced above"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use jiff::civil::date;
+
+    use super::*;
+    use crate::obscuration::{Qualifier, WeatherIntensity, WeatherPhenomenon};
+
+    #[test]
+    fn test_defaults() {
+        let observed = date(2025, 6, 16).at(12, 20, 0, 0).in_tz("UTC").unwrap();
+        let metar = MetarBuilder::new("ENGM").observed_at(&observed).build();
+        assert_eq!(metar.raw, "ENGM 161220Z 00000KT CAVOK 15/10 Q1013");
+    }
+
+    #[test]
+    fn test_built_report_parses_back() {
+        let metar = MetarBuilder::new("ENGM")
+            .wind(10, 8)
+            .gust(18)
+            .varying(350, 50)
+            .visibility(800)
+            .rvr("01L", 1000)
+            .weather(PresentWeather {
+                intensity: Some(WeatherIntensity::Light),
+                descriptor: Some(Qualifier::Freezing),
+                phenomena: vec![OptionalData::Data(WeatherPhenomenon::DZ)],
+            })
+            .cloud(CloudCoverage::Few, 400)
+            .convective_cloud(CloudCoverage::Broken, 1250, "CB")
+            .temperature(-2, -4)
+            .qnh(1002)
+            .nosig()
+            .build();
+        assert!(
+            metar.raw.ends_with(
+                "01008G18KT 350V050 0800 R01L/1000 -FZDZ FEW004 BKN012CB M02/M04 Q1002 NOSIG"
+            ),
+            "{}",
+            metar.raw
+        );
+        assert_eq!(Metar::from_str(&metar.raw), Ok(metar));
+    }
+
+    #[test]
+    fn test_visibility_replaces_cavok() {
+        let metar = MetarBuilder::new("KJFK")
+            .variable_wind(3)
+            .vertical_visibility(200)
+            .visibility(100)
+            .altimeter(2992)
+            .build();
+        assert!(metar.raw.ends_with("VRB03KT 0100 VV002 15/10 A2992"));
+        assert!(metar.ceiling().is_some());
+    }
+}
@@ -0,0 +1,308 @@
+//! Facts derived from the decoded groups of a [`Metar`]: ceiling, flight
+//! category, humidity, freezing conditions, LVP minima and pressure/density
+//! altitude. Unreadable groups never count as good weather: a ceiling layer
+//! with unreadable coverage is treated as a ceiling, one with unreadable
+//! height as the lowest layer.
+
+use crate::{
+    metar::Metar,
+    obscuration::{
+        Cloud, CloudCoverage, DescribedObscuration, METERS_PER_STATUTE_MILE, Obscuration,
+        Qualifier, Visibility,
+    },
+    optional_data::OptionalData,
+};
+
+const STANDARD_PRESSURE_HPA: f64 = 1013.25;
+/// Reported prevailing visibility for CAVOK and `9999`, "10 km or more".
+const CAVOK_VISIBILITY_METERS: u32 = 9999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ceiling {
+    pub kind: CeilingKind,
+    /// Height above the aerodrome in feet. `None` if reported as `///`.
+    pub height_ft: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CeilingKind {
+    Broken,
+    Overcast,
+    /// Cloud layer with unreadable coverage (`///015`).
+    Unknown,
+    VerticalVisibility,
+}
+
+/// FAA flight categories, ordered from worst to best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlightCategory {
+    /// Ceiling below 500 ft or visibility below 1 SM.
+    Lifr,
+    /// Ceiling below 1000 ft or visibility below 3 SM.
+    Ifr,
+    /// Ceiling up to 3000 ft or visibility up to 5 SM.
+    Mvfr,
+    Vfr,
+}
+
+/// The lowest values that low visibility procedures are usually keyed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LvpMinima {
+    /// Lowest of the prevailing and directional visibilities, in metres.
+    pub visibility_m: Option<u32>,
+    /// Lowest readable RVR, in metres.
+    pub rvr_m: Option<u32>,
+    /// Ceiling height in feet, `Some(0)` when the ceiling height is unreadable.
+    pub ceiling_ft: Option<u32>,
+}
+
+impl Metar {
+    fn described(&self) -> Option<&DescribedObscuration> {
+        match &self.obscuration {
+            Obscuration::Described(described) => Some(described),
+            Obscuration::Cavok => None,
+        }
+    }
+
+    /// Lowest broken or overcast layer, or the vertical visibility.
+    pub fn ceiling(&self) -> Option<Ceiling> {
+        let described = self.described()?;
+        let layers = described.clouds.iter().filter_map(|cloud| {
+            let Cloud::CloudData(data) = cloud else {
+                return None;
+            };
+            let kind = match &data.coverage {
+                OptionalData::Data(CloudCoverage::Broken) => CeilingKind::Broken,
+                OptionalData::Data(CloudCoverage::Overcast) => CeilingKind::Overcast,
+                OptionalData::Data(_) => return None,
+                OptionalData::Undefined => CeilingKind::Unknown,
+            };
+            Some(Ceiling {
+                kind,
+                height_ft: data
+                    .height
+                    .clone()
+                    .to_option()
+                    .map(|h| h.height as u32 * 100),
+            })
+        });
+        let vertical_visibility = described.vertical_visibility.as_ref().map(|vv| Ceiling {
+            kind: CeilingKind::VerticalVisibility,
+            height_ft: vv.visibility.to_option().map(|h| h * 100),
+        });
+        // `None` sorts first, so an unreadable height wins as the worst case.
+        layers
+            .chain(vertical_visibility)
+            .min_by_key(|c| c.height_ft)
+    }
+
+    /// Prevailing visibility in metres; statute miles are converted.
+    pub(crate) fn prevailing_visibility_m(&self) -> Option<u32> {
+        match self.described() {
+            Some(described) => described.visibility.in_meters(),
+            None => Some(CAVOK_VISIBILITY_METERS),
+        }
+    }
+
+    /// `None` when the visibility or the ceiling height is unreadable.
+    pub fn flight_category(&self) -> Option<FlightCategory> {
+        let visibility = self.prevailing_visibility_m()?;
+        let by_visibility = if f64::from(visibility) < METERS_PER_STATUTE_MILE {
+            FlightCategory::Lifr
+        } else if f64::from(visibility) < 3.0 * METERS_PER_STATUTE_MILE {
+            FlightCategory::Ifr
+        } else if f64::from(visibility) <= 5.0 * METERS_PER_STATUTE_MILE {
+            FlightCategory::Mvfr
+        } else {
+            FlightCategory::Vfr
+        };
+        let by_ceiling = match self.ceiling() {
+            None => FlightCategory::Vfr,
+            Some(ceiling) => match ceiling.height_ft? {
+                ..500 => FlightCategory::Lifr,
+                500..1000 => FlightCategory::Ifr,
+                1000..=3000 => FlightCategory::Mvfr,
+                _ => FlightCategory::Vfr,
+            },
+        };
+        Some(by_visibility.min(by_ceiling))
+    }
+
+    /// Temperature and dew point in °C, preferring the tenths from the
+    /// remarks `T`-group when present.
+    fn temperature_and_dew_point(&self) -> (Option<f64>, Option<f64>) {
+        if let Some(precise) = self
+            .remarks
+            .as_ref()
+            .and_then(|remarks| remarks.precise_temperature())
+        {
+            return (
+                Some(precise.temperature_celsius()),
+                precise.dew_point_celsius(),
+            );
+        }
+        (
+            self.temperature.temp.to_option().map(f64::from),
+            self.temperature.dew_point.to_option().map(f64::from),
+        )
+    }
+
+    /// Relative humidity in percent (Magnus formula).
+    pub fn relative_humidity(&self) -> Option<f64> {
+        let (Some(temperature), Some(dew_point)) = self.temperature_and_dew_point() else {
+            return None;
+        };
+        let magnus = |t: f64| (17.625 * t / (243.04 + t)).exp();
+        Some((100.0 * magnus(dew_point) / magnus(temperature)).min(100.0))
+    }
+
+    /// True when the temperature is 0 °C or below. `None` if unreadable.
+    pub fn at_or_below_freezing(&self) -> Option<bool> {
+        self.temperature_and_dew_point().0.map(|t| t <= 0.0)
+    }
+
+    /// True when present weather has the freezing descriptor (`FZDZ`, `FZRA`,
+    /// `FZFG`, ...).
+    pub fn freezing_weather(&self) -> bool {
+        self.described().is_some_and(|described| {
+            described
+                .present_weather
+                .iter()
+                .any(|weather| weather.descriptor == Some(Qualifier::Freezing))
+        })
+    }
+
+    pub fn lvp_minima(&self) -> LvpMinima {
+        let Some(described) = self.described() else {
+            return LvpMinima {
+                visibility_m: Some(CAVOK_VISIBILITY_METERS),
+                ..LvpMinima::default()
+            };
+        };
+        let visibility_m = std::iter::once(&described.visibility)
+            .chain(described.direction_visibility.iter().flatten())
+            .filter_map(Visibility::in_meters)
+            .min();
+        LvpMinima {
+            visibility_m,
+            rvr_m: described
+                .rvr
+                .iter()
+                .filter_map(|rvr| rvr.value.to_option())
+                .min(),
+            ceiling_ft: self.ceiling().map(|c| c.height_ft.unwrap_or(0)),
+        }
+    }
+
+    /// Pressure altitude in feet at an aerodrome with the given elevation.
+    pub fn pressure_altitude_ft(&self, elevation_ft: i32) -> Option<i32> {
+        let qnh = self.pressure.in_hectopascals()?;
+        let offset = 145_366.45 * (1.0 - (qnh / STANDARD_PRESSURE_HPA).powf(0.190_284));
+        Some(elevation_ft + offset.round() as i32)
+    }
+
+    /// Density altitude in feet at an aerodrome with the given elevation,
+    /// using the 118.8 ft/°C rule of thumb.
+    pub fn density_altitude_ft(&self, elevation_ft: i32) -> Option<i32> {
+        let pressure_altitude = self.pressure_altitude_ft(elevation_ft)?;
+        let temperature = self.temperature_and_dew_point().0?;
+        let isa_temperature = 15.0 - 1.98 * f64::from(pressure_altitude) / 1000.0;
+        Some(pressure_altitude + (118.8 * (temperature - isa_temperature)).round() as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn metar(s: &str) -> Metar {
+        Metar::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_ceiling() {
+        let m = metar("ENGM 161220Z 01008KT 9999 FEW004 BKN012 OVC030 05/03 Q1002");
+        assert_eq!(
+            m.ceiling(),
+            Some(Ceiling {
+                kind: CeilingKind::Broken,
+                height_ft: Some(1200),
+            })
+        );
+        let m = metar("ENGM 161220Z 01008KT 0300 FG VV002 05/05 Q1002");
+        assert_eq!(m.ceiling().unwrap().kind, CeilingKind::VerticalVisibility);
+        assert_eq!(m.ceiling().unwrap().height_ft, Some(200));
+        let m = metar("ENGM 161220Z 01008KT 9999 SCT010 BKN/// 05/03 Q1002");
+        assert_eq!(m.ceiling().unwrap().height_ft, None);
+        assert_eq!(m.lvp_minima().ceiling_ft, Some(0));
+        assert_eq!(m.flight_category(), None);
+        let m = metar("ENGM 161220Z 01008KT CAVOK 05/03 Q1002");
+        assert_eq!(m.ceiling(), None);
+    }
+
+    #[test]
+    fn test_flight_category() {
+        let category = |s| metar(s).flight_category();
+        assert_eq!(
+            category("ENGM 161220Z 01008KT CAVOK 05/03 Q1002"),
+            Some(FlightCategory::Vfr)
+        );
+        assert_eq!(
+            category("ENGM 161220Z 01008KT 8000 BKN040 05/03 Q1002"),
+            Some(FlightCategory::Mvfr)
+        );
+        assert_eq!(
+            category("ENGM 161220Z 01008KT 9999 OVC008 05/03 Q1002"),
+            Some(FlightCategory::Ifr)
+        );
+        assert_eq!(
+            category("KJFK 161251Z 01008KT 1/2SM FG OVC002 05/05 A2992"),
+            Some(FlightCategory::Lifr)
+        );
+    }
+
+    #[test]
+    fn test_humidity_and_freezing() {
+        let m = metar("ENGM 161220Z 01008KT 2000 -FZDZ BR OVC004 M02/M02 Q1002");
+        assert_eq!(m.relative_humidity(), Some(100.0));
+        assert_eq!(m.at_or_below_freezing(), Some(true));
+        assert!(m.freezing_weather());
+
+        let m = metar("KJFK 161251Z 01008KT 10SM FEW250 21/10 A2992 RMK AO2 T02110100");
+        let humidity = m.relative_humidity().unwrap();
+        assert!((49.0..50.0).contains(&humidity), "{humidity}");
+        assert_eq!(m.at_or_below_freezing(), Some(false));
+        assert!(!m.freezing_weather());
+    }
+
+    #[test]
+    fn test_lvp_minima() {
+        let m =
+            metar("ENGM 161220Z 01008KT 0800 0400NE R01L/P2000N R19R/0550D FG BKN002 01/01 Q1002");
+        assert_eq!(
+            m.lvp_minima(),
+            LvpMinima {
+                visibility_m: Some(400),
+                rvr_m: Some(550),
+                ceiling_ft: Some(200),
+            }
+        );
+    }
+
+    #[test]
+    fn test_pressure_and_density_altitude() {
+        let m = metar("ENGM 161220Z 01008KT CAVOK 15/03 Q1013");
+        assert_eq!(m.pressure_altitude_ft(0), Some(7));
+        let m = metar("ENGM 161220Z 01008KT CAVOK 30/03 Q1003");
+        assert_eq!(m.pressure_altitude_ft(681), Some(962));
+        assert_eq!(m.density_altitude_ft(681), Some(2970));
+        let m = metar("KDEN 161253Z 01008KT 10SM FEW250 30/03 A2992");
+        assert_eq!(m.pressure_altitude_ft(5434), Some(5435));
+    }
+}
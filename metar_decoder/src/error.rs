@@ -0,0 +1,285 @@
+//! Parse errors that point at the offending group of a METAR, with a guess
+//! at what the report meant.
+
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+};
+
+use crate::{
+    lenient,
+    metar::{Metar, MissingGroup},
+};
+
+/// The group the decoder was looking for where the report went wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedGroup {
+    Icao,
+    ObservationTime,
+    Wind,
+    Visibility,
+    SkyCondition,
+    Temperature,
+    Pressure,
+    /// Anything after the pressure: recent weather, wind shear, runway
+    /// state, trends or remarks.
+    Supplementary,
+}
+
+impl Display for ExpectedGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExpectedGroup::Icao => "ICAO code",
+            ExpectedGroup::ObservationTime => "observation time",
+            ExpectedGroup::Wind => "wind",
+            ExpectedGroup::Visibility => "visibility",
+            ExpectedGroup::SkyCondition => "cloud",
+            ExpectedGroup::Temperature => "temperature/dew point",
+            ExpectedGroup::Pressure => "pressure",
+            ExpectedGroup::Supplementary => "supplementary group or trend",
+        })
+    }
+}
+
+impl From<MissingGroup> for ExpectedGroup {
+    fn from(group: MissingGroup) -> Self {
+        match group {
+            MissingGroup::Wind => ExpectedGroup::Wind,
+            MissingGroup::Visibility => ExpectedGroup::Visibility,
+            MissingGroup::SkyCondition => ExpectedGroup::SkyCondition,
+            MissingGroup::Temperature => ExpectedGroup::Temperature,
+            MissingGroup::Pressure => ExpectedGroup::Pressure,
+        }
+    }
+}
+
+/// Why a METAR could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct MetarParseError {
+    /// The report as it was parsed, after trimming.
+    pub input: String,
+    /// Byte range of the offending token in `input`. Empty at the end of a
+    /// truncated report.
+    pub range: Range<usize>,
+    pub expected: ExpectedGroup,
+    pub suggestion: Option<&'static str>,
+}
+
+impl Display for MetarParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.token() {
+            "" => write!(f, "{} expected at end of report", self.expected)?,
+            token => write!(
+                f,
+                "{} expected, found '{token}' at bytes {}..{}",
+                self.expected, self.range.start, self.range.end
+            )?,
+        }
+        match self.suggestion {
+            Some(suggestion) => write!(f, " ({suggestion})"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl MetarParseError {
+    /// The offending token.
+    pub fn token(&self) -> &str {
+        &self.input[self.range.clone()]
+    }
+
+    /// The report with a caret line under the offending token, e.g.
+    ///
+    /// ```text
+    /// ENGM 161220Z 01008KT CAVOK 08/04 1013
+    ///                                  ^^^^ pressure expected: QNH without Q prefix?
+    /// ```
+    pub fn render(&self) -> String {
+        let indent = self.input[..self.range.start].chars().count();
+        let width = self.token().chars().count().max(1);
+        let mut rendered = format!(
+            "{}\n{}{} {} expected",
+            self.input,
+            " ".repeat(indent),
+            "^".repeat(width),
+            self.expected
+        );
+        if let Some(suggestion) = self.suggestion {
+            rendered.push_str(": ");
+            rendered.push_str(suggestion);
+        }
+        rendered
+    }
+}
+
+/// Explains why `input` failed to parse. `failed_at` is the byte offset
+/// where the strict parser stopped; the lenient parser usually pins the
+/// offending token down more precisely.
+pub(crate) fn diagnose(input: &str, failed_at: usize) -> MetarParseError {
+    match lenient::parse_lenient(input) {
+        Ok(parsed) => {
+            let range = match parsed.unparsed.first() {
+                Some(span) => span.range.clone(),
+                None => token_at(input, failed_at),
+            };
+            error(input, range, expected_group(&parsed.metar))
+        }
+        Err(_) => {
+            let report_start = input.len() - lenient::strip_report_type(input).len();
+            let icao = token_at(input, report_start);
+            let icao_valid = icao.len() == 4
+                && input[icao.clone()]
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric());
+            if icao_valid {
+                let time = token_at(input, icao.end);
+                error(input, time, ExpectedGroup::ObservationTime)
+            } else {
+                error(input, icao, ExpectedGroup::Icao)
+            }
+        }
+    }
+}
+
+/// The first mandatory group that is still missing, otherwise one of the
+/// optional groups that follow them.
+fn expected_group(metar: &Metar) -> ExpectedGroup {
+    metar
+        .missing_groups
+        .first()
+        .map_or(ExpectedGroup::Supplementary, |&group| group.into())
+}
+
+fn error(input: &str, range: Range<usize>, expected: ExpectedGroup) -> MetarParseError {
+    let suggestion = suggest(&input[range.clone()], expected);
+    MetarParseError {
+        input: input.to_string(),
+        range,
+        expected,
+        suggestion,
+    }
+}
+
+/// The whitespace-delimited token starting at or containing `offset`.
+fn token_at(input: &str, offset: usize) -> Range<usize> {
+    let offset = offset.min(input.len());
+    let start = input[..offset].rfind(' ').map_or(0, |space| space + 1);
+    let start = if input[offset..].starts_with(' ') {
+        offset + 1
+    } else {
+        start
+    };
+    let end = input[start..]
+        .find(' ')
+        .map_or(input.len(), |space| start + space);
+    start..end
+}
+
+/// Common mistakes in hand-written and non-standard reports.
+fn suggest(token: &str, expected: ExpectedGroup) -> Option<&'static str> {
+    let all_digits = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit());
+    let number = token.parse::<u32>().ok().filter(|_| all_digits);
+    Some(match (expected, token) {
+        (_, "") => "the report is truncated",
+        (ExpectedGroup::Icao, _) => {
+            "reports start with the ICAO code, optionally after METAR/SPECI"
+        }
+        (ExpectedGroup::ObservationTime, _) => "observation time is written ddhhmmZ",
+        (ExpectedGroup::Pressure, _)
+            if token.len() == 4 && number.is_some_and(|n| (850..=1090).contains(&n)) =>
+        {
+            "QNH without Q prefix?"
+        }
+        (ExpectedGroup::Pressure, _)
+            if token.len() == 4 && number.is_some_and(|n| (2500..=3200).contains(&n)) =>
+        {
+            "altimeter without A prefix?"
+        }
+        (_, "NO") => "NOSIG written with a space?",
+        (_, "SKC") => "SKC is not decoded; NSC or CLR?",
+        (_, "CCA" | "CCB" | "CCC") => "Canadian correction code; COR expected",
+        _ if token.ends_with("KTS") => "the wind unit is KT, not KTS",
+        _ if token.ends_with('=') => "stray '=' inside the report",
+        _ if token.contains("XX") => "unreadable values are written as slashes",
+        _ if token.bytes().any(|b| b.is_ascii_lowercase()) => "groups are written in upper case",
+        _ if token.ends_with("KM")
+            && token[..token.len() - 2].bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            "visibility in kilometres; ICAO reports use metres, 9999 for 10 km or more"
+        }
+        _ if (token.starts_with('Q') || token.starts_with('A'))
+            && token.len() != 5
+            && token[1..].bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            "pressure is written with four digits"
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn parse_error(input: &str) -> MetarParseError {
+        Metar::from_str(input).unwrap_err()
+    }
+
+    #[test]
+    fn test_qnh_without_prefix() {
+        let error = parse_error("ENGM 161220Z 01008KT CAVOK 08/04 1013");
+        assert_eq!(error.token(), "1013");
+        assert_eq!(error.range, 33..37);
+        assert_eq!(error.expected, ExpectedGroup::Pressure);
+        assert_eq!(error.suggestion, Some("QNH without Q prefix?"));
+        assert_eq!(
+            error.render(),
+            "ENGM 161220Z 01008KT CAVOK 08/04 1013\n                                 ^^^^ pressure expected: QNH without Q prefix?"
+        );
+        assert_eq!(
+            error.to_string(),
+            "pressure expected, found '1013' at bytes 33..37 (QNH without Q prefix?)"
+        );
+    }
+
+    #[test]
+    fn test_header_errors() {
+        let error = parse_error("EN 161220Z 01008KT CAVOK 08/04 Q1013");
+        assert_eq!(error.expected, ExpectedGroup::Icao);
+
+        let error = parse_error("SPECI ENGM 1612Z 01008KT CAVOK 08/04 Q1013");
+        assert_eq!(error.expected, ExpectedGroup::ObservationTime);
+        assert_eq!(error.token(), "1612Z");
+    }
+
+    #[test]
+    fn test_unknown_supplementary_group() {
+        let error = parse_error("VEJR 161230Z 23002KT 5000 HZ SCT025 BKN100 28/24 Q1004 NO SIG");
+        assert_eq!(error.token(), "NO");
+        assert_eq!(error.expected, ExpectedGroup::Supplementary);
+        assert_eq!(error.suggestion, Some("NOSIG written with a space?"));
+    }
+
+    #[test]
+    fn test_unreadable_written_as_x() {
+        let error = parse_error("SVBC 161500Z 00000KT 9999 FEW016 32/XX Q1014");
+        assert_eq!(error.token(), "32/XX");
+        assert_eq!(error.expected, ExpectedGroup::Temperature);
+        assert_eq!(
+            error.suggestion,
+            Some("unreadable values are written as slashes")
+        );
+    }
+
+    #[test]
+    fn test_corpus_errors_point_into_input() {
+        for line in include_str!("../all_metars").lines() {
+            if let Err(error) = Metar::from_str(line) {
+                assert!(error.range.end <= error.input.len(), "{line}");
+                assert!(error.render().starts_with(&error.input));
+            }
+        }
+    }
+}
@@ -0,0 +1,442 @@
+use std::ops::Range;
+
+use nom::{
+    Finish, Parser,
+    bytes::complete::{tag, take},
+    character::complete::char,
+    combinator::opt,
+    sequence::preceded,
+};
+
+use crate::{
+    metar::{
+        Metar, MetarRef, missing_groups, nom_parse_metar, nom_report_type, unreported_temperature,
+    },
+    nato_mil_code::nom_nato_mil_code,
+    obscuration::{
+        DescribedObscuration, Obscuration, Visibility, VisibilityUnit, nom_cloud, nom_obscuration,
+        nom_present_weather, nom_recent_present_weather, nom_rvr, nom_vertical_visibility,
+        nom_visibility,
+    },
+    optional_data::OptionalData,
+    pressure::{Pressure, nom_pressure},
+    runway_state::{nom_runway_state, nom_snow_closed, nom_wind_shear},
+    sea_surface_indicator::nom_sea_surface_indicator,
+    temperature::nom_temperature_info,
+    trend::nom_trend_group,
+    units::{
+        timestamp::nom_metar_timestamp,
+        track::Track,
+        velocity::{VelocityUnit, WindVelocity},
+    },
+    wind::{Wind, WindDirection, nom_wind},
+};
+
+/// Result of [`Metar::parse_lenient`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LenientMetar {
+    /// Mandatory groups that could not be found are filled in as not
+    /// reported (`//`), the same way an automatic station reports a missing
+    /// sensor.
+    pub metar: Metar,
+    pub unparsed: Vec<UnparsedSpan>,
+}
+
+/// A token that the lenient parser skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnparsedSpan {
+    /// Byte offsets into [`Metar::raw`].
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// Runs `parser` and only accepts the result if it ends on a group boundary,
+/// so that a parser matching the start of an unknown token does not eat it.
+fn group<'a, O>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>,
+) -> Option<(&'a str, O)> {
+    let (rest, output) = parser.parse(input).ok()?;
+    let consumed = &input[..input.len() - rest.len()];
+    let at_boundary = !consumed.is_empty()
+        && (rest.is_empty() || rest.starts_with(' ') || consumed.ends_with(' '));
+    at_boundary.then_some((rest, output))
+}
+
+pub(crate) fn unreported_wind() -> Wind {
+    Wind {
+        dir: WindDirection::Heading(Track(OptionalData::Undefined)),
+        speed: WindVelocity {
+            velocity: OptionalData::Undefined,
+            gust: None,
+            unit: VelocityUnit::Knots,
+        },
+        varying: None,
+    }
+}
+
+pub(crate) fn unreported_obscuration<S>() -> DescribedObscuration<S> {
+    DescribedObscuration {
+        visibility: Visibility {
+            value: VisibilityUnit::Meters(OptionalData::Undefined),
+            direction: None,
+            ndv: false,
+        },
+        direction_visibility: None,
+        rvr: Vec::new(),
+        clouds: Vec::new(),
+        present_weather: Vec::new(),
+        vertical_visibility: None,
+    }
+}
+
+/// The report without its leading `METAR`/`SPECI` keyword.
+pub(crate) fn strip_report_type(input: &str) -> &str {
+    nom_report_type(input).map_or(input, |(rest, _)| rest)
+}
+
+/// Only the header (ICAO and observation time) is required; every other
+/// group is decoded where it can be found and unknown tokens are skipped.
+pub(crate) fn parse_lenient(input: &str) -> Result<LenientMetar, nom::error::Error<String>> {
+    if let Ok(("", metar)) = nom_parse_metar(input) {
+        return Ok(LenientMetar {
+            metar: metar.to_owned(),
+            unparsed: Vec::new(),
+        });
+    }
+
+    let (mut remaining, (report_type, icao, timestamp, corrected, auto)) = (
+        opt(nom_report_type).map(Option::unwrap_or_default),
+        take(4usize),
+        preceded(char(' '), nom_metar_timestamp),
+        opt(tag(" COR")).map(|c| c.is_some()),
+        opt(tag(" AUTO")).map(|a| a.is_some()),
+    )
+        .parse(input)
+        .finish()?;
+    let reference = timestamp.zoned().clone();
+
+    let mut wind = None;
+    let mut obscuration: Option<Obscuration<&str>> = None;
+    let mut visibility_found = false;
+    let mut temperature = None;
+    let mut pressure = None;
+    let mut recent_weather = None;
+    let mut wind_shear = Vec::new();
+    let mut runway_states = Vec::new();
+    let mut snow_closed = false;
+    let mut sea_surface_indicator = None;
+    let mut nato_mil_code = None;
+    let mut nosig = false;
+    let mut trends = Vec::new();
+    let mut remarks = None;
+    let mut unparsed = Vec::new();
+
+    loop {
+        remaining = remaining.trim_start_matches(' ');
+        if remaining.is_empty() {
+            break;
+        }
+        if let Some(rmk) = remaining.strip_prefix("RMK ") {
+            remarks = Some(rmk);
+            break;
+        }
+
+        let matched: Option<&str> = 'matched: {
+            if trends.is_empty() {
+                if wind.is_none()
+                    && let Some((rest, w)) = group(remaining, nom_wind)
+                {
+                    wind = Some(w);
+                    break 'matched Some(rest);
+                }
+                if obscuration.is_none()
+                    && let Some((rest, o)) = group(remaining, nom_obscuration)
+                {
+                    obscuration = Some(o);
+                    visibility_found = true;
+                    break 'matched Some(rest);
+                }
+                if obscuration.is_none()
+                    && let Some((rest, visibility)) = group(remaining, nom_visibility)
+                {
+                    obscuration = Some(Obscuration::Described(DescribedObscuration {
+                        visibility,
+                        ..unreported_obscuration()
+                    }));
+                    visibility_found = true;
+                    break 'matched Some(rest);
+                }
+                if let Some((rest, state)) = group(remaining, nom_runway_state) {
+                    runway_states.push(state);
+                    break 'matched Some(rest);
+                }
+                if let Some(rest) = extend_obscuration(remaining, &mut obscuration) {
+                    break 'matched Some(rest);
+                }
+                if temperature.is_none()
+                    && let Some((rest, t)) = group(remaining, nom_temperature_info)
+                {
+                    temperature = Some(t);
+                    break 'matched Some(rest);
+                }
+                if pressure.is_none()
+                    && let Some((rest, p)) = group(remaining, nom_pressure)
+                {
+                    pressure = Some(p);
+                    break 'matched Some(rest);
+                }
+                if recent_weather.is_none()
+                    && let Some((rest, r)) = group(remaining, nom_recent_present_weather)
+                {
+                    recent_weather = Some(r);
+                    break 'matched Some(rest);
+                }
+                if let Some((rest, mut ws)) = group(remaining, nom_wind_shear) {
+                    wind_shear.append(&mut ws);
+                    break 'matched Some(rest);
+                }
+                if !snow_closed && let Some((rest, _)) = group(remaining, nom_snow_closed) {
+                    snow_closed = true;
+                    break 'matched Some(rest);
+                }
+                if sea_surface_indicator.is_none()
+                    && let Some((rest, s)) = group(remaining, nom_sea_surface_indicator)
+                {
+                    sea_surface_indicator = Some(s);
+                    break 'matched Some(rest);
+                }
+                if !nosig && let Some((rest, _)) = group(remaining, tag("NOSIG")) {
+                    nosig = true;
+                    break 'matched Some(rest);
+                }
+                // Stands in for a group whose sensor is unavailable, which
+                // is already reported as missing when the group is absent.
+                if let Some((rest, _)) = group(remaining, tag("M")) {
+                    break 'matched Some(rest);
+                }
+            }
+            if nato_mil_code.is_none()
+                && let Some((rest, n)) =
+                    group(remaining, OptionalData::optional_field(nom_nato_mil_code))
+            {
+                nato_mil_code = Some(n);
+                break 'matched Some(rest);
+            }
+            if let Some((rest, t)) = group(remaining, |i| nom_trend_group(i, &reference)) {
+                trends.push(t);
+                break 'matched Some(rest);
+            }
+            None
+        };
+
+        remaining = match matched {
+            Some(rest) => rest,
+            None => {
+                let token_len = remaining.find(' ').unwrap_or(remaining.len());
+                let start = input.len() - remaining.len();
+                unparsed.push(UnparsedSpan {
+                    range: start..start + token_len,
+                    text: remaining[..token_len].to_string(),
+                });
+                &remaining[token_len..]
+            }
+        };
+    }
+
+    let obscuration =
+        obscuration.unwrap_or_else(|| Obscuration::Described(unreported_obscuration()));
+    let missing_groups = missing_groups(
+        wind.is_some(),
+        visibility_found,
+        &obscuration,
+        temperature.is_some(),
+        pressure.is_some(),
+    );
+    Ok(LenientMetar {
+        metar: MetarRef {
+            raw: input,
+            report_type,
+            icao,
+            timestamp,
+            corrected,
+            auto,
+            wind: wind.unwrap_or_else(unreported_wind),
+            obscuration,
+            temperature: temperature.unwrap_or_else(unreported_temperature),
+            pressure: pressure.unwrap_or(Pressure {
+                qnh: None,
+                altimeter: None,
+            }),
+            recent_weather,
+            wind_shear,
+            runway_states,
+            snow_closed,
+            nosig,
+            sea_surface_indicator,
+            trends,
+            nato_mil_code,
+            remarks,
+            missing_groups,
+        }
+        .to_owned(),
+        unparsed,
+    })
+}
+
+/// Picks up RVR, weather, cloud and vertical visibility groups that were
+/// separated from the visibility by an unknown token, or that are reported
+/// without any visibility at all.
+fn extend_obscuration<'a>(
+    input: &'a str,
+    obscuration: &mut Option<Obscuration<&'a str>>,
+) -> Option<&'a str> {
+    if matches!(obscuration, Some(Obscuration::Cavok)) {
+        return None;
+    }
+    let mut described = unreported_obscuration();
+    let rest = if let Some((rest, rvr)) = group(input, nom_rvr) {
+        described.rvr.push(rvr);
+        rest
+    } else if let Some((rest, weather)) = group(input, nom_present_weather) {
+        described.present_weather.push(weather);
+        rest
+    } else if let Some((rest, cloud)) = group(input, nom_cloud) {
+        described.clouds.push(cloud);
+        rest
+    } else if let Some((rest, vv)) = group(input, nom_vertical_visibility) {
+        described.vertical_visibility = Some(vv);
+        rest
+    } else {
+        return None;
+    };
+    match obscuration {
+        Some(Obscuration::Described(existing)) => {
+            existing.rvr.append(&mut described.rvr);
+            existing
+                .present_weather
+                .append(&mut described.present_weather);
+            existing.clouds.append(&mut described.clouds);
+            existing.vertical_visibility = described
+                .vertical_visibility
+                .or(existing.vertical_visibility.take());
+        }
+        _ => *obscuration = Some(Obscuration::Described(described)),
+    }
+    Some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_test::traced_test;
+
+    use crate::obscuration::Cloud;
+
+    #[test]
+    #[traced_test]
+    fn test_strict_metar_has_no_unparsed_spans() {
+        let input = "ENGM 161550Z 02010KT CAVOK 18/17 Q1013 NOSIG";
+        let parsed = Metar::parse_lenient(input).unwrap();
+        assert!(parsed.unparsed.is_empty());
+        assert!(parsed.metar.nosig);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_skips_pressure_without_unit() {
+        let input = "ENOA 161550Z 02010KT CAVOK 18/17 1013";
+        let parsed = Metar::parse_lenient(input).unwrap();
+        assert_eq!(
+            parsed.unparsed,
+            vec![UnparsedSpan {
+                range: 33..37,
+                text: "1013".to_string(),
+            }]
+        );
+        assert_eq!(&parsed.metar.raw[33..37], "1013");
+        assert_eq!(parsed.metar.obscuration, Obscuration::Cavok);
+        assert_eq!(parsed.metar.wind.speed.velocity, OptionalData::Data(10));
+        assert_eq!(parsed.metar.pressure.qnh, None);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_keeps_groups_after_unknown_token() {
+        let input = "URMM 161556Z 23003MPS 190V250 9999 QBB200 FEW/// BKN070CB 25/20 Q1007 TEMPO 0500 +TSRA";
+        let parsed = Metar::parse_lenient(input).unwrap();
+        assert_eq!(parsed.unparsed.len(), 1);
+        assert_eq!(parsed.unparsed[0].text, "QBB200");
+        let Obscuration::Described(described) = &parsed.metar.obscuration else {
+            panic!("expected described obscuration");
+        };
+        assert_eq!(described.clouds.len(), 2);
+        assert_eq!(parsed.metar.trends.len(), 1);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_fills_missing_mandatory_groups() {
+        let input = "KXYZ 161555Z AUTO 27008KT CLR A2992";
+        let parsed = Metar::parse_lenient(input).unwrap();
+        assert!(parsed.unparsed.is_empty());
+        assert_eq!(parsed.metar.temperature.temp, OptionalData::Undefined);
+        let Obscuration::Described(described) = &parsed.metar.obscuration else {
+            panic!("expected described obscuration");
+        };
+        assert_eq!(
+            described.visibility.value,
+            VisibilityUnit::Meters(OptionalData::Undefined)
+        );
+        assert_eq!(described.clouds, vec![Cloud::CLR]);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_requires_header() {
+        assert!(Metar::parse_lenient("not a metar").is_err());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_corpus_only_fails_on_header() {
+        for corpus in [
+            include_str!("../all_metars"),
+            include_str!("../na_metars.txt"),
+            include_str!("../test.metars2"),
+        ] {
+            for line in corpus.lines().filter(|l| !l.trim().is_empty()) {
+                match Metar::parse_lenient(line) {
+                    Ok(parsed) => {
+                        for span in &parsed.unparsed {
+                            assert_eq!(&parsed.metar.raw[span.range.clone()], span.text);
+                        }
+                    }
+                    Err(e) => {
+                        // Only a broken header may fail the lenient parse.
+                        let header = (take(4usize), preceded(char(' '), nom_metar_timestamp))
+                            .parse(line.trim());
+                        assert!(header.is_err(), "Failed to parse line '{}': {:?}", line, e);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_report_type_and_missing_marker() {
+        let input = "SPECI KDMA 161538Z 06008KT 10SM BKN100 M A3008 XYZ";
+        let parsed = Metar::parse_lenient(input).unwrap();
+        assert_eq!(parsed.metar.report_type, crate::metar::ReportType::Speci);
+        assert_eq!(parsed.metar.icao, "KDMA");
+        assert_eq!(
+            parsed.metar.missing_groups,
+            [crate::metar::MissingGroup::Temperature]
+        );
+        assert_eq!(parsed.unparsed.len(), 1);
+        assert_eq!(parsed.unparsed[0].text, "XYZ");
+    }
+}
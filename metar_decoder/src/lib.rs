@@ -1,9 +1,33 @@
+//! Decoder for METAR and TAF reports.
+//!
+//! With the `serde` feature every public report type implements
+//! `Serialize`/`Deserialize`. Structs use their field names, enum variants
+//! their Rust names, timestamps are RFC 9557 strings and a field reported as
+//! slashes is `"Unreadable"` (see [`optional_data::OptionalData`]). The JSON
+//! for the corpus files is pinned by the snapshots in `snapshots/`.
+//!
+//! Reports can be put together with [`builder::MetarBuilder`]. The
+//! `proptest` feature adds strategies for arbitrary valid reports in
+//! [`arbitrary`].
+
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+pub mod builder;
+pub mod derived;
+pub mod error;
+pub mod lenient;
 pub mod metar;
 pub mod nato_mil_code;
 pub mod obscuration;
 pub mod optional_data;
+pub mod plain_language;
 pub mod pressure;
+pub mod remarks;
+pub mod runway_state;
 pub mod sea_surface_indicator;
+#[cfg(all(test, feature = "serde"))]
+mod snapshot;
+pub mod taf;
 pub mod temperature;
 pub mod trend;
 pub mod units;
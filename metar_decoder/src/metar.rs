@@ -1,36 +1,46 @@
 use std::{
-    fmt::Debug,
+    fmt::{Debug, Display},
     io::{BufRead, BufReader, Read},
     str::FromStr,
 };
 
 use itertools::Itertools;
+use jiff::{Zoned, tz::TimeZone};
 use nom::{
-    AsBytes, Finish, IResult, Parser,
-    branch::permutation,
+    Finish, IResult, Parser,
+    branch::{alt, permutation},
     bytes::complete::tag,
     character::complete::{char, space0},
-    combinator::{opt, rest},
-    error::ParseError,
-    sequence::preceded,
+    combinator::{eof, opt, peek, rest, success, value, verify},
+    multi::many0,
+    sequence::{preceded, terminated},
 };
 use tracing::warn;
 
 use crate::{
+    error::{MetarParseError, diagnose},
+    lenient::{self, LenientMetar, unreported_obscuration, unreported_wind},
     nato_mil_code::{NatoMilCode, nom_nato_mil_code},
-    obscuration::{Obscuration, PresentWeather, nom_obscuration, nom_recent_present_weather},
+    obscuration::{
+        Obscuration, PresentWeather, nom_obscuration, nom_obscuration_without_visibility,
+        nom_recent_present_weather,
+    },
     optional_data::OptionalData,
     pressure::{Pressure, nom_pressure},
+    remarks::{Remarks, parse_remarks},
+    runway_state::{RunwayState, WindShear, nom_runway_states, nom_snow_closed, nom_wind_shear},
     sea_surface_indicator::{SeaSurfaceIndicator, nom_sea_surface_indicator},
     temperature::{TemperatureInfo, nom_temperature_info},
-    trend::{Trend, nom_becoming},
-    units::timestamp::{Timestamp, nom_metar_timestamp},
+    trend::{TrendGroup, nom_trend_group},
+    units::timestamp::{Timestamp, nom_metar_timestamp, nom_metar_timestamp_with_zone},
     wind::{Wind, nom_wind},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metar {
     pub raw: String,
+    pub report_type: ReportType,
     pub icao: String,
     pub timestamp: Timestamp,
     pub corrected: bool,
@@ -40,133 +50,505 @@ pub struct Metar {
     pub temperature: TemperatureInfo,
     pub pressure: Pressure,
     pub recent_weather: Option<Vec<PresentWeather>>,
+    pub wind_shear: Vec<WindShear>,
+    pub runway_states: Vec<RunwayState>,
+    pub snow_closed: bool,
     pub nosig: bool,
     pub sea_surface_indicator: Option<SeaSurfaceIndicator>,
-    pub tempo: Option<Trend>,
-    pub becoming: Option<Trend>,
+    pub trends: Vec<TrendGroup>,
     pub nato_mil_code: Option<OptionalData<NatoMilCode, 3>>,
-    pub remarks: Option<String>,
+    pub remarks: Option<Remarks>,
+    /// Mandatory groups whose sensor is unavailable. Their fields hold
+    /// unreadable values.
+    pub missing_groups: Vec<MissingGroup>,
+}
+
+/// A [`Metar`] that borrows its text from the report instead of copying it,
+/// for decoding large batches where allocation would dominate. The remarks
+/// are kept as text and decoded on demand by [`MetarRef::remarks`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetarRef<'a> {
+    pub raw: &'a str,
+    pub report_type: ReportType,
+    pub icao: &'a str,
+    pub timestamp: Timestamp,
+    pub corrected: bool,
+    pub auto: bool,
+    pub wind: Wind,
+    pub obscuration: Obscuration<&'a str>,
+    pub temperature: TemperatureInfo,
+    pub pressure: Pressure,
+    pub recent_weather: Option<Vec<PresentWeather>>,
+    pub wind_shear: Vec<WindShear<&'a str>>,
+    pub runway_states: Vec<RunwayState<&'a str>>,
+    pub snow_closed: bool,
+    pub nosig: bool,
+    pub sea_surface_indicator: Option<SeaSurfaceIndicator>,
+    pub trends: Vec<TrendGroup<&'a str>>,
+    pub nato_mil_code: Option<OptionalData<NatoMilCode, 3>>,
+    /// The text after `RMK`.
+    pub remarks: Option<&'a str>,
+    pub missing_groups: Vec<MissingGroup>,
+}
+
+/// Whether the report is a routine `METAR` or a special `SPECI`. A report
+/// without the keyword is routine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReportType {
+    #[default]
+    Metar,
+    Speci,
+}
+
+impl Display for ReportType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReportType::Metar => "METAR",
+            ReportType::Speci => "SPECI",
+        })
+    }
+}
+
+/// A mandatory group that an automatic station left out or replaced by `M`
+/// because its sensor is unavailable. The sky condition counts as missing
+/// whenever a report without CAVOK has no cloud or vertical visibility group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MissingGroup {
+    Wind,
+    Visibility,
+    SkyCondition,
+    Temperature,
+    Pressure,
+}
+
+/// A `NIL` report: the station sent no observation for this time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NilReport {
+    pub report_type: ReportType,
+    pub icao: String,
+    pub timestamp: Timestamp,
+}
+
+/// Either a decoded observation or a `NIL` placeholder.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetarReport {
+    Observation(Box<Metar>),
+    Nil(NilReport),
+}
+
+/// Prints the decoded report as canonical ICAO METAR text. `raw` is not
+/// used, so the output may differ from the original in spacing and group
+/// order.
+impl Display for Metar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut groups = Vec::new();
+        if self.report_type == ReportType::Speci {
+            groups.push(self.report_type.to_string());
+        }
+        groups.extend([self.icao.clone(), self.timestamp.to_string()]);
+        if self.corrected {
+            groups.push("COR".to_string());
+        }
+        if self.auto {
+            groups.push("AUTO".to_string());
+        }
+        // Only AUTO reports may leave missing groups out; others mark them `M`.
+        let missing = if self.auto {
+            None
+        } else {
+            Some("M".to_string())
+        };
+        let group_or_missing = |group, text: String| match self.is_missing(group) {
+            true => missing.clone(),
+            false => Some(text),
+        };
+        groups.extend(group_or_missing(MissingGroup::Wind, self.wind.to_string()));
+        match &self.obscuration {
+            Obscuration::Described(described) if self.is_missing(MissingGroup::Visibility) => {
+                let sky = described.groups_without_visibility().collect_vec();
+                groups.extend(missing.clone());
+                if sky.is_empty() {
+                    groups.extend(missing.clone());
+                }
+                groups.extend(sky);
+            }
+            obscuration => groups.push(obscuration.to_string()),
+        }
+        groups.extend(group_or_missing(
+            MissingGroup::Temperature,
+            self.temperature.to_string(),
+        ));
+        groups.extend(group_or_missing(
+            MissingGroup::Pressure,
+            self.pressure.to_string(),
+        ));
+        groups.extend(
+            self.recent_weather
+                .iter()
+                .flatten()
+                .map(|weather| format!("RE{weather}")),
+        );
+        groups.extend(self.wind_shear.iter().map(ToString::to_string));
+        groups.extend(self.sea_surface_indicator.iter().map(ToString::to_string));
+        groups.extend(self.runway_states.iter().map(ToString::to_string));
+        if self.snow_closed {
+            groups.push("R/SNOCLO".to_string());
+        }
+        groups.extend(self.nato_mil_code.iter().map(ToString::to_string));
+        if self.nosig {
+            groups.push("NOSIG".to_string());
+        }
+        groups.extend(self.trends.iter().map(ToString::to_string));
+        if let Some(remarks) = &self.remarks {
+            groups.push(format!("RMK {remarks}"));
+        }
+        f.write_str(&groups.join(" "))
+    }
 }
 
 impl FromStr for Metar {
-    type Err = nom::error::Error<String>;
+    type Err = MetarParseError;
 
     #[tracing::instrument(name = "metar_parse")]
-    fn from_str(mut s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Metar::parse_at(s, &default_reference())
+    }
+}
+
+/// Reports carry only the day of the month, so they are resolved against
+/// the current time. The hour of slack allows for clock skew.
+fn default_reference() -> Zoned {
+    let mut now = Zoned::now().with_time_zone(TimeZone::UTC);
+    now += jiff::SignedDuration::from_hours(1);
+    now
+}
+
+impl Display for NilReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.report_type == ReportType::Speci {
+            write!(f, "{} ", self.report_type)?;
+        }
+        write!(f, "{} {} NIL", self.icao, self.timestamp)
+    }
+}
+
+/// Fails with the parser's own error, since anything but `NIL` after the
+/// header is an ordinary report rather than a mistake.
+impl FromStr for NilReport {
+    type Err = nom::error::Error<String>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_end_matches('=');
+        let (_, nil) = nom_nil_report(s)
+            .finish()
+            .map_err(|e| nom::error::Error::new(e.input.to_string(), e.code))?;
+        Ok(nil)
+    }
+}
+
+impl FromStr for MetarReport {
+    type Err = MetarParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<NilReport>() {
+            Ok(nil) => Ok(MetarReport::Nil(nil)),
+            Err(_) => Ok(MetarReport::Observation(Box::new(s.parse()?))),
+        }
+    }
+}
+
+impl Metar {
+    /// True if the group was left out or reported as `M`.
+    pub fn is_missing(&self, group: MissingGroup) -> bool {
+        self.missing_groups.contains(&group)
+    }
+
+    /// Parses a METAR, resolving the observation day against `reference`
+    /// instead of the current time. Used when decoding stored reports.
+    pub fn parse_at(s: &str, reference: &Zoned) -> Result<Self, MetarParseError> {
+        MetarRef::parse_at(s, reference).map(|metar| metar.to_owned())
+    }
+
+    /// Parses a METAR without failing on unknown tokens. Only the ICAO and
+    /// observation time are required, everything else is decoded where
+    /// possible and the skipped tokens are returned with their byte offsets.
+    pub fn parse_lenient(s: &str) -> Result<LenientMetar, MetarParseError> {
+        let s = s.trim().trim_end_matches(" RMK").trim_end_matches('=');
+        lenient::parse_lenient(s).map_err(|e| {
+            let error = diagnose(s, s.len() - e.input.len());
+            warn!(%error, "Failed to parse METAR header");
+            error
+        })
+    }
+}
+
+impl<'a> MetarRef<'a> {
+    /// Parses a METAR without copying any of its text, resolving the
+    /// observation day against the current time.
+    pub fn parse(s: &'a str) -> Result<Self, MetarParseError> {
+        MetarRef::parse_at(s, &default_reference())
+    }
+
+    /// Like [`Metar::parse_at`], but borrowing from `s`.
+    pub fn parse_at(mut s: &'a str, reference: &Zoned) -> Result<Self, MetarParseError> {
         s = s.trim();
         s = s.trim_end_matches(" RMK"); // Military airports in Switzerland sometimes end with RMK but have no remarks.
         s = s.trim_end_matches('='); // Nonsense = at the end
-        let (rest, metar) = nom_parse_metar(s).finish().inspect_err(|e| {
-            warn!(?e);
-        })?;
-        if !rest.is_empty() {
-            warn!(?rest, "Unparsed input remains");
-            Err(nom::error::Error::new(
-                rest.to_string(),
-                nom::error::ErrorKind::NonEmpty,
-            ))
-        } else {
-            Ok(metar)
+        let rest = match nom_parse_metar_at(s, reference).finish() {
+            Ok(("", metar)) => return Ok(metar),
+            Ok((rest, _)) => rest,
+            Err(e) => e.input,
+        };
+        let error = diagnose(s, s.len() - rest.len());
+        warn!(%error, "Failed to parse METAR");
+        Err(error)
+    }
+
+    /// True if the group was left out or reported as `M`.
+    pub fn is_missing(&self, group: MissingGroup) -> bool {
+        self.missing_groups.contains(&group)
+    }
+
+    /// Decodes the remarks, resolving their times against the observation.
+    pub fn remarks(&self) -> Option<Remarks> {
+        self.remarks
+            .map(|remarks| parse_remarks(remarks, self.timestamp.zoned()))
+    }
+
+    pub fn to_owned(&self) -> Metar {
+        Metar {
+            raw: self.raw.to_string(),
+            report_type: self.report_type,
+            icao: self.icao.to_string(),
+            timestamp: self.timestamp.clone(),
+            corrected: self.corrected,
+            auto: self.auto,
+            wind: self.wind.clone(),
+            obscuration: self.obscuration.to_owned(),
+            temperature: self.temperature,
+            pressure: self.pressure,
+            recent_weather: self.recent_weather.clone(),
+            wind_shear: self.wind_shear.iter().map(|ws| ws.to_owned()).collect(),
+            runway_states: self
+                .runway_states
+                .iter()
+                .map(|state| state.to_owned())
+                .collect(),
+            snow_closed: self.snow_closed,
+            nosig: self.nosig,
+            sea_surface_indicator: self.sea_surface_indicator.clone(),
+            trends: self.trends.iter().map(|trend| trend.to_owned()).collect(),
+            nato_mil_code: self.nato_mil_code.clone(),
+            remarks: self.remarks(),
+            missing_groups: self.missing_groups.clone(),
         }
     }
 }
 
-#[allow(unused)]
-pub(crate) fn debug<P, I, O, E>(
-    name: &'static str,
-    mut parser: P,
-) -> impl FnMut(I) -> IResult<I, O, E>
-where
-    P: Parser<I, Output = O, Error = E>,
-    I: Clone + AsBytes + Debug,
-    E: ParseError<I> + Debug,
-    O: Debug,
-{
-    move |input: I| {
-        eprintln!("→ {name} | input: {}", preview(&input));
-        let res = parser.parse(input.clone());
-        match &res {
-            Ok((rest, out)) => {
-                let in_len = input.as_bytes().len();
-                let rest_len = rest.as_bytes().len();
-                let consumed = in_len.saturating_sub(rest_len);
-                eprintln!(
-                    "← {name} | OK    | out={:?} | rest: {} | consumed: {}",
-                    short_dbg(out),
-                    preview(rest),
-                    consumed
-                );
-            }
-            Err(nom::Err::Error(e)) => {
-                eprintln!("← {name} | ERROR | {e:?}");
-            }
-            Err(nom::Err::Failure(e)) => {
-                eprintln!("← {name} | FAIL  | {e:?}");
-            }
-            Err(nom::Err::Incomplete(needed)) => {
-                eprintln!("← {name} | INCOMPLETE | needed: {needed:?}");
-            }
+pub(crate) fn nom_parse_metar(input: &str) -> IResult<&str, MetarRef<'_>> {
+    nom_parse_metar_at(input, &default_reference())
+}
+
+pub(crate) fn nom_report_type(input: &str) -> IResult<&str, ReportType> {
+    terminated(
+        alt((
+            value(ReportType::Metar, tag("METAR")),
+            value(ReportType::Speci, tag("SPECI")),
+        )),
+        char(' '),
+    )
+    .parse(input)
+}
+
+fn nom_nil_report(input: &str) -> IResult<&str, NilReport> {
+    (
+        opt(nom_report_type).map(Option::unwrap_or_default),
+        nom::bytes::complete::take(4usize),
+        preceded(char(' '), nom_metar_timestamp),
+        preceded(char(' '), tag("NIL")),
+        eof,
+    )
+        .map(|(report_type, icao, timestamp, _, _)| NilReport {
+            report_type,
+            icao: icao.to_string(),
+            timestamp,
+        })
+        .parse(input)
+}
+
+/// A standalone `M`, which US automatic stations send in place of a group
+/// whose sensor is unavailable.
+fn nom_missing_marker(input: &str) -> IResult<&str, ()> {
+    value(
+        (),
+        preceded(
+            opt(char(' ')),
+            terminated(char('M'), peek(alt((tag(" "), eof)))),
+        ),
+    )
+    .parse(input)
+}
+
+/// Accepts a group reported as `M`, or, in `AUTO` reports, left out.
+fn nom_missing_or_omitted<'a>(
+    auto: bool,
+) -> impl Parser<&'a str, Output = (), Error = nom::error::Error<&'a str>> {
+    alt((nom_missing_marker, verify(success(()), move |_| auto)))
+}
+
+/// Runs `parser`, or accepts the group as missing if it is reported as `M`
+/// or, in `AUTO` reports, left out.
+fn nom_or_missing<'a, O: Clone>(
+    parser: impl Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>,
+    auto: bool,
+) -> impl Parser<&'a str, Output = Option<O>, Error = nom::error::Error<&'a str>> {
+    alt((parser.map(Some), value(None, nom_missing_or_omitted(auto))))
+}
+
+pub(crate) struct MandatoryGroups<'a> {
+    pub(crate) obscuration: Option<Obscuration<&'a str>>,
+    pub(crate) visibility_missing: bool,
+    pub(crate) temperature: Option<TemperatureInfo>,
+    pub(crate) pressure: Option<Pressure>,
+}
+
+/// Visibility, weather and cloud, temperature and pressure. Reports with all
+/// of them may list them in any order; otherwise they must come in the
+/// usual order, with missing groups reported as `M` or, in `AUTO` reports,
+/// left out. The obscuration parsers may consume the space after their last
+/// group, so the space before the following groups is optional.
+fn nom_mandatory_groups(input: &str, auto: bool) -> IResult<&str, MandatoryGroups<'_>> {
+    let complete = permutation((
+        preceded(char(' '), nom_obscuration),
+        preceded(opt(char(' ')), nom_temperature_info),
+        preceded(char(' '), nom_pressure),
+    ))
+    .map(|(obscuration, temperature, pressure)| MandatoryGroups {
+        obscuration: Some(obscuration),
+        visibility_missing: false,
+        temperature: Some(temperature),
+        pressure: Some(pressure),
+    });
+    // An `M` after a visibility without sky condition stands for the sky.
+    let with_visibility = |input| {
+        let (input, obscuration) = preceded(char(' '), nom_obscuration).parse(input)?;
+        let (input, _) = match sky_reported(&obscuration) {
+            true => (input, None),
+            false => opt(nom_missing_marker).parse(input)?,
+        };
+        Ok((input, (Some(obscuration), false)))
+    };
+    let without_visibility = (
+        nom_missing_or_omitted(auto),
+        nom_or_missing(
+            preceded(opt(char(' ')), nom_obscuration_without_visibility),
+            auto,
+        ),
+    )
+        .map(|(_, sky)| (sky.map(Obscuration::Described), true));
+    let incomplete = (
+        alt((with_visibility, without_visibility)),
+        nom_or_missing(preceded(opt(char(' ')), nom_temperature_info), auto),
+        nom_or_missing(preceded(opt(char(' ')), nom_pressure), auto),
+    )
+        .map(
+            |((obscuration, visibility_missing), temperature, pressure)| MandatoryGroups {
+                obscuration,
+                visibility_missing,
+                temperature,
+                pressure,
+            },
+        );
+    alt((complete, incomplete)).parse(input)
+}
+
+fn sky_reported<S>(obscuration: &Obscuration<S>) -> bool {
+    match obscuration {
+        Obscuration::Cavok => true,
+        Obscuration::Described(described) => {
+            !described.clouds.is_empty() || described.vertical_visibility.is_some()
         }
-        res
     }
 }
 
-fn preview<I: AsBytes>(i: &I) -> String {
-    let b = i.as_bytes();
-    let take = core::cmp::min(48, b.len());
-    let head = String::from_utf8_lossy(&b[..take]);
-    let ell = if b.len() > take { "…" } else { "" };
-    format!("\"{head}{ell}\" (len {})", b.len())
+/// Lists the missing groups of a report, given which groups were found.
+pub(crate) fn missing_groups<S>(
+    wind_found: bool,
+    visibility_found: bool,
+    obscuration: &Obscuration<S>,
+    temperature_found: bool,
+    pressure_found: bool,
+) -> Vec<MissingGroup> {
+    let sky_found = sky_reported(obscuration);
+    [
+        (wind_found, MissingGroup::Wind),
+        (visibility_found, MissingGroup::Visibility),
+        (sky_found, MissingGroup::SkyCondition),
+        (temperature_found, MissingGroup::Temperature),
+        (pressure_found, MissingGroup::Pressure),
+    ]
+    .into_iter()
+    .filter(|(found, _)| !found)
+    .map(|(_, group)| group)
+    .collect()
 }
 
-fn short_dbg<T: Debug>(t: &T) -> String {
-    let s = format!("{t:?}");
-    const MAX: usize = 120;
-    if s.len() > MAX {
-        format!("{}…", &s[..MAX])
-    } else {
-        s
+pub(crate) fn unreported_temperature() -> TemperatureInfo {
+    TemperatureInfo {
+        temp: OptionalData::Undefined,
+        dew_point: OptionalData::Undefined,
     }
 }
 
-fn nom_parse_metar(input: &str) -> IResult<&str, Metar> {
+pub(crate) fn nom_parse_metar_at<'a>(
+    input: &'a str,
+    reference: &Zoned,
+) -> IResult<&'a str, MetarRef<'a>> {
+    let (remaining, (report_type, icao, timestamp, corrected, auto)) = (
+        opt(nom_report_type).map(Option::unwrap_or_default),
+        nom::bytes::complete::take(4usize),
+        preceded(char(' '), |i| {
+            nom_metar_timestamp_with_zone(i, &mut reference.clone())
+        }),
+        opt(tag(" COR")),
+        opt(tag(" AUTO")),
+    )
+        .parse(input)?;
     let (
-        rest,
+        remaining,
         (
-            icao,
-            timestamp,
-            corrected,
-            auto,
             wind,
-            (obscuration, temperature, pressure),
+            mandatory,
             recent_weather,
+            wind_shear,
             sea_surface_indicator,
+            runway_states,
+            snow_closed,
             nato_mil_code,
             nosig,
-            becoming,
-            tempo,
-            remark,
         ),
     ) = (
-        nom::bytes::complete::take(4usize),
-        preceded(char(' '), nom_metar_timestamp),
-        opt(tag(" COR")),
-        opt(tag(" AUTO")),
-        preceded(char(' '), nom_wind),
-        permutation((
-            preceded(char(' '), nom_obscuration),
-            preceded(opt(char(' ')), nom_temperature_info),
-            preceded(char(' '), nom_pressure),
-        )),
+        nom_or_missing(preceded(char(' '), nom_wind), auto.is_some()),
+        |i| nom_mandatory_groups(i, auto.is_some()),
         preceded(space0, opt(nom_recent_present_weather)),
+        opt(preceded(space0, nom_wind_shear)).map(Option::unwrap_or_default),
         preceded(space0, opt(nom_sea_surface_indicator)),
+        opt(preceded(space0, nom_runway_states)).map(Option::unwrap_or_default),
+        opt(preceded(space0, nom_snow_closed)).map(|s| s.is_some()),
         opt(preceded(
             space0,
             OptionalData::optional_field(nom_nato_mil_code),
         )),
         opt(preceded(space0, tag("NOSIG"))),
-        opt(preceded((space0, tag("BECMG")), nom_becoming)),
-        opt(preceded((space0, tag("TEMPO")), nom_becoming)),
+    )
+        .parse(remaining)?;
+    let reference = timestamp.zoned().clone();
+    let (rest, (trends, remark)) = (
+        many0(preceded(space0, |i| nom_trend_group(i, &reference))),
         opt(preceded(
             (space0, tag("RMK ")),
             rest.map_res(|s| match s {
@@ -175,26 +557,43 @@ fn nom_parse_metar(input: &str) -> IResult<&str, Metar> {
             }),
         )),
     )
-        .parse(input)?;
+        .parse(remaining)?;
+    let obscuration = mandatory
+        .obscuration
+        .unwrap_or_else(|| Obscuration::Described(unreported_obscuration()));
+    let missing_groups = missing_groups(
+        wind.is_some(),
+        !mandatory.visibility_missing,
+        &obscuration,
+        mandatory.temperature.is_some(),
+        mandatory.pressure.is_some(),
+    );
     Ok((
         rest,
-        Metar {
-            raw: input.to_string(),
-            icao: icao.to_string(),
+        MetarRef {
+            raw: input,
+            report_type,
+            icao,
             timestamp,
             corrected: corrected.is_some(),
             auto: auto.is_some(),
-            wind,
+            wind: wind.unwrap_or_else(unreported_wind),
             obscuration,
-            temperature,
-            pressure,
+            temperature: mandatory.temperature.unwrap_or_else(unreported_temperature),
+            pressure: mandatory.pressure.unwrap_or(Pressure {
+                qnh: None,
+                altimeter: None,
+            }),
             recent_weather,
+            wind_shear,
+            runway_states,
+            snow_closed,
             nosig: nosig.is_some(),
             sea_surface_indicator,
-            becoming,
-            tempo,
+            trends,
             nato_mil_code,
-            remarks: remark.map(str::to_string),
+            remarks: remark,
+            missing_groups,
         },
     ))
 }
@@ -208,7 +607,7 @@ fn parse_metars<R: Read>(input: R) -> Result<Vec<(String, Metar)>, nom::error::E
         .map(|s| s.trim().to_owned())
         .map(|m| -> Result<(String, Metar), nom::error::Error<String>> {
             let (rest, metar) = nom_parse_metar(&m).finish()?;
-            Ok((rest.to_string(), metar))
+            Ok((rest.to_string(), metar.to_owned()))
         })
         .try_collect()
 }
@@ -218,6 +617,11 @@ mod tests {
     use super::*;
     use tracing_test::traced_test;
 
+    use crate::{
+        obscuration::{Cloud, VisibilityUnit},
+        trend::TrendKind,
+    };
+
     #[test]
     #[traced_test]
     fn test_parse() {
@@ -276,6 +680,290 @@ mod tests {
         Metar::from_str(input).unwrap();
     }
 
+    #[test]
+    #[traced_test]
+    fn test_runway_state_and_wind_shear() {
+        let input = "ENGM 161220Z 01008KT 9999 -SN BKN010 M02/M04 Q1002 WS R19R R01L/490195 R01R/CLRD// NOSIG";
+        let metar = Metar::from_str(input).unwrap();
+        assert_eq!(metar.wind_shear, vec![WindShear::Runway("19R".to_string())]);
+        assert_eq!(metar.runway_states.len(), 2);
+        assert_eq!(metar.runway_states[1].runway, "01R");
+        assert!(metar.nosig);
+        assert!(!metar.snow_closed);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_snow_closed() {
+        let input = "ENEV 161220Z 27025G40KT 0300 +SN VV002 M05/M06 Q0985 R/SNOCLO";
+        let metar = Metar::from_str(input).unwrap();
+        assert!(metar.snow_closed);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_multiple_trends() {
+        let input = "EKCH 161520Z 27012KT 9999 FEW025 17/11 Q1012 TEMPO SHRA BKN014 BECMG FM1600 TL1700 32008KT";
+        let metar = Metar::from_str(input).unwrap();
+        let kinds = metar.trends.iter().map(|t| t.kind).collect_vec();
+        assert_eq!(kinds, vec![TrendKind::Temporary, TrendKind::Becoming]);
+        let becoming = &metar.trends[1];
+        assert_eq!(becoming.from.as_ref().unwrap().zoned().hour(), 16);
+        assert_eq!(becoming.until.as_ref().unwrap().zoned().hour(), 17);
+        assert!(becoming.conditions.wind.is_some());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_out_of_range_trend_time() {
+        let input = "ENGM 161220Z 01008KT 9999 FEW030 10/05 Q1013 BECMG FM2599 3000";
+        assert!(Metar::from_str(input).is_err());
+        let parsed = Metar::parse_lenient(input).unwrap();
+        assert_eq!(parsed.unparsed[0].text, "FM2599");
+        assert_eq!(parsed.metar.trends.len(), 1);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_out_of_range_remark_time() {
+        for remarks in ["PK WND 30039/99", "WSHFT 7777"] {
+            let input =
+                format!("KJFK 161251Z 31015KT 10SM FEW250 22/10 A3001 RMK AO2 {remarks} SLP164");
+            let metar = Metar::from_str(&input).unwrap();
+            let parsed_remarks = metar.remarks.as_ref().unwrap();
+            assert_eq!(parsed_remarks.free_text().collect_vec(), [remarks]);
+            let parsed = Metar::parse_lenient(&input).unwrap();
+            assert!(parsed.unparsed.is_empty());
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_trend_with_nato_mil_code() {
+        let input = "ETNW 161520Z 25006KT 9999 SCT020 BKN080 18/15 Q1010 BLU BLU TEMPO YLO";
+        let metar = Metar::from_str(input).unwrap();
+        assert!(metar.trends[0].nato_mil_code.is_some());
+    }
+
+    #[test]
+    fn test_report_type_keyword() {
+        let speci =
+            Metar::from_str("SPECI ENGM 161235Z 01008KT 0800 FG VV002 M02/M04 Q1002").unwrap();
+        assert_eq!(speci.report_type, ReportType::Speci);
+        assert_eq!(speci.icao, "ENGM");
+        assert!(speci.to_string().starts_with("SPECI ENGM 161235Z"));
+
+        let metar = Metar::from_str("METAR ENGM 161220Z 01008KT CAVOK 08/04 Q1026").unwrap();
+        assert_eq!(metar.report_type, ReportType::Metar);
+        assert_eq!(metar.to_string(), "ENGM 161220Z 01008KT CAVOK 08/04 Q1026");
+    }
+
+    #[test]
+    fn test_nil_report() {
+        let report = MetarReport::from_str("METAR ENRM 161220Z NIL=").unwrap();
+        let MetarReport::Nil(nil) = report else {
+            panic!("{report:?}")
+        };
+        assert_eq!(nil.icao, "ENRM");
+        assert_eq!(nil.report_type, ReportType::Metar);
+        assert_eq!(nil.to_string(), "ENRM 161220Z NIL");
+        assert!(Metar::from_str("ENRM 161220Z NIL").is_err());
+
+        let report = MetarReport::from_str("ENGM 161220Z 01008KT CAVOK 08/04 Q1026").unwrap();
+        assert!(matches!(report, MetarReport::Observation(_)));
+    }
+
+    #[test]
+    fn test_missing_groups() {
+        let metar = Metar::from_str("KHHG 251455Z AUTO 10SM RMK AO2 PWINO PNO TSNO").unwrap();
+        assert_eq!(
+            metar.missing_groups,
+            [
+                MissingGroup::Wind,
+                MissingGroup::SkyCondition,
+                MissingGroup::Temperature,
+                MissingGroup::Pressure
+            ]
+        );
+        assert_eq!(metar.temperature.temp, OptionalData::Undefined);
+        assert_eq!(
+            metar.to_string(),
+            "KHHG 251455Z AUTO 10SM RMK AO2 PWINO PNO TSNO"
+        );
+
+        let metar = Metar::from_str("K0R0 161535Z AUTO VRB05KT CLR 34/26 A3005 RMK AO2").unwrap();
+        assert_eq!(metar.missing_groups, [MissingGroup::Visibility]);
+        assert_eq!(
+            metar.to_string(),
+            "K0R0 161535Z AUTO VRB05KT CLR 34/26 A3005 RMK AO2"
+        );
+    }
+
+    #[test]
+    fn test_missing_markers() {
+        let metar = Metar::from_str("KQWX 221255Z AUTO 21003KT 10SM M 09/M02 A2987").unwrap();
+        assert_eq!(metar.missing_groups, [MissingGroup::SkyCondition]);
+
+        let metar = Metar::from_str("KDMA 161538Z 06008KT 10SM BKN100 OVC160 M A3008").unwrap();
+        assert_eq!(metar.missing_groups, [MissingGroup::Temperature]);
+        assert_eq!(
+            metar.pressure.altimeter.unwrap().value,
+            OptionalData::Data(3008)
+        );
+        assert_eq!(
+            metar.to_string(),
+            "KDMA 161538Z 06008KT 10SM BKN100 OVC160 M A3008"
+        );
+
+        let metar =
+            Metar::from_str("K1CM 161555Z AUTO 11009KT 10SM BKN011 OVC016 11/09 M").unwrap();
+        assert_eq!(metar.missing_groups, [MissingGroup::Pressure]);
+    }
+
+    #[test]
+    fn test_only_auto_reports_may_leave_groups_out() {
+        assert!(Metar::from_str("KXYZ 161555Z").is_err());
+        assert!(Metar::from_str("KXYZ 161555Z 27008KT 10SM CLR A2992").is_err());
+        let metar = Metar::from_str("KXYZ 161555Z AUTO").unwrap();
+        assert_eq!(metar.missing_groups.len(), 5);
+    }
+
+    fn assert_round_trip(corpus: &str) {
+        let mut checked = 0;
+        for line in corpus.lines() {
+            let Ok(metar) = Metar::from_str(line) else {
+                continue;
+            };
+            let text = metar.to_string();
+            let mut reparsed = Metar::from_str(&text)
+                .unwrap_or_else(|e| panic!("'{text}' (from '{line}') does not parse: {e:?}"));
+            assert_eq!(reparsed.to_string(), text, "from '{line}'");
+            reparsed.raw = metar.raw.clone();
+            assert_eq!(reparsed, metar, "'{text}' (from '{line}')");
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn test_round_trip_all_metars() {
+        assert_round_trip(include_str!("../all_metars"));
+    }
+
+    #[test]
+    fn test_round_trip_na_metars() {
+        assert_round_trip(include_str!("../na_metars.txt"));
+    }
+
+    #[test]
+    fn test_round_trip_test_metars() {
+        assert_round_trip(include_str!("../test.metars"));
+        assert_round_trip(include_str!("../test.metars2"));
+    }
+
+    #[test]
+    fn test_metar_ref_borrows_from_input() {
+        let input = "ENGM 161220Z 01008KT 0800 R01L/P2000N FG FEW010CB BKN020 M02/M04 Q1002 WS R19R R01L/490195 TEMPO 3000 SCT015TCU RMK SLP013";
+        let metar = MetarRef::parse(input).unwrap();
+        let borrowed = |s: &str| input.as_bytes().as_ptr_range().contains(&s.as_ptr());
+        assert!(borrowed(metar.raw) && borrowed(metar.icao));
+        let Obscuration::Described(described) = &metar.obscuration else {
+            panic!("expected described obscuration");
+        };
+        assert!(borrowed(described.rvr[0].runway));
+        let Cloud::CloudData(cb) = &described.clouds[0] else {
+            panic!("expected cloud data");
+        };
+        assert_eq!(cb.cloud_type, Some(OptionalData::Data("CB")));
+        assert!(borrowed(metar.runway_states[0].runway));
+        assert_eq!(metar.wind_shear, [WindShear::Runway("19R")]);
+        assert_eq!(metar.remarks, Some("SLP013"));
+        assert_eq!(metar.wind.speed.velocity, OptionalData::Data(8));
+        assert_eq!(
+            described.visibility.value,
+            VisibilityUnit::Meters(OptionalData::Data(800))
+        );
+        assert_eq!(metar.temperature.temp, OptionalData::Data(-2));
+        assert_eq!(metar.pressure.qnh.unwrap().value, OptionalData::Data(1002));
+        assert_eq!(metar.trends[0].kind, TrendKind::Temporary);
+        assert_eq!(metar.to_owned().raw, input);
+    }
+
+    /// The snapshot was recorded with the owned parser, before it was built
+    /// on [`MetarRef`].
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_metar_ref_matches_owned_parser_snapshot() {
+        let reference = jiff::civil::date(2025, 7, 1)
+            .at(0, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap();
+        let snapshot = include_str!("../snapshots/test.metars.jsonl");
+        let corpus = include_str!("../test.metars");
+        assert_eq!(snapshot.lines().count(), corpus.lines().count());
+        for (line, expected) in corpus.lines().zip(snapshot.lines()) {
+            let expected: Option<Metar> = serde_json::from_str(expected).unwrap();
+            let borrowed = MetarRef::parse_at(line, &reference).ok();
+            assert_eq!(borrowed.map(|metar| metar.to_owned()), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_display_canonical() {
+        let input = "ENGM 161220Z 01008G18KT 350V050 0800 R01L/P2000N -FZDZ BR BKN004 OVC010 M02/M04 Q1002 WS R19R R01L/490195 BECMG FM1300 3000 NSW";
+        let metar = Metar::from_str(input).unwrap();
+        assert_eq!(metar.to_string(), input);
+    }
+
+    #[cfg(feature = "serde")]
+    fn parse_corpus(corpus: &str) -> Vec<Option<Metar>> {
+        let reference = jiff::civil::date(2025, 7, 1)
+            .at(0, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap();
+        corpus
+            .lines()
+            .map(|line| Metar::parse_at(line, &reference).ok())
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_snapshot() {
+        let metars = parse_corpus(include_str!("../test.metars"));
+        crate::snapshot::assert_json_lines_snapshot("test.metars.jsonl", &metars);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_round_trip() {
+        for corpus in [
+            include_str!("../all_metars"),
+            include_str!("../na_metars.txt"),
+            include_str!("../test.metars2"),
+        ] {
+            for metar in parse_corpus(corpus).into_iter().flatten() {
+                let json = serde_json::to_string(&metar).unwrap();
+                let decoded: Metar = serde_json::from_str(&json).unwrap();
+                assert_eq!(decoded, metar, "{json}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_unreadable_fields() {
+        let metar = Metar::from_str("ENZV 161220Z /////KT 9999 OVC006/// 12/08 Q1012").unwrap();
+        let json = serde_json::to_value(&metar).unwrap();
+        assert_eq!(json["wind"]["speed"]["velocity"], "Unreadable");
+        assert_eq!(json["temperature"]["temp"]["Data"], 12);
+        assert_eq!(
+            json["obscuration"]["Described"]["clouds"][0]["CloudData"]["cloud_type"],
+            "Unreadable"
+        );
+        assert_eq!(json["sea_surface_indicator"], serde_json::Value::Null);
+    }
+
     #[test]
     #[ignore = "only used for testing locally"]
     #[traced_test]
@@ -1,3 +1,6 @@
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
 use nom::{
     IResult, Parser,
     branch::alt,
@@ -8,11 +11,13 @@ use nom::{
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NatoMilCode {
     pub codes: Vec<(NatoMilCodeType, Option<char>)>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NatoMilCodeType {
     Blue,
     White,
@@ -23,6 +28,30 @@ pub enum NatoMilCodeType {
     Black,
 }
 
+impl Display for NatoMilCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut codes = self.codes.iter().map(|(code, suffix)| match suffix {
+            Some(suffix) => format!("{code}{suffix}"),
+            None => code.to_string(),
+        });
+        f.write_str(&codes.join(" "))
+    }
+}
+
+impl Display for NatoMilCodeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NatoMilCodeType::Blue => "BLU",
+            NatoMilCodeType::White => "WHT",
+            NatoMilCodeType::Green => "GRN",
+            NatoMilCodeType::Yellow => "YLO",
+            NatoMilCodeType::Amber => "AMB",
+            NatoMilCodeType::Red => "RED",
+            NatoMilCodeType::Black => "BLACK",
+        })
+    }
+}
+
 pub(crate) fn nom_nato_mil_code(input: &str) -> IResult<&str, NatoMilCode> {
     separated_list1(
         space0,
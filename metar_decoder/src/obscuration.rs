@@ -1,3 +1,6 @@
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
 use nom::{
     Parser,
     branch::alt,
@@ -14,34 +17,39 @@ use crate::{
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub enum Obscuration {
-    Described(DescribedObscuration),
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Obscuration<S = String> {
+    Described(DescribedObscuration<S>),
     Cavok,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct DescribedObscuration {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescribedObscuration<S = String> {
     pub visibility: Visibility,
     pub direction_visibility: Option<Vec<Visibility>>,
-    pub rvr: Vec<Rvr>,
-    pub clouds: Vec<Cloud>,
+    pub rvr: Vec<Rvr<S>>,
+    pub clouds: Vec<Cloud<S>>,
     pub present_weather: Vec<PresentWeather>,
     pub vertical_visibility: Option<VerticalVisibility>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Rvr {
-    pub runway: String,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rvr<S = String> {
+    pub runway: S,
     pub value: OptionalData<u32, 4>,
     pub distance_modifier: Option<DistanceModifier>,
     pub comment: Option<Trend>,
 }
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VerticalVisibility {
     pub visibility: OptionalData<u32, 3>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Trend {
     Increasing,
     Decreasing,
@@ -49,21 +57,24 @@ pub enum Trend {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub enum Cloud {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cloud<S = String> {
     NCD, // No cloud detected
     NSC, // No significant clouds
     CLR, // Clear
-    CloudData(CloudData),
+    CloudData(CloudData<S>),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct CloudData {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CloudData<S = String> {
     pub coverage: OptionalData<CloudCoverage, 3>,
     pub height: OptionalData<CloudHeight, 3>,
-    pub cloud_type: Option<OptionalData<String, 3>>,
+    pub cloud_type: Option<OptionalData<S, 3>>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CloudCoverage {
     Few,
     Scattered,
@@ -72,6 +83,7 @@ pub enum CloudCoverage {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Visibility {
     pub value: VisibilityUnit,
     pub direction: Option<Direction>,
@@ -79,12 +91,14 @@ pub struct Visibility {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VisibilityUnit {
     Meters(OptionalData<u32, 4>),
     StatuteMiles(StatuteMilesVisibility),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     N,
     NE,
@@ -97,6 +111,7 @@ pub enum Direction {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StatuteMilesVisibility {
     pub whole: Option<u32>,
     pub fraction: Option<(u32, u32)>,
@@ -104,12 +119,14 @@ pub struct StatuteMilesVisibility {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DistanceModifier {
     LessThan,
     GreaterThan,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PresentWeather {
     pub intensity: Option<WeatherIntensity>,
     pub descriptor: Option<Qualifier>,
@@ -117,6 +134,7 @@ pub struct PresentWeather {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WeatherIntensity {
     Light,    // -
     Heavy,    // +
@@ -124,6 +142,7 @@ pub enum WeatherIntensity {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Qualifier {
     Shallow,      // MI
     Patches,      // BC
@@ -136,6 +155,7 @@ pub enum Qualifier {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WeatherPhenomenon {
     DZ, // Drizzle
     RA, // Rain
@@ -159,7 +179,261 @@ pub enum WeatherPhenomenon {
     DS, // Duststorm
 }
 
-pub(crate) fn nom_obscuration(input: &str) -> nom::IResult<&str, Obscuration> {
+pub(crate) const METERS_PER_STATUTE_MILE: f64 = 1609.344;
+
+impl Visibility {
+    /// Visibility in metres; statute-mile reports are converted.
+    pub fn in_meters(&self) -> Option<u32> {
+        match &self.value {
+            VisibilityUnit::Meters(meters) => meters.to_option(),
+            VisibilityUnit::StatuteMiles(miles) => Some(miles.in_meters()),
+        }
+    }
+}
+
+impl StatuteMilesVisibility {
+    pub fn in_meters(&self) -> u32 {
+        let fraction = self
+            .fraction
+            .map(|(numerator, denominator)| f64::from(numerator) / f64::from(denominator))
+            .unwrap_or(0.0);
+        let total = f64::from(self.whole.unwrap_or(0)) + fraction;
+        (total * METERS_PER_STATUTE_MILE).round() as u32
+    }
+}
+
+impl Obscuration<&str> {
+    pub fn to_owned(&self) -> Obscuration {
+        match self {
+            Obscuration::Described(described) => Obscuration::Described(described.to_owned()),
+            Obscuration::Cavok => Obscuration::Cavok,
+        }
+    }
+}
+
+impl DescribedObscuration<&str> {
+    pub fn to_owned(&self) -> DescribedObscuration {
+        DescribedObscuration {
+            visibility: self.visibility.clone(),
+            direction_visibility: self.direction_visibility.clone(),
+            rvr: self.rvr.iter().map(|rvr| rvr.to_owned()).collect(),
+            clouds: self.clouds.iter().map(|cloud| cloud.to_owned()).collect(),
+            present_weather: self.present_weather.clone(),
+            vertical_visibility: self.vertical_visibility.clone(),
+        }
+    }
+}
+
+impl Rvr<&str> {
+    pub fn to_owned(&self) -> Rvr {
+        Rvr {
+            runway: self.runway.to_string(),
+            value: self.value,
+            distance_modifier: self.distance_modifier.clone(),
+            comment: self.comment.clone(),
+        }
+    }
+}
+
+impl Cloud<&str> {
+    pub fn to_owned(&self) -> Cloud {
+        match self {
+            Cloud::NCD => Cloud::NCD,
+            Cloud::NSC => Cloud::NSC,
+            Cloud::CLR => Cloud::CLR,
+            Cloud::CloudData(data) => Cloud::CloudData(CloudData {
+                coverage: data.coverage.clone(),
+                height: data.height.clone(),
+                cloud_type: data
+                    .cloud_type
+                    .map(|cloud_type| cloud_type.map(str::to_string)),
+            }),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Display for Obscuration<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Obscuration::Described(described) => described.fmt(f),
+            Obscuration::Cavok => f.write_str("CAVOK"),
+        }
+    }
+}
+
+impl<S: AsRef<str>> DescribedObscuration<S> {
+    /// The groups after the prevailing visibility, for reports where the
+    /// visibility sensor is unavailable.
+    pub(crate) fn groups_without_visibility(&self) -> impl Iterator<Item = String> + '_ {
+        self.direction_visibility
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .chain(self.rvr.iter().map(ToString::to_string))
+            .chain(self.present_weather.iter().map(ToString::to_string))
+            .chain(self.clouds.iter().map(ToString::to_string))
+            .chain(self.vertical_visibility.iter().map(ToString::to_string))
+    }
+}
+
+impl<S: AsRef<str>> Display for DescribedObscuration<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut groups =
+            std::iter::once(self.visibility.to_string()).chain(self.groups_without_visibility());
+        f.write_str(&groups.join(" "))
+    }
+}
+
+impl<S: AsRef<str>> Display for Rvr<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "R{}/", self.runway.as_ref())?;
+        if let Some(modifier) = &self.distance_modifier {
+            modifier.fmt(f)?;
+        }
+        self.value.fmt(f)?;
+        match self.comment {
+            Some(Trend::Increasing) => f.write_str("U"),
+            Some(Trend::Decreasing) => f.write_str("D"),
+            Some(Trend::NoDistinctChange) => f.write_str("N"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for VerticalVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VV{}", self.visibility)
+    }
+}
+
+impl<S: AsRef<str>> Display for Cloud<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cloud::NCD => f.write_str("NCD"),
+            Cloud::NSC => f.write_str("NSC"),
+            Cloud::CLR => f.write_str("CLR"),
+            Cloud::CloudData(data) => data.fmt(f),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Display for CloudData<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // In the report a cloud base is just the hundreds of feet, `BKN018`.
+        let height = self.height.clone().map(|height| height.height);
+        write!(f, "{}{height}", self.coverage)?;
+        match &self.cloud_type {
+            Some(OptionalData::Data(cloud_type)) => f.write_str(cloud_type.as_ref()),
+            Some(OptionalData::Undefined) => f.write_str("///"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for CloudCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CloudCoverage::Few => "FEW",
+            CloudCoverage::Scattered => "SCT",
+            CloudCoverage::Broken => "BKN",
+            CloudCoverage::Overcast => "OVC",
+        })
+    }
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            VisibilityUnit::Meters(meters) => meters.fmt(f)?,
+            VisibilityUnit::StatuteMiles(miles) => miles.fmt(f)?,
+        }
+        if self.ndv {
+            f.write_str("NDV")?;
+        }
+        match &self.direction {
+            Some(direction) => direction.fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for StatuteMilesVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(modifier) = &self.modifier {
+            modifier.fmt(f)?;
+        }
+        match (self.whole, self.fraction) {
+            (Some(whole), Some((numerator, denominator))) => {
+                write!(f, "{whole} {numerator}/{denominator}")?
+            }
+            (Some(whole), None) => write!(f, "{whole}")?,
+            (None, Some((numerator, denominator))) => write!(f, "{numerator}/{denominator}")?,
+            (None, None) => {}
+        }
+        f.write_str("SM")
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Display for DistanceModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DistanceModifier::LessThan => "M",
+            DistanceModifier::GreaterThan => "P",
+        })
+    }
+}
+
+impl Display for PresentWeather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(intensity) = &self.intensity {
+            intensity.fmt(f)?;
+        }
+        if let Some(descriptor) = &self.descriptor {
+            descriptor.fmt(f)?;
+        }
+        self.phenomena.iter().try_for_each(|p| p.fmt(f))
+    }
+}
+
+impl Display for WeatherIntensity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WeatherIntensity::Light => "-",
+            WeatherIntensity::Heavy => "+",
+            WeatherIntensity::Vicinity => "VC",
+        })
+    }
+}
+
+impl Display for Qualifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Qualifier::Shallow => "MI",
+            Qualifier::Patches => "BC",
+            Qualifier::Partial => "PR",
+            Qualifier::Low => "DR",
+            Qualifier::Blowing => "BL",
+            Qualifier::Showers => "SH",
+            Qualifier::Thunderstorm => "TS",
+            Qualifier::Freezing => "FZ",
+        })
+    }
+}
+
+impl Display for WeatherPhenomenon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Variant names are the two-letter METAR codes.
+        write!(f, "{self:?}")
+    }
+}
+
+pub(crate) fn nom_obscuration(input: &str) -> nom::IResult<&str, Obscuration<&str>> {
     alt((
         value(Obscuration::Cavok, tag("CAVOK")),
         map(nom_described_obscuration, Obscuration::Described),
@@ -167,7 +441,7 @@ pub(crate) fn nom_obscuration(input: &str) -> nom::IResult<&str, Obscuration> {
     .parse(input)
 }
 
-fn nom_described_obscuration(input: &str) -> nom::IResult<&str, DescribedObscuration> {
+fn nom_described_obscuration(input: &str) -> nom::IResult<&str, DescribedObscuration<&str>> {
     map(
         (
             nom_visibility,
@@ -201,6 +475,42 @@ fn nom_described_obscuration(input: &str) -> nom::IResult<&str, DescribedObscura
     .parse(input)
 }
 
+/// Weather, cloud and vertical visibility groups without a prevailing
+/// visibility, as sent by an automatic station whose visibility sensor is
+/// unavailable. At least one group is required.
+pub(crate) fn nom_obscuration_without_visibility(
+    input: &str,
+) -> nom::IResult<&str, DescribedObscuration<&str>> {
+    map_res(
+        (
+            separated_list0(complete::char(' '), nom_present_weather),
+            preceded(
+                opt(complete::char(' ')),
+                separated_list0(complete::char(' '), nom_cloud),
+            ),
+            preceded(opt(complete::char(' ')), opt(nom_vertical_visibility)),
+        ),
+        |(present_weather, clouds, vertical_visibility)| {
+            if present_weather.is_empty() && clouds.is_empty() && vertical_visibility.is_none() {
+                return Err("No weather or cloud groups");
+            }
+            Ok(DescribedObscuration {
+                visibility: Visibility {
+                    value: VisibilityUnit::Meters(OptionalData::Undefined),
+                    direction: None,
+                    ndv: false,
+                },
+                direction_visibility: None,
+                rvr: Vec::new(),
+                clouds,
+                present_weather,
+                vertical_visibility,
+            })
+        },
+    )
+    .parse(input)
+}
+
 pub(crate) fn nom_visibility(input: &str) -> nom::IResult<&str, Visibility> {
     terminated(
         (
@@ -282,7 +592,7 @@ fn nom_distance_modifier(input: &str) -> nom::IResult<&str, DistanceModifier> {
     .parse(input)
 }
 
-fn nom_rvr(input: &str) -> nom::IResult<&str, Rvr> {
+pub(crate) fn nom_rvr(input: &str) -> nom::IResult<&str, Rvr<&str>> {
     map(
         preceded(
             tag("R"),
@@ -301,7 +611,7 @@ fn nom_rvr(input: &str) -> nom::IResult<&str, Rvr> {
             ),
         ),
         |(runway, (distance_modifier, value, comment))| Rvr {
-            runway: runway.to_string(),
+            runway,
             value,
             distance_modifier,
             comment,
@@ -331,11 +641,11 @@ fn nom_cloud_coverage(input: &str) -> nom::IResult<&str, OptionalData<CloudCover
     .parse(input)
 }
 
-fn nom_cloud_type(input: &str) -> nom::IResult<&str, OptionalData<String, 3>> {
-    OptionalData::optional_field(map(alphanumeric1, |s: &str| s.to_string())).parse(input)
+fn nom_cloud_type(input: &str) -> nom::IResult<&str, OptionalData<&str, 3>> {
+    OptionalData::optional_field(alphanumeric1).parse(input)
 }
 
-pub(crate) fn nom_cloud(input: &str) -> nom::IResult<&str, Cloud> {
+pub(crate) fn nom_cloud(input: &str) -> nom::IResult<&str, Cloud<&str>> {
     alt((
         value(Cloud::NCD, tag("NCD")),
         value(Cloud::NSC, tag("NSC")),
@@ -345,7 +655,7 @@ pub(crate) fn nom_cloud(input: &str) -> nom::IResult<&str, Cloud> {
     .parse(input)
 }
 
-fn nom_cloud_data(input: &str) -> nom::IResult<&str, CloudData> {
+fn nom_cloud_data(input: &str) -> nom::IResult<&str, CloudData<&str>> {
     let (input, coverage) = nom_cloud_coverage.parse(input)?;
     let (input, height) = nom_cloud_height.parse(input)?;
     let (input, cloud_type) = opt(nom_cloud_type).parse(input)?;
@@ -439,7 +749,12 @@ mod tests {
             height: OptionalData::Data(CloudHeight { height: 18 }),
             cloud_type: Some(OptionalData::Undefined),
         };
-        assert_eq!(nom_cloud_data(input), Ok(("", expected)));
+        assert_eq!(nom_cloud_data(input), Ok(("", expected.clone())));
+        assert_eq!(expected.to_string(), input);
+        let OptionalData::Data(height) = &expected.height else {
+            unreachable!()
+        };
+        assert_eq!(height.to_string(), "018FT");
     }
 
     #[test]
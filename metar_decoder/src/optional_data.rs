@@ -5,8 +5,16 @@ use nom::{
     error::ParseError, multi::count,
 };
 
+/// A METAR field that is either decoded or reported as unreadable with `N`
+/// slashes (`//`, `////`, ...).
+///
+/// With the `serde` feature an unreadable field is serialized as the string
+/// `"Unreadable"` and decoded data as `{"Data": ...}`, so it never collides
+/// with an absent group, which is `null`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OptionalData<T, const N: usize> {
+    #[cfg_attr(feature = "serde", serde(rename = "Unreadable"))]
     Undefined,
     Data(T),
 }
@@ -21,6 +29,14 @@ impl<T, const N: usize> OptionalData<T, N> {
     pub fn to_option(self) -> Option<T> {
         self.into()
     }
+
+    /// Converts decoded data, keeping an unreadable field unreadable.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> OptionalData<U, N> {
+        match self {
+            OptionalData::Undefined => OptionalData::Undefined,
+            OptionalData::Data(data) => OptionalData::Data(f(data)),
+        }
+    }
 }
 
 impl<T, const N: usize> OptionalData<T, N> {
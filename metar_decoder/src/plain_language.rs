@@ -0,0 +1,564 @@
+//! Renders a [`Metar`] as short English sentences for readers who do not
+//! decode METARs at a glance. Cloud heights stay in feet and wind speeds in
+//! the reported unit in both unit systems, as is usual in aviation;
+//! [`UnitSystem`] selects visibility, RVR, temperature and pressure units.
+
+use itertools::Itertools;
+
+use crate::{
+    metar::{Metar, ReportType},
+    obscuration::{
+        Cloud, CloudCoverage, Direction, DistanceModifier, METERS_PER_STATUTE_MILE, Obscuration,
+        PresentWeather, Qualifier, Rvr, Trend, VerticalVisibility, Visibility, VisibilityUnit,
+        WeatherIntensity, WeatherPhenomenon,
+    },
+    optional_data::OptionalData,
+    pressure::{HPA_PER_INCH_OF_MERCURY, PressureUnit},
+    runway_state::WindShear,
+    taf::ForecastConditions,
+    temperature::TemperatureInfo,
+    trend::{TrendGroup, TrendKind},
+    units::{timestamp::Timestamp, velocity::VelocityUnit},
+    wind::{Wind, WindDirection},
+};
+
+const FEET_PER_METER: f64 = 3.280_84;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSystem {
+    /// Metres and kilometres, °C and hPa.
+    #[default]
+    Metric,
+    /// Statute miles and feet, °F and inHg.
+    Imperial,
+}
+
+impl Metar {
+    /// One sentence per element of the report, in report order.
+    pub fn plain_language(&self, units: UnitSystem) -> Vec<String> {
+        let mut sentences = vec![sentence(&observation_clause(self))];
+        sentences.push(sentence(&wind_clause(&self.wind)));
+        match &self.obscuration {
+            Obscuration::Cavok => sentences.push(sentence(CAVOK)),
+            Obscuration::Described(described) => {
+                sentences.push(sentence(&visibility_clause(
+                    &described.visibility,
+                    described
+                        .direction_visibility
+                        .as_deref()
+                        .unwrap_or_default(),
+                    units,
+                )));
+                sentences.extend(
+                    described
+                        .rvr
+                        .iter()
+                        .map(|rvr| sentence(&rvr_clause(rvr, units))),
+                );
+                if let Some(weather) = weather_clause(&described.present_weather) {
+                    sentences.push(sentence(&weather));
+                }
+                if let Some(clouds) =
+                    cloud_clause(&described.clouds, described.vertical_visibility.as_ref())
+                {
+                    sentences.push(sentence(&clouds));
+                }
+            }
+        }
+        sentences.push(sentence(&temperature_clause(&self.temperature, units)));
+        if let Some(pressure) = pressure_clause(self, units) {
+            sentences.push(sentence(&pressure));
+        }
+        if let Some(recent) = self.recent_weather.as_deref().and_then(weather_clause) {
+            sentences.push(sentence(&format!("recent {recent}")));
+        }
+        sentences.extend(self.wind_shear.iter().map(|ws| {
+            sentence(&match ws {
+                WindShear::AllRunways => "wind shear on all runways".to_string(),
+                WindShear::Runway(runway) => format!("wind shear on runway {runway}"),
+            })
+        }));
+        if self.snow_closed {
+            sentences.push(sentence("aerodrome closed due to snow"));
+        }
+        if self.nosig {
+            sentences.push(sentence("no significant change expected"));
+        }
+        sentences.extend(
+            self.trends
+                .iter()
+                .map(|trend| sentence(&trend_clause(trend, units))),
+        );
+        sentences
+    }
+}
+
+const CAVOK: &str = "ceiling and visibility OK: no cloud below 5000 ft and no significant weather";
+
+/// Capitalises the clause and ends it with a full stop.
+fn sentence(clause: &str) -> String {
+    let mut chars = clause.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+fn time_of(timestamp: &Timestamp) -> String {
+    timestamp.zoned().strftime("%H:%M UTC").to_string()
+}
+
+fn observation_clause(metar: &Metar) -> String {
+    let kind = match metar.report_type {
+        ReportType::Metar => "observation",
+        ReportType::Speci => "special observation",
+    };
+    let mut clause = format!(
+        "{} {kind} at {} on the {}",
+        metar.icao,
+        time_of(&metar.timestamp),
+        ordinal(metar.timestamp.zoned().day())
+    );
+    if metar.auto {
+        clause.push_str(", automated");
+    }
+    if metar.corrected {
+        clause.push_str(", corrected");
+    }
+    clause
+}
+
+fn ordinal(day: i8) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{day}{suffix}")
+}
+
+fn wind_clause(wind: &Wind) -> String {
+    let unit = match wind.speed.unit {
+        VelocityUnit::Knots => "knots",
+        VelocityUnit::MetersPerSecond => "metres per second",
+        VelocityUnit::KilometersPerHour => "km/h",
+    };
+    let direction = match &wind.dir {
+        WindDirection::Variable => Some("variable".to_string()),
+        WindDirection::Heading(track) => track.0.to_option().map(|d| format!("from {d:03}°")),
+    };
+    let Some(speed) = wind.speed.velocity.to_option() else {
+        return "wind not reported".to_string();
+    };
+    if speed == 0 && direction.as_deref() != Some("variable") {
+        return "wind calm".to_string();
+    }
+    let mut clause = match direction {
+        Some(direction) => format!("wind {direction} at {speed} {unit}"),
+        None => format!("wind from an unknown direction at {speed} {unit}"),
+    };
+    if let Some(gust) = wind.speed.gust.and_then(OptionalData::to_option) {
+        clause.push_str(&format!(", gusting {gust} {unit}"));
+    }
+    if let Some((from, to)) = &wind.varying
+        && let (Some(from), Some(to)) = (from.0.to_option(), to.0.to_option())
+    {
+        clause.push_str(&format!(", varying between {from:03}° and {to:03}°"));
+    }
+    clause
+}
+
+fn distance(meters: u32, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric if meters >= 9999 => "10 km or more".to_string(),
+        UnitSystem::Metric if meters >= 5000 || meters.is_multiple_of(1000) => {
+            format!("{} km", trim_decimal(f64::from(meters) / 1000.0))
+        }
+        UnitSystem::Metric => format!("{meters} m"),
+        UnitSystem::Imperial if meters >= 9999 => "more than 6 statute miles".to_string(),
+        UnitSystem::Imperial => format!(
+            "{} statute miles",
+            trim_decimal(f64::from(meters) / METERS_PER_STATUTE_MILE)
+        ),
+    }
+}
+
+fn trim_decimal(value: f64) -> String {
+    let formatted = format!("{value:.1}");
+    formatted
+        .strip_suffix(".0")
+        .map(str::to_string)
+        .unwrap_or(formatted)
+}
+
+fn modifier_phrase(modifier: Option<&DistanceModifier>) -> &'static str {
+    match modifier {
+        Some(DistanceModifier::LessThan) => "less than ",
+        Some(DistanceModifier::GreaterThan) => "more than ",
+        None => "",
+    }
+}
+
+fn single_visibility(visibility: &Visibility, units: UnitSystem) -> String {
+    let text = match (&visibility.value, units) {
+        (VisibilityUnit::StatuteMiles(miles), UnitSystem::Imperial) => {
+            let amount = match (miles.whole, miles.fraction) {
+                (Some(whole), Some((n, d))) => format!("{whole} {n}/{d}"),
+                (Some(whole), None) => whole.to_string(),
+                (None, Some((n, d))) => format!("{n}/{d}"),
+                (None, None) => String::new(),
+            };
+            format!(
+                "{}{amount} statute miles",
+                modifier_phrase(miles.modifier.as_ref())
+            )
+        }
+        (VisibilityUnit::StatuteMiles(miles), UnitSystem::Metric) => format!(
+            "{}{}",
+            modifier_phrase(miles.modifier.as_ref()),
+            distance(visibility.in_meters().unwrap_or_default(), units)
+        ),
+        (VisibilityUnit::Meters(_), _) => match visibility.in_meters() {
+            Some(meters) => distance(meters, units),
+            None => "not reported".to_string(),
+        },
+    };
+    match &visibility.direction {
+        Some(direction) => format!("{text} to the {}", direction_name(direction)),
+        None => text,
+    }
+}
+
+fn direction_name(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::N => "north",
+        Direction::NE => "north-east",
+        Direction::E => "east",
+        Direction::SE => "south-east",
+        Direction::S => "south",
+        Direction::SW => "south-west",
+        Direction::W => "west",
+        Direction::NW => "north-west",
+    }
+}
+
+fn visibility_clause(
+    visibility: &Visibility,
+    directional: &[Visibility],
+    units: UnitSystem,
+) -> String {
+    let mut clause = format!("visibility {}", single_visibility(visibility, units));
+    if visibility.ndv {
+        clause.push_str(" (no directional variation available)");
+    }
+    for lowest in directional {
+        clause.push_str(&format!(", lowest {}", single_visibility(lowest, units)));
+    }
+    clause
+}
+
+fn rvr_clause(rvr: &Rvr, units: UnitSystem) -> String {
+    let value = match rvr.value.to_option() {
+        None => "not reported".to_string(),
+        Some(meters) => {
+            let amount = match units {
+                UnitSystem::Metric => format!("{meters} m"),
+                UnitSystem::Imperial => {
+                    let feet = (f64::from(meters) * FEET_PER_METER / 50.0).round() * 50.0;
+                    format!("{feet} ft")
+                }
+            };
+            format!(
+                "{}{amount}",
+                modifier_phrase(rvr.distance_modifier.as_ref())
+            )
+        }
+    };
+    let trend = match rvr.comment {
+        Some(Trend::Increasing) => ", increasing",
+        Some(Trend::Decreasing) => ", decreasing",
+        Some(Trend::NoDistinctChange) => ", no change",
+        None => "",
+    };
+    format!(
+        "runway visual range for runway {} {value}{trend}",
+        rvr.runway
+    )
+}
+
+fn phenomenon_name(phenomenon: &OptionalData<WeatherPhenomenon, 2>) -> &'static str {
+    let OptionalData::Data(phenomenon) = phenomenon else {
+        return "unknown weather";
+    };
+    match phenomenon {
+        WeatherPhenomenon::DZ => "drizzle",
+        WeatherPhenomenon::RA => "rain",
+        WeatherPhenomenon::SN => "snow",
+        WeatherPhenomenon::SG => "snow grains",
+        WeatherPhenomenon::PL => "ice pellets",
+        WeatherPhenomenon::GR => "hail",
+        WeatherPhenomenon::GS => "small hail",
+        WeatherPhenomenon::UP => "unknown precipitation",
+        WeatherPhenomenon::BR => "mist",
+        WeatherPhenomenon::FG => "fog",
+        WeatherPhenomenon::FU => "smoke",
+        WeatherPhenomenon::VA => "volcanic ash",
+        WeatherPhenomenon::DU => "widespread dust",
+        WeatherPhenomenon::SA => "sand",
+        WeatherPhenomenon::HZ => "haze",
+        WeatherPhenomenon::PO => "dust whirls",
+        WeatherPhenomenon::SQ => "squalls",
+        WeatherPhenomenon::FC => "funnel cloud",
+        WeatherPhenomenon::SS => "sandstorm",
+        WeatherPhenomenon::DS => "duststorm",
+    }
+}
+
+fn weather_phrase(weather: &PresentWeather) -> Option<String> {
+    let phenomena = weather.phenomena.iter().map(phenomenon_name).join(" and ");
+    let described = match (&weather.descriptor, phenomena.is_empty()) {
+        (Some(Qualifier::Thunderstorm), true) => "thunderstorm".to_string(),
+        (Some(Qualifier::Thunderstorm), false) => format!("thunderstorm with {phenomena}"),
+        (Some(Qualifier::Showers), true) => "showers".to_string(),
+        (Some(Qualifier::Showers), false) => format!("showers of {phenomena}"),
+        (Some(descriptor), _) => {
+            let adjective = match descriptor {
+                Qualifier::Shallow => "shallow",
+                Qualifier::Patches => "patches of",
+                Qualifier::Partial => "partial",
+                Qualifier::Low => "low drifting",
+                Qualifier::Blowing => "blowing",
+                Qualifier::Freezing => "freezing",
+                Qualifier::Showers | Qualifier::Thunderstorm => unreachable!(),
+            };
+            format!("{adjective} {phenomena}")
+        }
+        (None, true) => return None,
+        (None, false) => phenomena,
+    };
+    Some(match weather.intensity {
+        Some(WeatherIntensity::Light) => format!("light {described}"),
+        Some(WeatherIntensity::Heavy) => format!("heavy {described}"),
+        Some(WeatherIntensity::Vicinity) => format!("{described} in the vicinity"),
+        None => described,
+    })
+}
+
+fn weather_clause(weather: &[PresentWeather]) -> Option<String> {
+    let phrases = weather.iter().filter_map(weather_phrase).join(", ");
+    (!phrases.is_empty()).then_some(phrases)
+}
+
+fn cloud_clause(
+    clouds: &[Cloud],
+    vertical_visibility: Option<&VerticalVisibility>,
+) -> Option<String> {
+    let mut layers = clouds
+        .iter()
+        .map(|cloud| match cloud {
+            Cloud::NCD => "no cloud detected".to_string(),
+            Cloud::NSC => "no significant cloud".to_string(),
+            Cloud::CLR => "no cloud below 12000 ft".to_string(),
+            Cloud::CloudData(data) => {
+                let coverage = match &data.coverage {
+                    OptionalData::Data(CloudCoverage::Few) => "few clouds",
+                    OptionalData::Data(CloudCoverage::Scattered) => "scattered clouds",
+                    OptionalData::Data(CloudCoverage::Broken) => "broken clouds",
+                    OptionalData::Data(CloudCoverage::Overcast) => "overcast",
+                    OptionalData::Undefined => "cloud of unknown amount",
+                };
+                let height = match &data.height {
+                    OptionalData::Data(height) => format!("at {} ft", height.height * 100),
+                    OptionalData::Undefined => "at an unknown height".to_string(),
+                };
+                let cloud_type = match data.cloud_type.as_ref().and_then(|t| match t {
+                    OptionalData::Data(t) => Some(t.as_str()),
+                    OptionalData::Undefined => None,
+                }) {
+                    Some("CB") => " (cumulonimbus)",
+                    Some("TCU") => " (towering cumulus)",
+                    _ => "",
+                };
+                format!("{coverage} {height}{cloud_type}")
+            }
+        })
+        .collect_vec();
+    if let Some(vv) = vertical_visibility {
+        layers.push(match vv.visibility.to_option() {
+            Some(hundreds) => format!("sky obscured, vertical visibility {} ft", hundreds * 100),
+            None => "sky obscured, vertical visibility not reported".to_string(),
+        });
+    }
+    (!layers.is_empty()).then(|| layers.join(", "))
+}
+
+fn temperature_value(celsius: Option<i32>, units: UnitSystem) -> String {
+    match (celsius, units) {
+        (None, _) => "not reported".to_string(),
+        (Some(c), UnitSystem::Metric) => format!("{c} °C"),
+        (Some(c), UnitSystem::Imperial) => {
+            format!("{} °F", (f64::from(c) * 9.0 / 5.0 + 32.0).round())
+        }
+    }
+}
+
+fn temperature_clause(temperature: &TemperatureInfo, units: UnitSystem) -> String {
+    format!(
+        "temperature {}, dew point {}",
+        temperature_value(temperature.temp.to_option(), units),
+        temperature_value(temperature.dew_point.to_option(), units)
+    )
+}
+
+fn pressure_clause(metar: &Metar, units: UnitSystem) -> Option<String> {
+    let reported = [metar.pressure.qnh, metar.pressure.altimeter]
+        .into_iter()
+        .flatten()
+        .filter_map(|p| Some((p.unit, p.value.to_option()?)))
+        .collect_vec();
+    let wanted = match units {
+        UnitSystem::Metric => PressureUnit::Hectopascals,
+        UnitSystem::Imperial => PressureUnit::InchesOfMercury,
+    };
+    if let Some((_, value)) = reported.iter().find(|(unit, _)| *unit == wanted) {
+        return Some(match wanted {
+            PressureUnit::Hectopascals => format!("QNH {value} hPa"),
+            PressureUnit::InchesOfMercury => {
+                format!("altimeter {:.2} inHg", f64::from(*value) / 100.0)
+            }
+        });
+    }
+    let hpa = metar.pressure.in_hectopascals()?;
+    Some(match units {
+        UnitSystem::Metric => format!("QNH {} hPa", hpa.round()),
+        UnitSystem::Imperial => {
+            format!("altimeter {:.2} inHg", hpa / HPA_PER_INCH_OF_MERCURY)
+        }
+    })
+}
+
+fn conditions_clauses(conditions: &ForecastConditions, units: UnitSystem) -> Vec<String> {
+    let mut clauses = Vec::new();
+    if let Some(wind) = &conditions.wind {
+        clauses.push(wind_clause(wind));
+    }
+    if conditions.cavok {
+        clauses.push(CAVOK.to_string());
+    }
+    if let Some(visibility) = &conditions.visibility {
+        clauses.push(visibility_clause(visibility, &[], units));
+    }
+    if conditions.nsw {
+        clauses.push("no significant weather".to_string());
+    }
+    clauses.extend(weather_clause(&conditions.present_weather));
+    clauses.extend(cloud_clause(
+        &conditions.clouds,
+        conditions.vertical_visibility.as_ref(),
+    ));
+    clauses
+}
+
+fn trend_clause(trend: &TrendGroup, units: UnitSystem) -> String {
+    let mut clause = match trend.kind {
+        TrendKind::Becoming => "becoming".to_string(),
+        TrendKind::Temporary => "temporarily".to_string(),
+    };
+    for (word, time) in [
+        ("from", &trend.from),
+        ("until", &trend.until),
+        ("at", &trend.at),
+    ] {
+        if let Some(time) = time {
+            clause.push_str(&format!(" {word} {}", time_of(time)));
+        }
+    }
+    let conditions = conditions_clauses(&trend.conditions, units);
+    if !conditions.is_empty() {
+        clause.push_str(&format!(": {}", conditions.join("; ")));
+    }
+    clause
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_metric() {
+        let metar = Metar::from_str(
+            "ENGM 161220Z 01008G18KT 350V050 0800 R01L/P2000N -FZDZ BR BKN004 OVC010CB M02/M04 Q1002 WS R19R BECMG FM1300 3000 NSW",
+        )
+        .unwrap();
+        assert_eq!(
+            metar.plain_language(UnitSystem::Metric)[1..],
+            [
+                "Wind from 010° at 8 knots, gusting 18 knots, varying between 350° and 050°.",
+                "Visibility 800 m.",
+                "Runway visual range for runway 01L more than 2000 m, no change.",
+                "Light freezing drizzle, mist.",
+                "Broken clouds at 400 ft, overcast at 1000 ft (cumulonimbus).",
+                "Temperature -2 °C, dew point -4 °C.",
+                "QNH 1002 hPa.",
+                "Wind shear on runway 19R.",
+                "Becoming from 13:00 UTC: visibility 3 km; no significant weather.",
+            ]
+        );
+        assert!(
+            metar.plain_language(UnitSystem::Metric)[0]
+                .starts_with("ENGM observation at 12:20 UTC on the 16th")
+        );
+    }
+
+    #[test]
+    fn test_imperial() {
+        let metar =
+            Metar::from_str("KJFK 161251Z VRB03KT 1 1/2SM +TSRA SCT008 BKN015 21/19 A2992 RMK AO2")
+                .unwrap();
+        let sentences = metar.plain_language(UnitSystem::Imperial);
+        assert_eq!(
+            sentences[1..],
+            [
+                "Wind variable at 3 knots.",
+                "Visibility 1 1/2 statute miles.",
+                "Heavy thunderstorm with rain.",
+                "Scattered clouds at 800 ft, broken clouds at 1500 ft.",
+                "Temperature 70 °F, dew point 66 °F.",
+                "Altimeter 29.92 inHg.",
+            ]
+        );
+        assert_eq!(
+            metar.plain_language(UnitSystem::Metric)[2],
+            "Visibility 2414 m."
+        );
+        assert_eq!(metar.plain_language(UnitSystem::Metric)[6], "QNH 1013 hPa.");
+    }
+
+    #[test]
+    fn test_cavok_and_calm() {
+        let metar = Metar::from_str("ENZV 191650Z 00000KT CAVOK 08/04 Q1026 NOSIG").unwrap();
+        let sentences = metar.plain_language(UnitSystem::Imperial);
+        assert_eq!(sentences[1], "Wind calm.");
+        assert_eq!(
+            sentences[2],
+            "Ceiling and visibility OK: no cloud below 5000 ft and no significant weather."
+        );
+        assert_eq!(sentences[4], "Altimeter 30.30 inHg.");
+        assert_eq!(sentences[5], "No significant change expected.");
+    }
+
+    #[test]
+    fn test_corpus_renders() {
+        for line in include_str!("../all_metars").lines() {
+            if let Ok(metar) = Metar::from_str(line) {
+                for units in [UnitSystem::Metric, UnitSystem::Imperial] {
+                    assert!(metar.plain_language(units).iter().all(|s| s.ends_with('.')));
+                }
+            }
+        }
+    }
+}
@@ -1,30 +1,64 @@
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
 use nom::{
     IResult, Parser,
     bytes::complete::take,
     character::complete::{self, u32},
     combinator::{all_consuming, map_parser, opt, value},
+    sequence::preceded,
 };
 
 use crate::optional_data::OptionalData;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pressure {
     pub qnh: Option<PressureSingle>,
     pub altimeter: Option<PressureSingle>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PressureSingle {
     pub value: OptionalData<u32, 4>,
     pub unit: PressureUnit,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PressureUnit {
     Hectopascals,
     InchesOfMercury,
 }
 
+pub(crate) const HPA_PER_INCH_OF_MERCURY: f64 = 33.863_886;
+
+impl Pressure {
+    /// QNH in hPa, from the `Q` group or converted from the `A` group.
+    pub fn in_hectopascals(&self) -> Option<f64> {
+        self.qnh
+            .as_ref()
+            .and_then(PressureSingle::in_hectopascals)
+            .or_else(|| {
+                self.altimeter
+                    .as_ref()
+                    .and_then(PressureSingle::in_hectopascals)
+            })
+    }
+}
+
+impl PressureSingle {
+    /// The reading in hPa; `A` groups are hundredths of an inch of mercury.
+    pub fn in_hectopascals(&self) -> Option<f64> {
+        let value = f64::from(self.value.to_option()?);
+        Some(match self.unit {
+            PressureUnit::Hectopascals => value,
+            PressureUnit::InchesOfMercury => value / 100.0 * HPA_PER_INCH_OF_MERCURY,
+        })
+    }
+}
+
 impl PressureUnit {
     const fn pressure_letter(&self) -> char {
         match self {
@@ -34,10 +68,28 @@ impl PressureUnit {
     }
 }
 
+impl Display for Pressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.qnh.iter().chain(&self.altimeter).join(" "))
+    }
+}
+
+impl Display for PressureSingle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.unit.pressure_letter(), self.value)
+    }
+}
+
+/// A QNH group, an altimeter group or both, e.g. `Q1013A2992`. Both may also
+/// be given as separate groups, `Q1013 A2992`, which is how [`Pressure`]
+/// displays them.
 pub(crate) fn nom_pressure(input: &str) -> IResult<&str, Pressure> {
     let hectopascals = move |i| nom_pressure_single(i, PressureUnit::Hectopascals);
     let inches_of_mercury = move |i| nom_pressure_single(i, PressureUnit::InchesOfMercury);
-    (opt(hectopascals), opt(inches_of_mercury))
+    (
+        opt(hectopascals),
+        opt(preceded(opt(complete::char(' ')), inches_of_mercury)),
+    )
         //ensure that at least one of qnh or altimeter is present
         .map_res(|(qnh, altimeter)| {
             if qnh.is_some() || altimeter.is_some() {
@@ -66,3 +118,30 @@ fn nom_pressure_unit<'a>(
         complete::char(pressure_unit.pressure_letter()),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_hectopascals() {
+        let (_, qnh) = nom_pressure("Q1002").unwrap();
+        assert_eq!(qnh.in_hectopascals(), Some(1002.0));
+        let (_, altimeter) = nom_pressure("A2992").unwrap();
+        assert_eq!(altimeter.in_hectopascals().map(f64::round), Some(1013.0));
+        let (_, both) = nom_pressure("Q////A2992").unwrap();
+        assert_eq!(both.in_hectopascals().map(f64::round), Some(1013.0));
+    }
+
+    #[test]
+    fn test_qnh_and_altimeter_groups() {
+        for input in ["Q1013A2992", "Q1013 A2992"] {
+            let (rest, pressure) = nom_pressure(input).unwrap();
+            assert_eq!(rest, "");
+            assert_eq!(pressure.qnh.unwrap().value, OptionalData::Data(1013));
+            assert_eq!(pressure.altimeter.unwrap().value, OptionalData::Data(2992));
+            assert_eq!(pressure.to_string(), "Q1013 A2992");
+        }
+        assert_eq!(nom_pressure("Q1013 RMK").unwrap().0, " RMK");
+    }
+}
@@ -0,0 +1,525 @@
+use std::fmt::{self, Display};
+
+use itertools::Itertools;
+use jiff::Zoned;
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, one_of, u32},
+    combinator::{eof, map, map_opt, map_parser, opt, peek, value},
+    sequence::{preceded, terminated},
+};
+
+use crate::units::timestamp::{Timestamp, resolve_hour_minute_before};
+
+/// Decoded `RMK` section. The groups are kept in report order, so anything
+/// that is not recognised stays in place as [`Remark::FreeText`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Remarks {
+    pub groups: Vec<Remark>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Remark {
+    StationType(StationType),
+    /// `SLPppp`, or `None` for `SLPNO`.
+    SeaLevelPressure(Option<SeaLevelPressure>),
+    PreciseTemperature(PreciseTemperature),
+    PeakWind(PeakWind),
+    WindShift(WindShift),
+    PressureTendency(PressureTendency),
+    SensorUnavailable(Sensor),
+    /// `$`: the station needs maintenance.
+    MaintenanceRequired,
+    FreeText(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StationType {
+    /// `AO1`
+    Automated,
+    /// `AO2`
+    AutomatedWithPrecipitationDiscriminator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeaLevelPressure {
+    pub tenths_hpa: u32,
+}
+
+/// The `T`-group, in tenths of a degree Celsius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreciseTemperature {
+    pub temperature: i32,
+    pub dew_point: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeakWind {
+    pub direction: u32,
+    pub speed: u32,
+    pub at: Timestamp,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindShift {
+    pub at: Timestamp,
+    pub frontal_passage: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PressureTendency {
+    RisingRapidly,
+    FallingRapidly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sensor {
+    Thunderstorm,
+    PresentWeather,
+    Precipitation,
+    FreezingRain,
+    Rvr,
+}
+
+impl Remarks {
+    pub fn station_type(&self) -> Option<StationType> {
+        self.groups.iter().find_map(|group| match group {
+            Remark::StationType(station_type) => Some(*station_type),
+            _ => None,
+        })
+    }
+
+    pub fn sea_level_pressure(&self) -> Option<SeaLevelPressure> {
+        self.groups.iter().find_map(|group| match group {
+            Remark::SeaLevelPressure(pressure) => *pressure,
+            _ => None,
+        })
+    }
+
+    pub fn precise_temperature(&self) -> Option<PreciseTemperature> {
+        self.groups.iter().find_map(|group| match group {
+            Remark::PreciseTemperature(temperature) => Some(*temperature),
+            _ => None,
+        })
+    }
+
+    pub fn peak_wind(&self) -> Option<&PeakWind> {
+        self.groups.iter().find_map(|group| match group {
+            Remark::PeakWind(peak_wind) => Some(peak_wind),
+            _ => None,
+        })
+    }
+
+    pub fn wind_shift(&self) -> Option<&WindShift> {
+        self.groups.iter().find_map(|group| match group {
+            Remark::WindShift(wind_shift) => Some(wind_shift),
+            _ => None,
+        })
+    }
+
+    pub fn pressure_tendency(&self) -> Option<PressureTendency> {
+        self.groups.iter().find_map(|group| match group {
+            Remark::PressureTendency(tendency) => Some(*tendency),
+            _ => None,
+        })
+    }
+
+    pub fn sensor_unavailable(&self, sensor: Sensor) -> bool {
+        self.groups.contains(&Remark::SensorUnavailable(sensor))
+    }
+
+    pub fn maintenance_required(&self) -> bool {
+        self.groups.contains(&Remark::MaintenanceRequired)
+    }
+
+    pub fn free_text(&self) -> impl Iterator<Item = &str> {
+        self.groups.iter().filter_map(|group| match group {
+            Remark::FreeText(text) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl SeaLevelPressure {
+    /// Decodes the three digits of `SLPppp`, which drop the leading 9 or 10.
+    pub fn from_code(code: u32) -> Self {
+        let base = if code >= 500 { 9000 } else { 10000 };
+        SeaLevelPressure {
+            tenths_hpa: base + code,
+        }
+    }
+
+    pub fn hectopascals(&self) -> f64 {
+        f64::from(self.tenths_hpa) / 10.0
+    }
+}
+
+impl PreciseTemperature {
+    pub fn temperature_celsius(&self) -> f64 {
+        f64::from(self.temperature) / 10.0
+    }
+
+    pub fn dew_point_celsius(&self) -> Option<f64> {
+        self.dew_point.map(|d| f64::from(d) / 10.0)
+    }
+}
+
+impl Display for Remarks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.groups.iter().join(" "))
+    }
+}
+
+impl Display for Remark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Remark::StationType(StationType::Automated) => f.write_str("AO1"),
+            Remark::StationType(StationType::AutomatedWithPrecipitationDiscriminator) => {
+                f.write_str("AO2")
+            }
+            Remark::SeaLevelPressure(Some(pressure)) => {
+                write!(f, "SLP{:03}", pressure.tenths_hpa % 1000)
+            }
+            Remark::SeaLevelPressure(None) => f.write_str("SLPNO"),
+            Remark::PreciseTemperature(temperature) => {
+                write!(f, "T{}", TenthsCelsius(temperature.temperature))?;
+                match temperature.dew_point {
+                    Some(dew_point) => write!(f, "{}", TenthsCelsius(dew_point)),
+                    None => Ok(()),
+                }
+            }
+            Remark::PeakWind(peak_wind) => write!(
+                f,
+                "PK WND {:03}{:02}/{}",
+                peak_wind.direction,
+                peak_wind.speed,
+                peak_wind.at.zoned().strftime("%H%M")
+            ),
+            Remark::WindShift(wind_shift) => {
+                write!(f, "WSHFT {}", wind_shift.at.zoned().strftime("%H%M"))?;
+                if wind_shift.frontal_passage {
+                    f.write_str(" FROPA")?;
+                }
+                Ok(())
+            }
+            Remark::PressureTendency(PressureTendency::RisingRapidly) => f.write_str("PRESRR"),
+            Remark::PressureTendency(PressureTendency::FallingRapidly) => f.write_str("PRESFR"),
+            Remark::SensorUnavailable(sensor) => f.write_str(match sensor {
+                Sensor::Thunderstorm => "TSNO",
+                Sensor::PresentWeather => "PWINO",
+                Sensor::Precipitation => "PNO",
+                Sensor::FreezingRain => "FZRANO",
+                Sensor::Rvr => "RVRNO",
+            }),
+            Remark::MaintenanceRequired => f.write_str("$"),
+            Remark::FreeText(text) => f.write_str(text),
+        }
+    }
+}
+
+struct TenthsCelsius(i32);
+
+impl Display for TenthsCelsius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { 1 } else { 0 };
+        write!(f, "{sign}{:03}", self.0.unsigned_abs())
+    }
+}
+
+fn token_end(input: &str) -> IResult<&str, ()> {
+    value((), peek(alt((tag(" "), eof)))).parse(input)
+}
+
+fn digits<'a>(n: usize) -> impl Parser<&'a str, Output = u32, Error = nom::error::Error<&'a str>> {
+    map_parser(take_while_m_n(n, n, |c: char| c.is_ascii_digit()), u32)
+}
+
+fn nom_station_type(input: &str) -> IResult<&str, StationType> {
+    alt((
+        value(StationType::Automated, alt((tag("AO1"), tag("A01")))),
+        value(
+            StationType::AutomatedWithPrecipitationDiscriminator,
+            alt((tag("AO2"), tag("A02"))),
+        ),
+    ))
+    .parse(input)
+}
+
+fn nom_sea_level_pressure(input: &str) -> IResult<&str, Option<SeaLevelPressure>> {
+    preceded(
+        tag("SLP"),
+        alt((
+            value(None, tag("NO")),
+            map(digits(3), |code| Some(SeaLevelPressure::from_code(code))),
+        )),
+    )
+    .parse(input)
+}
+
+fn nom_tenths_celsius(input: &str) -> IResult<&str, i32> {
+    (one_of("01"), digits(3))
+        .map(|(sign, value)| match sign {
+            '1' => -(value as i32),
+            _ => value as i32,
+        })
+        .parse(input)
+}
+
+fn nom_precise_temperature(input: &str) -> IResult<&str, PreciseTemperature> {
+    preceded(char('T'), (nom_tenths_celsius, opt(nom_tenths_celsius)))
+        .map(|(temperature, dew_point)| PreciseTemperature {
+            temperature,
+            dew_point,
+        })
+        .parse(input)
+}
+
+/// `(hh)mm`, resolved to the last such time before the observation.
+fn nom_remark_time<'a>(input: &'a str, observed: &Zoned) -> IResult<&'a str, Timestamp> {
+    map_opt(
+        alt((
+            terminated((digits(2), digits(2)), token_end).map(|(h, m)| (Some(h as i8), m as i8)),
+            digits(2).map(|m| (None, m as i8)),
+        )),
+        |(hour, minute)| resolve_hour_minute_before(observed, hour, minute).map(Timestamp::new),
+    )
+    .parse(input)
+}
+
+fn nom_peak_wind<'a>(input: &'a str, observed: &Zoned) -> IResult<&'a str, PeakWind> {
+    preceded(
+        tag("PK WND "),
+        (
+            digits(3),
+            map_parser(take_while_m_n(2, 3, |c: char| c.is_ascii_digit()), u32),
+            preceded(char('/'), |i| nom_remark_time(i, observed)),
+        ),
+    )
+    .map(|(direction, speed, at)| PeakWind {
+        direction,
+        speed,
+        at,
+    })
+    .parse(input)
+}
+
+fn nom_wind_shift<'a>(input: &'a str, observed: &Zoned) -> IResult<&'a str, WindShift> {
+    preceded(
+        tag("WSHFT "),
+        (|i| nom_remark_time(i, observed), opt(tag(" FROPA"))),
+    )
+    .map(|(at, fropa)| WindShift {
+        at,
+        frontal_passage: fropa.is_some(),
+    })
+    .parse(input)
+}
+
+fn nom_remark<'a>(input: &'a str, observed: &Zoned) -> IResult<&'a str, Remark> {
+    alt((
+        map(nom_station_type, Remark::StationType),
+        map(nom_sea_level_pressure, Remark::SeaLevelPressure),
+        map(nom_precise_temperature, Remark::PreciseTemperature),
+        map(|i| nom_peak_wind(i, observed), Remark::PeakWind),
+        map(|i| nom_wind_shift(i, observed), Remark::WindShift),
+        value(
+            Remark::PressureTendency(PressureTendency::RisingRapidly),
+            tag("PRESRR"),
+        ),
+        value(
+            Remark::PressureTendency(PressureTendency::FallingRapidly),
+            tag("PRESFR"),
+        ),
+        map(
+            alt((
+                value(Sensor::Thunderstorm, tag("TSNO")),
+                value(Sensor::PresentWeather, tag("PWINO")),
+                value(Sensor::Precipitation, tag("PNO")),
+                value(Sensor::FreezingRain, tag("FZRANO")),
+                value(Sensor::Rvr, tag("RVRNO")),
+            )),
+            Remark::SensorUnavailable,
+        ),
+        value(Remark::MaintenanceRequired, tag("$")),
+    ))
+    .parse(input)
+}
+
+/// Decodes the text after `RMK`. This never fails: consecutive tokens that are
+/// not recognised are kept together as free text.
+pub(crate) fn parse_remarks(input: &str, observed: &Zoned) -> Remarks {
+    let mut groups = Vec::new();
+    let mut remaining = input;
+    loop {
+        remaining = remaining.trim_start_matches(' ');
+        if remaining.is_empty() {
+            break;
+        }
+        if let Ok((rest, remark)) =
+            terminated(|i| nom_remark(i, observed), token_end).parse(remaining)
+        {
+            groups.push(remark);
+            remaining = rest;
+            continue;
+        }
+        let (token, rest) = remaining.split_once(' ').unwrap_or((remaining, ""));
+        match groups.last_mut() {
+            Some(Remark::FreeText(text)) => {
+                text.push(' ');
+                text.push_str(token);
+            }
+            _ => groups.push(Remark::FreeText(token.to_string())),
+        }
+        remaining = rest;
+    }
+    Remarks { groups }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use jiff::{civil::date, tz::TimeZone};
+
+    use super::*;
+    use crate::metar::Metar;
+
+    fn observed() -> Zoned {
+        date(2025, 6, 16)
+            .at(4, 53, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap()
+    }
+
+    fn at(hour: i8, minute: i8) -> Timestamp {
+        Timestamp::new(
+            date(2025, 6, 16)
+                .at(hour, minute, 0, 0)
+                .to_zoned(TimeZone::UTC)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_common_remarks() {
+        let remarks = parse_remarks("AO2 SLP164 T02330228 $", &observed());
+        assert_eq!(
+            remarks.station_type(),
+            Some(StationType::AutomatedWithPrecipitationDiscriminator)
+        );
+        assert_eq!(remarks.sea_level_pressure().unwrap().tenths_hpa, 10164);
+        assert_eq!(
+            remarks.precise_temperature(),
+            Some(PreciseTemperature {
+                temperature: 233,
+                dew_point: Some(228),
+            })
+        );
+        assert!(remarks.maintenance_required());
+        assert_eq!(remarks.free_text().count(), 0);
+        assert_eq!(remarks.to_string(), "AO2 SLP164 T02330228 $");
+    }
+
+    #[test]
+    fn test_sea_level_pressure_below_1000() {
+        let pressure = SeaLevelPressure::from_code(982);
+        assert_eq!(pressure.tenths_hpa, 9982);
+        assert_eq!(pressure.hectopascals(), 998.2);
+        let remarks = parse_remarks("AO1 SLPNO", &observed());
+        assert_eq!(remarks.sea_level_pressure(), None);
+        assert_eq!(remarks.groups[1], Remark::SeaLevelPressure(None));
+    }
+
+    #[test]
+    fn test_negative_precise_temperature() {
+        let remarks = parse_remarks("T10171033", &observed());
+        let temperature = remarks.precise_temperature().unwrap();
+        assert_eq!(temperature.temperature_celsius(), -1.7);
+        assert_eq!(temperature.dew_point_celsius(), Some(-3.3));
+    }
+
+    #[test]
+    fn test_peak_wind_and_wind_shift() {
+        let remarks = parse_remarks("AO2 PK WND 30039/22 WSHFT 0403 FROPA", &observed());
+        assert_eq!(
+            remarks.peak_wind(),
+            Some(&PeakWind {
+                direction: 300,
+                speed: 39,
+                at: at(4, 22),
+            })
+        );
+        assert_eq!(
+            remarks.wind_shift(),
+            Some(&WindShift {
+                at: at(4, 3),
+                frontal_passage: true,
+            })
+        );
+        assert_eq!(
+            remarks.to_string(),
+            "AO2 PK WND 30039/0422 WSHFT 0403 FROPA"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_remark_times_are_free_text() {
+        let remarks = parse_remarks("AO2 PK WND 30039/99 SLP164", &observed());
+        assert_eq!(remarks.peak_wind(), None);
+        assert_eq!(remarks.free_text().collect::<Vec<_>>(), ["PK WND 30039/99"]);
+        assert!(remarks.sea_level_pressure().is_some());
+
+        let remarks = parse_remarks("AO2 WSHFT 7777", &observed());
+        assert_eq!(remarks.wind_shift(), None);
+        assert_eq!(remarks.free_text().collect::<Vec<_>>(), ["WSHFT 7777"]);
+    }
+
+    #[test]
+    fn test_sensors_and_free_text() {
+        let remarks = parse_remarks(
+            "AO2 LTG DSNT W AND NW PRESFR 6//// PWINO PNO TSNO",
+            &observed(),
+        );
+        assert_eq!(
+            remarks.free_text().collect::<Vec<_>>(),
+            ["LTG DSNT W AND NW", "6////"]
+        );
+        assert_eq!(
+            remarks.pressure_tendency(),
+            Some(PressureTendency::FallingRapidly)
+        );
+        assert!(remarks.sensor_unavailable(Sensor::Thunderstorm));
+        assert!(remarks.sensor_unavailable(Sensor::PresentWeather));
+        assert!(remarks.sensor_unavailable(Sensor::Precipitation));
+        assert!(!remarks.sensor_unavailable(Sensor::Rvr));
+    }
+
+    #[test]
+    fn test_na_corpus_known_groups_are_decoded() {
+        let known = ["AO1", "AO2", "PRESRR", "PRESFR", "TSNO", "$", "SLPNO"];
+        for line in include_str!("../na_metars.txt").lines() {
+            let Ok(metar) = Metar::from_str(line) else {
+                continue;
+            };
+            let Some(remarks) = metar.remarks else {
+                continue;
+            };
+            for text in remarks.free_text() {
+                for token in text.split(' ') {
+                    assert!(!known.contains(&token), "'{token}' in '{line}'");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use std::fmt::{self, Display};
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, one_of, space1},
+    combinator::{map, map_opt, map_res, opt, peek, recognize, value},
+    multi::separated_list1,
+    sequence::{preceded, terminated},
+};
+
+use crate::optional_data::OptionalData;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunwayState<S = String> {
+    /// Runway designator, e.g. `01L`. `88` means all runways and `99` repeats
+    /// the previous report.
+    pub runway: S,
+    pub report: RunwayStateReport,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RunwayStateReport {
+    Contaminated {
+        deposit: OptionalData<RunwayDeposit, 1>,
+        extent: OptionalData<ContaminationExtent, 1>,
+        depth: OptionalData<DepositDepth, 2>,
+        braking: OptionalData<BrakingAction, 2>,
+    },
+    /// `CLRD`: contamination has been cleared.
+    Cleared {
+        braking: OptionalData<BrakingAction, 2>,
+    },
+}
+
+/// WMO code table 0919.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RunwayDeposit {
+    ClearAndDry,
+    Damp,
+    WetOrWaterPatches,
+    RimeOrFrost,
+    DrySnow,
+    WetSnow,
+    Slush,
+    Ice,
+    CompactedSnow,
+    FrozenRuts,
+}
+
+/// WMO code table 0519.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContaminationExtent {
+    UpTo10Percent,
+    UpTo25Percent,
+    UpTo50Percent,
+    UpTo100Percent,
+}
+
+/// WMO code table 1079.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DepositDepth {
+    Millimetres(u16),
+    FortyCentimetresOrMore,
+    /// The runway is not operational due to snow, slush, ice or standing
+    /// water; depth not measured.
+    RunwayNotOperational,
+}
+
+/// WMO code table 0366.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BrakingAction {
+    /// Friction coefficient in hundredths, e.g. `35` for 0.35.
+    FrictionCoefficient(u8),
+    Poor,
+    MediumPoor,
+    Medium,
+    MediumGood,
+    Good,
+    Unreliable,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindShear<S = String> {
+    AllRunways,
+    Runway(S),
+}
+
+impl RunwayState<&str> {
+    pub fn to_owned(&self) -> RunwayState {
+        RunwayState {
+            runway: self.runway.to_string(),
+            report: self.report.clone(),
+        }
+    }
+}
+
+impl WindShear<&str> {
+    pub fn to_owned(&self) -> WindShear {
+        match self {
+            WindShear::AllRunways => WindShear::AllRunways,
+            WindShear::Runway(runway) => WindShear::Runway(runway.to_string()),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Display for RunwayState<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "R{}/{}", self.runway.as_ref(), self.report)
+    }
+}
+
+impl Display for RunwayStateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunwayStateReport::Contaminated {
+                deposit,
+                extent,
+                depth,
+                braking,
+            } => write!(f, "{deposit}{extent}{depth}{braking}"),
+            RunwayStateReport::Cleared { braking } => write!(f, "CLRD{braking}"),
+        }
+    }
+}
+
+impl Display for RunwayDeposit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            RunwayDeposit::ClearAndDry => 0,
+            RunwayDeposit::Damp => 1,
+            RunwayDeposit::WetOrWaterPatches => 2,
+            RunwayDeposit::RimeOrFrost => 3,
+            RunwayDeposit::DrySnow => 4,
+            RunwayDeposit::WetSnow => 5,
+            RunwayDeposit::Slush => 6,
+            RunwayDeposit::Ice => 7,
+            RunwayDeposit::CompactedSnow => 8,
+            RunwayDeposit::FrozenRuts => 9,
+        };
+        write!(f, "{code}")
+    }
+}
+
+impl Display for ContaminationExtent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ContaminationExtent::UpTo10Percent => "1",
+            ContaminationExtent::UpTo25Percent => "2",
+            ContaminationExtent::UpTo50Percent => "5",
+            ContaminationExtent::UpTo100Percent => "9",
+        })
+    }
+}
+
+impl Display for DepositDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match *self {
+            DepositDepth::Millimetres(mm @ 0..=90) => mm,
+            DepositDepth::Millimetres(mm) => mm / 50 + 90,
+            DepositDepth::FortyCentimetresOrMore => 98,
+            DepositDepth::RunwayNotOperational => 99,
+        };
+        write!(f, "{code:02}")
+    }
+}
+
+impl Display for BrakingAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            BrakingAction::FrictionCoefficient(hundredths) => *hundredths,
+            BrakingAction::Poor => 91,
+            BrakingAction::MediumPoor => 92,
+            BrakingAction::Medium => 93,
+            BrakingAction::MediumGood => 94,
+            BrakingAction::Good => 95,
+            BrakingAction::Unreliable => 99,
+        };
+        write!(f, "{code:02}")
+    }
+}
+
+impl<S: AsRef<str>> Display for WindShear<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindShear::AllRunways => f.write_str("WS ALL RWY"),
+            WindShear::Runway(runway) => write!(f, "WS R{}", runway.as_ref()),
+        }
+    }
+}
+
+fn digits(n: usize) -> impl Fn(&str) -> IResult<&str, u16> {
+    move |input| {
+        map_res(
+            take_while_m_n(n, n, |c: char| c.is_ascii_digit()),
+            str::parse,
+        )
+        .parse(input)
+    }
+}
+
+fn nom_runway_designator(input: &str) -> IResult<&str, &str> {
+    recognize((
+        take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+        opt(one_of("LCR")),
+    ))
+    .parse(input)
+}
+
+fn nom_deposit(input: &str) -> IResult<&str, OptionalData<RunwayDeposit, 1>> {
+    OptionalData::optional_field(map_opt(digits(1), |d| {
+        Some(match d {
+            0 => RunwayDeposit::ClearAndDry,
+            1 => RunwayDeposit::Damp,
+            2 => RunwayDeposit::WetOrWaterPatches,
+            3 => RunwayDeposit::RimeOrFrost,
+            4 => RunwayDeposit::DrySnow,
+            5 => RunwayDeposit::WetSnow,
+            6 => RunwayDeposit::Slush,
+            7 => RunwayDeposit::Ice,
+            8 => RunwayDeposit::CompactedSnow,
+            9 => RunwayDeposit::FrozenRuts,
+            _ => return None,
+        })
+    }))
+    .parse(input)
+}
+
+fn nom_extent(input: &str) -> IResult<&str, OptionalData<ContaminationExtent, 1>> {
+    OptionalData::optional_field(alt((
+        value(ContaminationExtent::UpTo10Percent, char('1')),
+        value(ContaminationExtent::UpTo25Percent, char('2')),
+        value(ContaminationExtent::UpTo50Percent, char('5')),
+        value(ContaminationExtent::UpTo100Percent, char('9')),
+    )))
+    .parse(input)
+}
+
+fn nom_depth(input: &str) -> IResult<&str, OptionalData<DepositDepth, 2>> {
+    OptionalData::optional_field(map_opt(digits(2), |d| match d {
+        0..=90 => Some(DepositDepth::Millimetres(d)),
+        92..=97 => Some(DepositDepth::Millimetres((d - 90) * 50)),
+        98 => Some(DepositDepth::FortyCentimetresOrMore),
+        99 => Some(DepositDepth::RunwayNotOperational),
+        _ => None,
+    }))
+    .parse(input)
+}
+
+fn nom_braking(input: &str) -> IResult<&str, OptionalData<BrakingAction, 2>> {
+    OptionalData::optional_field(map_opt(digits(2), |d| match d {
+        0..=90 => Some(BrakingAction::FrictionCoefficient(d as u8)),
+        91 => Some(BrakingAction::Poor),
+        92 => Some(BrakingAction::MediumPoor),
+        93 => Some(BrakingAction::Medium),
+        94 => Some(BrakingAction::MediumGood),
+        95 => Some(BrakingAction::Good),
+        99 => Some(BrakingAction::Unreliable),
+        _ => None,
+    }))
+    .parse(input)
+}
+
+fn token_end(input: &str) -> IResult<&str, ()> {
+    peek(alt((value((), char(' ')), value((), nom::combinator::eof)))).parse(input)
+}
+
+pub(crate) fn nom_runway_state(input: &str) -> IResult<&str, RunwayState<&str>> {
+    map(
+        terminated(
+            (
+                preceded(char('R'), terminated(nom_runway_designator, char('/'))),
+                alt((
+                    map(preceded(tag("CLRD"), nom_braking), |braking| {
+                        RunwayStateReport::Cleared { braking }
+                    }),
+                    map(
+                        (nom_deposit, nom_extent, nom_depth, nom_braking),
+                        |(deposit, extent, depth, braking)| RunwayStateReport::Contaminated {
+                            deposit,
+                            extent,
+                            depth,
+                            braking,
+                        },
+                    ),
+                )),
+            ),
+            token_end,
+        ),
+        |(runway, report)| RunwayState { runway, report },
+    )
+    .parse(input)
+}
+
+pub(crate) fn nom_runway_states(input: &str) -> IResult<&str, Vec<RunwayState<&str>>> {
+    separated_list1(space1, nom_runway_state).parse(input)
+}
+
+/// `SNOCLO` or `R/SNOCLO`: the aerodrome is closed due to snow.
+pub(crate) fn nom_snow_closed(input: &str) -> IResult<&str, ()> {
+    value((), preceded(opt(tag("R/")), tag("SNOCLO"))).parse(input)
+}
+
+fn nom_single_wind_shear(input: &str) -> IResult<&str, WindShear<&str>> {
+    preceded(
+        tag("WS "),
+        alt((
+            value(WindShear::AllRunways, tag("ALL RWY")),
+            map(
+                preceded(alt((tag("RWY"), tag("R"))), nom_runway_designator),
+                WindShear::Runway,
+            ),
+        )),
+    )
+    .parse(input)
+}
+
+pub(crate) fn nom_wind_shear(input: &str) -> IResult<&str, Vec<WindShear<&str>>> {
+    separated_list1(char(' '), terminated(nom_single_wind_shear, token_end)).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contaminated_runway() {
+        let expected = RunwayState {
+            runway: "01L",
+            report: RunwayStateReport::Contaminated {
+                deposit: OptionalData::Data(RunwayDeposit::WetOrWaterPatches),
+                extent: OptionalData::Data(ContaminationExtent::UpTo100Percent),
+                depth: OptionalData::Data(DepositDepth::Millimetres(0)),
+                braking: OptionalData::Data(BrakingAction::Good),
+            },
+        };
+        assert_eq!(nom_runway_state("R01L/290095"), Ok(("", expected)));
+    }
+
+    #[test]
+    fn test_cleared_runway() {
+        let expected = RunwayState {
+            runway: "19R",
+            report: RunwayStateReport::Cleared {
+                braking: OptionalData::Undefined,
+            },
+        };
+        assert_eq!(nom_runway_state("R19R/CLRD//"), Ok(("", expected)));
+    }
+
+    #[test]
+    fn test_deep_snow_and_friction() {
+        let (_, state) = nom_runway_state("R88/459335").unwrap();
+        assert_eq!(
+            state.report,
+            RunwayStateReport::Contaminated {
+                deposit: OptionalData::Data(RunwayDeposit::DrySnow),
+                extent: OptionalData::Data(ContaminationExtent::UpTo50Percent),
+                depth: OptionalData::Data(DepositDepth::Millimetres(150)),
+                braking: OptionalData::Data(BrakingAction::FrictionCoefficient(35)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_rvr_is_not_runway_state() {
+        assert!(nom_runway_state("R01L/1200").is_err());
+        assert!(nom_runway_state("R01L/P2000N").is_err());
+    }
+
+    #[test]
+    fn test_snow_closed() {
+        assert_eq!(nom_snow_closed("SNOCLO"), Ok(("", ())));
+        assert_eq!(nom_snow_closed("R/SNOCLO"), Ok(("", ())));
+    }
+
+    #[test]
+    fn test_wind_shear() {
+        assert_eq!(
+            nom_wind_shear("WS R19R WS RWY01L"),
+            Ok(("", vec![WindShear::Runway("19R"), WindShear::Runway("01L")]))
+        );
+        assert_eq!(
+            nom_wind_shear("WS ALL RWY"),
+            Ok(("", vec![WindShear::AllRunways]))
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::{self, Display};
+
 use nom::{
     Parser,
     branch::alt,
@@ -5,21 +7,27 @@ use nom::{
     sequence::{preceded, separated_pair},
 };
 
-use crate::{optional_data::OptionalData, temperature::nom_maybe_negative_temp};
+use crate::{
+    optional_data::OptionalData,
+    temperature::{nom_maybe_negative_temp, write_temperature},
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SeaSurfaceIndicator {
     pub temperature: OptionalData<i32, 2>,
     pub state_of_sea: OptionalData<StateOfSea, 2>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StateOfSea {
     SeaState(OptionalData<CodeTable3700, 1>),
     SignificantWaveHeight(OptionalData<u16, 3>), // in decimeters
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CodeTable3700 {
     CalmGlassLike = 0,
@@ -34,6 +42,29 @@ pub enum CodeTable3700 {
     Phenomenal = 9,
 }
 
+impl Display for SeaSurfaceIndicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("W")?;
+        write_temperature(f, self.temperature)?;
+        write!(f, "/{}", self.state_of_sea)
+    }
+}
+
+impl Display for StateOfSea {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateOfSea::SeaState(state) => write!(f, "S{state}"),
+            StateOfSea::SignificantWaveHeight(height) => write!(f, "H{height}"),
+        }
+    }
+}
+
+impl Display for CodeTable3700 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.clone() as u8)
+    }
+}
+
 pub(crate) fn nom_sea_surface_indicator(input: &str) -> nom::IResult<&str, SeaSurfaceIndicator> {
     let (rest, (temperature, state_of_sea)) = separated_pair(
         preceded(
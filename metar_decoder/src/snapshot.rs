@@ -0,0 +1,44 @@
+//! JSON snapshots of the decoded corpus files, one report per line so the
+//! snapshot lines up with the corpus. Run the tests with `UPDATE_SNAPSHOTS=1`
+//! to rewrite them after an intended change to the JSON format.
+
+use std::{fmt::Debug, fs, path::Path};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+pub(crate) fn assert_json_lines_snapshot<T>(name: &str, values: &[Option<T>])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let mut actual = String::new();
+    for value in values {
+        let json = serde_json::to_string(value).unwrap();
+        let decoded: Option<T> = serde_json::from_str(&json).unwrap();
+        assert_eq!(&decoded, value, "{json}");
+        actual.push_str(&json);
+        actual.push('\n');
+    }
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "Failed to read {}: {e}. Run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    for (number, (actual, expected)) in actual.lines().zip(expected.lines()).enumerate() {
+        assert_eq!(actual, expected, "{name}:{}", number + 1);
+    }
+    assert_eq!(
+        actual.lines().count(),
+        expected.lines().count(),
+        "{name} has a different number of lines"
+    );
+}
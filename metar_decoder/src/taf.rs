@@ -0,0 +1,549 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+use jiff::{Zoned, tz::TimeZone};
+use nom::{
+    Finish, IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take, take_while_m_n},
+    character::complete::{char, u8},
+    combinator::{eof, map_res, opt, peek, value, verify},
+    error::{Error, ErrorKind},
+    multi::many0,
+    sequence::{preceded, separated_pair, terminated},
+};
+use tracing::warn;
+
+use crate::{
+    obscuration::{
+        Cloud, PresentWeather, VerticalVisibility, Visibility, nom_cloud, nom_present_weather,
+        nom_vertical_visibility, nom_visibility,
+    },
+    temperature::nom_maybe_negative_temp,
+    units::timestamp::{Timestamp, nom_metar_timestamp_with_zone, resolve_day_time},
+    wind::{Wind, nom_wind},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Taf {
+    pub raw: String,
+    pub icao: String,
+    pub issued: Timestamp,
+    pub amended: bool,
+    pub corrected: bool,
+    pub nil: bool,
+    pub cancelled: bool,
+    /// Missing for NIL reports.
+    pub validity: Option<ValidityPeriod>,
+    pub base: ForecastConditions,
+    pub temperatures: Vec<TemperatureForecast>,
+    pub changes: Vec<ChangeGroup>,
+    pub remarks: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidityPeriod {
+    pub from: Timestamp,
+    pub until: Timestamp,
+}
+
+/// Weather elements shared by the base forecast and every change group.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForecastConditions<S = String> {
+    pub wind: Option<Wind>,
+    pub cavok: bool,
+    pub visibility: Option<Visibility>,
+    pub nsw: bool,
+    pub present_weather: Vec<PresentWeather>,
+    pub clouds: Vec<Cloud<S>>,
+    pub vertical_visibility: Option<VerticalVisibility>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChangeGroup {
+    pub kind: ChangeKind,
+    /// For `FM` groups this ends at the next `FM` group, or at the end of the
+    /// TAF validity when there is none.
+    pub period: ValidityPeriod,
+    pub conditions: ForecastConditions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeKind {
+    From,
+    Becoming,
+    Temporary,
+    Probability { percent: u8, temporary: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemperatureForecast {
+    pub kind: TemperatureKind,
+    pub temperature: i32,
+    pub at: Timestamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TemperatureKind {
+    Maximum,
+    Minimum,
+}
+
+impl ForecastConditions<&str> {
+    pub fn to_owned(&self) -> ForecastConditions {
+        ForecastConditions {
+            wind: self.wind.clone(),
+            cavok: self.cavok,
+            visibility: self.visibility.clone(),
+            nsw: self.nsw,
+            present_weather: self.present_weather.clone(),
+            clouds: self.clouds.iter().map(|cloud| cloud.to_owned()).collect(),
+            vertical_visibility: self.vertical_visibility.clone(),
+        }
+    }
+}
+
+impl<S: AsRef<str>> Display for ForecastConditions<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups = self
+            .wind
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.cavok.then(|| "CAVOK".to_string()))
+            .chain(self.visibility.iter().map(ToString::to_string))
+            .chain(self.nsw.then(|| "NSW".to_string()))
+            .chain(self.present_weather.iter().map(ToString::to_string))
+            .chain(self.clouds.iter().map(ToString::to_string))
+            .chain(self.vertical_visibility.iter().map(ToString::to_string))
+            .join(" ");
+        f.write_str(&groups)
+    }
+}
+
+impl FromStr for Taf {
+    type Err = nom::error::Error<String>;
+
+    #[tracing::instrument(name = "taf_parse")]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut now = Zoned::now().with_time_zone(TimeZone::UTC);
+        now += jiff::SignedDuration::from_hours(1);
+        parse_taf(s, &now)
+    }
+}
+
+/// TAFs are usually split over several lines, so all whitespace is collapsed
+/// before parsing.
+fn parse_taf(s: &str, reference: &Zoned) -> Result<Taf, nom::error::Error<String>> {
+    let normalized = s.split_whitespace().join(" ");
+    let normalized = normalized.trim_end_matches('=').trim_end();
+    let (rest, taf) = nom_taf(normalized, reference).finish().inspect_err(|e| {
+        warn!(?e);
+    })?;
+    if !rest.is_empty() {
+        warn!(?rest, "Unparsed input remains");
+        return Err(nom::error::Error::new(
+            rest.to_string(),
+            nom::error::ErrorKind::NonEmpty,
+        ));
+    }
+    Ok(taf)
+}
+
+fn nom_taf<'a>(input: &'a str, reference: &Zoned) -> IResult<&'a str, Taf> {
+    let (rest, (amended, corrected, icao, issued)) = (
+        preceded(opt(tag("TAF ")), opt(tag("AMD ")).map(|a| a.is_some())),
+        opt(tag("COR ")).map(|c| c.is_some()),
+        take(4usize),
+        preceded(char(' '), |i| {
+            nom_metar_timestamp_with_zone(i, &mut reference.clone())
+        }),
+    )
+        .parse(input)?;
+    let mut taf = Taf {
+        raw: input.to_string(),
+        icao: icao.to_string(),
+        issued,
+        amended,
+        corrected,
+        nil: false,
+        cancelled: false,
+        validity: None,
+        base: ForecastConditions::default(),
+        temperatures: Vec::new(),
+        changes: Vec::new(),
+        remarks: None,
+    };
+
+    if let (rest, Some(_)) = opt(tag(" NIL")).parse(rest)? {
+        taf.nil = true;
+        return Ok((rest, taf));
+    }
+
+    let issued = taf.issued.zoned().clone();
+    let (rest, validity) = preceded(char(' '), |i| nom_validity_period(i, &issued)).parse(rest)?;
+    let from = validity.from.zoned().clone();
+    taf.validity = Some(validity);
+
+    if let (rest, Some(_)) = opt(tag(" CNL")).parse(rest)? {
+        taf.cancelled = true;
+        return Ok((rest, taf));
+    }
+
+    let (rest, (base, mut temperatures, changes, trailing_temperatures, remarks)) = (
+        nom_forecast_conditions,
+        many0(preceded(char(' '), |i| nom_temperature_forecast(i, &from))),
+        many0(preceded(char(' '), |i| nom_change_group(i, &from))),
+        many0(preceded(char(' '), |i| nom_temperature_forecast(i, &from))),
+        opt(preceded(tag(" RMK "), nom::combinator::rest)),
+    )
+        .parse(rest)?;
+    temperatures.extend(trailing_temperatures);
+
+    taf.base = base.to_owned();
+    taf.temperatures = temperatures;
+    taf.changes = changes;
+    taf.remarks = remarks.map(str::to_string);
+    close_from_groups(&mut taf);
+    Ok((rest, taf))
+}
+
+/// `FM` groups only carry a start time, so they are closed by the next `FM`
+/// group or by the end of the TAF validity.
+fn close_from_groups(taf: &mut Taf) {
+    let Some(validity) = &taf.validity else {
+        return;
+    };
+    let mut until = validity.until.clone();
+    for group in taf.changes.iter_mut().rev() {
+        if group.kind == ChangeKind::From {
+            group.period.until = until;
+            until = group.period.from.clone();
+        }
+    }
+}
+
+fn token_end(input: &str) -> IResult<&str, ()> {
+    value((), peek(alt((tag(" "), eof)))).parse(input)
+}
+
+fn nom_two_digits(input: &str) -> IResult<&str, i8> {
+    map_res(
+        take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+        str::parse::<i8>,
+    )
+    .parse(input)
+}
+
+fn resolve<'a>(
+    input: &'a str,
+    reference: &Zoned,
+    (day, hour, minute): (i8, i8, i8),
+) -> Result<Timestamp, nom::Err<Error<&'a str>>> {
+    resolve_day_time(reference, day, hour, minute)
+        .map(Timestamp::new)
+        .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Verify)))
+}
+
+fn nom_day_hour<'a>(input: &'a str, reference: &Zoned) -> IResult<&'a str, Timestamp> {
+    let (rest, (day, hour)) = (nom_two_digits, nom_two_digits).parse(input)?;
+    Ok((rest, resolve(input, reference, (day, hour, 0))?))
+}
+
+fn nom_validity_period<'a>(input: &'a str, reference: &Zoned) -> IResult<&'a str, ValidityPeriod> {
+    let (rest, (from, (day, hour))) = separated_pair(
+        |i| nom_day_hour(i, reference),
+        char('/'),
+        (nom_two_digits, nom_two_digits),
+    )
+    .parse(input)?;
+    let until = resolve(input, from.zoned(), (day, hour, 0))?;
+    Ok((rest, ValidityPeriod { from, until }))
+}
+
+pub(crate) fn nom_forecast_conditions(input: &str) -> IResult<&str, ForecastConditions<&str>> {
+    (
+        opt(preceded(char(' '), nom_wind)),
+        opt(preceded(char(' '), tag("CAVOK"))).map(|c| c.is_some()),
+        opt(preceded(char(' '), nom_visibility)),
+        opt(preceded(char(' '), tag("NSW"))).map(|n| n.is_some()),
+        many0(preceded(
+            char(' '),
+            terminated(nom_present_weather, token_end),
+        )),
+        many0(preceded(char(' '), terminated(nom_cloud, token_end))),
+        opt(preceded(char(' '), nom_vertical_visibility)),
+    )
+        .map(
+            |(wind, cavok, visibility, nsw, present_weather, clouds, vertical_visibility)| {
+                ForecastConditions {
+                    wind,
+                    cavok,
+                    visibility,
+                    nsw,
+                    present_weather,
+                    clouds,
+                    vertical_visibility,
+                }
+            },
+        )
+        .parse(input)
+}
+
+fn nom_change_group<'a>(input: &'a str, reference: &Zoned) -> IResult<&'a str, ChangeGroup> {
+    let (rest, (kind, period)) = alt((
+        |i: &'a str| {
+            let (rest, fields) =
+                preceded(tag("FM"), (nom_two_digits, nom_two_digits, nom_two_digits)).parse(i)?;
+            let from = resolve(i, reference, fields)?;
+            let period = ValidityPeriod {
+                until: from.clone(),
+                from,
+            };
+            Ok((rest, (ChangeKind::From, period)))
+        },
+        (
+            alt((
+                value(ChangeKind::Becoming, tag("BECMG")),
+                value(ChangeKind::Temporary, tag("TEMPO")),
+                (
+                    preceded(tag("PROB"), verify(u8, |p| *p == 30 || *p == 40)),
+                    opt(tag(" TEMPO")),
+                )
+                    .map(|(percent, tempo)| ChangeKind::Probability {
+                        percent,
+                        temporary: tempo.is_some(),
+                    }),
+            )),
+            preceded(char(' '), |i| nom_validity_period(i, reference)),
+        ),
+    ))
+    .parse(input)?;
+    let (rest, conditions) = nom_forecast_conditions(rest)?;
+    Ok((
+        rest,
+        ChangeGroup {
+            kind,
+            period,
+            conditions: conditions.to_owned(),
+        },
+    ))
+}
+
+fn nom_temperature_forecast<'a>(
+    input: &'a str,
+    reference: &Zoned,
+) -> IResult<&'a str, TemperatureForecast> {
+    let (rest, (kind, temperature, at)) = (
+        alt((
+            value(TemperatureKind::Maximum, tag("TX")),
+            value(TemperatureKind::Minimum, tag("TN")),
+        )),
+        terminated(nom_maybe_negative_temp, char('/')),
+        terminated(|i| nom_day_hour(i, reference), char('Z')),
+    )
+        .parse(input)?;
+    Ok((
+        rest,
+        TemperatureForecast {
+            kind,
+            temperature,
+            at,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::date;
+    use tracing_test::traced_test;
+
+    use crate::{
+        obscuration::{CloudCoverage, CloudData, VisibilityUnit, WeatherPhenomenon},
+        optional_data::OptionalData,
+        units::altitudes::CloudHeight,
+    };
+
+    fn reference() -> Zoned {
+        date(2025, 6, 16)
+            .at(12, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap()
+    }
+
+    fn utc(month: i8, day: i8, hour: i8, minute: i8) -> Timestamp {
+        Timestamp::new(
+            date(2025, month, day)
+                .at(hour, minute, 0, 0)
+                .to_zoned(TimeZone::UTC)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_parse() {
+        let input = include_str!("../test.tafs");
+        input.lines().for_each(|line| {
+            if let Err(e) = parse_taf(line, &reference()) {
+                panic!("Failed to parse line '{}': {:?}", line, e);
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_json_snapshot() {
+        let tafs = include_str!("../test.tafs")
+            .lines()
+            .map(|line| parse_taf(line, &reference()).ok())
+            .collect_vec();
+        crate::snapshot::assert_json_lines_snapshot("test.tafs.jsonl", &tafs);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_parse_groups() {
+        let input = "TAF ENGM 161100Z 1612/1718 19010KT 9999 FEW030 TX22/1614Z TN12/1704Z \
+                     BECMG 1614/1616 24015G25KT \
+                     TEMPO 1618/1624 4000 -SHRA BKN012 \
+                     PROB30 TEMPO 1700/1706 0800 FG VV002 \
+                     FM170800 27010KT CAVOK";
+        let taf = parse_taf(input, &reference()).unwrap();
+        assert_eq!(taf.icao, "ENGM");
+        assert_eq!(taf.issued, utc(6, 16, 11, 0));
+        assert_eq!(
+            taf.validity,
+            Some(ValidityPeriod {
+                from: utc(6, 16, 12, 0),
+                until: utc(6, 17, 18, 0),
+            })
+        );
+        assert_eq!(
+            taf.base.clouds,
+            vec![Cloud::CloudData(CloudData {
+                coverage: OptionalData::Data(CloudCoverage::Few),
+                height: OptionalData::Data(CloudHeight { height: 30 }),
+                cloud_type: None,
+            })]
+        );
+        assert_eq!(
+            taf.temperatures,
+            vec![
+                TemperatureForecast {
+                    kind: TemperatureKind::Maximum,
+                    temperature: 22,
+                    at: utc(6, 16, 14, 0),
+                },
+                TemperatureForecast {
+                    kind: TemperatureKind::Minimum,
+                    temperature: 12,
+                    at: utc(6, 17, 4, 0),
+                },
+            ]
+        );
+
+        let kinds = taf.changes.iter().map(|c| c.kind).collect_vec();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::Becoming,
+                ChangeKind::Temporary,
+                ChangeKind::Probability {
+                    percent: 30,
+                    temporary: true
+                },
+                ChangeKind::From,
+            ]
+        );
+        assert_eq!(taf.changes[1].period.until, utc(6, 17, 0, 0));
+        assert_eq!(
+            taf.changes[1].conditions.present_weather[0].phenomena,
+            vec![OptionalData::Data(WeatherPhenomenon::RA)]
+        );
+        assert_eq!(
+            taf.changes[2].conditions.visibility.as_ref().unwrap().value,
+            VisibilityUnit::Meters(OptionalData::Data(800))
+        );
+        assert!(taf.changes[2].conditions.vertical_visibility.is_some());
+        assert!(taf.changes[3].conditions.cavok);
+        assert_eq!(
+            taf.changes[3].period,
+            ValidityPeriod {
+                from: utc(6, 17, 8, 0),
+                until: utc(6, 17, 18, 0),
+            }
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_from_groups_close_each_other() {
+        let input = "TAF KJFK 161120Z 1612/1718 19012KT P6SM FEW050 \
+                     FM161800 20015G25KT P6SM SCT040 \
+                     FM170200 21010KT P6SM BKN035";
+        let taf = parse_taf(input, &reference()).unwrap();
+        assert_eq!(taf.changes[0].period.until, utc(6, 17, 2, 0));
+        assert_eq!(taf.changes[1].period.until, utc(6, 17, 18, 0));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_month_rollover() {
+        let reference = date(2025, 6, 30)
+            .at(12, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap();
+        let taf = parse_taf("TAF ENBR 301100Z 3012/0118 VRB03KT CAVOK", &reference).unwrap();
+        assert_eq!(taf.validity.unwrap().until, utc(7, 1, 18, 0));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_nil_and_cancelled() {
+        let taf = parse_taf("TAF ENRY 161100Z NIL=", &reference()).unwrap();
+        assert!(taf.nil);
+        assert!(taf.validity.is_none());
+
+        let taf = parse_taf("TAF AMD ENGM 161130Z 1612/1718 CNL", &reference()).unwrap();
+        assert!(taf.amended);
+        assert!(taf.cancelled);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_multiline() {
+        let input = "TAF ENZV 161100Z 1612/1712 30012KT 9999 FEW025\n      TEMPO 1612/1618 4000 SHRA BKN012=";
+        let taf = parse_taf(input, &reference()).unwrap();
+        assert_eq!(taf.changes.len(), 1);
+        assert_eq!(
+            taf.raw,
+            "TAF ENZV 161100Z 1612/1712 30012KT 9999 FEW025 TEMPO 1612/1618 4000 SHRA BKN012"
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_weather_does_not_eat_prob() {
+        let input = "TAF ESSA 161100Z 1612/1712 22008KT 9999 -RA BKN015 PROB40 1614/1618 3000 RA";
+        let taf = parse_taf(input, &reference()).unwrap();
+        assert_eq!(taf.base.present_weather.len(), 1);
+        assert_eq!(
+            taf.changes[0].kind,
+            ChangeKind::Probability {
+                percent: 40,
+                temporary: false
+            }
+        );
+    }
+}
@@ -1,23 +1,53 @@
+use std::fmt::{self, Display};
+
 use nom::{
     IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
     character::complete::{char, i32},
-    combinator::opt,
+    combinator::{eof, opt, peek, value},
     sequence::separated_pair,
 };
 
 use crate::optional_data::OptionalData;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TemperatureInfo {
     pub temp: OptionalData<i32, 2>,
     pub dew_point: OptionalData<i32, 2>,
 }
 
+impl Display for TemperatureInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_temperature(f, self.temp)?;
+        f.write_str("/")?;
+        write_temperature(f, self.dew_point)
+    }
+}
+
+/// Negative temperatures are written with an `M` prefix, e.g. `M05`.
+pub(crate) fn write_temperature(
+    f: &mut fmt::Formatter<'_>,
+    temperature: OptionalData<i32, 2>,
+) -> fmt::Result {
+    match temperature {
+        OptionalData::Data(t) if t < 0 => write!(f, "M{:02}", -t),
+        OptionalData::Data(t) => write!(f, "{t:02}"),
+        OptionalData::Undefined => f.write_str("//"),
+    }
+}
+
+/// A dew point left out entirely (`23/`) means its sensor is unavailable
+/// and is decoded like `//`.
 pub(crate) fn nom_temperature_info(input: &str) -> IResult<&str, TemperatureInfo> {
     separated_pair(
         OptionalData::optional_field(nom_maybe_negative_temp),
         char('/'),
-        OptionalData::optional_field(nom_maybe_negative_temp),
+        alt((
+            OptionalData::optional_field(nom_maybe_negative_temp),
+            value(OptionalData::Undefined, peek(alt((tag(" "), eof)))),
+        )),
     )
     .map(|(temp, dew_point)| TemperatureInfo { temp, dew_point })
     .parse(input)
@@ -51,4 +81,14 @@ mod tests {
         let result_with_negative = nom_temperature_info(input_with_negative);
         assert_eq!(Ok(("", expected_with_negative)), result_with_negative);
     }
+
+    #[test]
+    fn test_missing_dew_point() {
+        let expected = TemperatureInfo {
+            temp: OptionalData::Data(23),
+            dew_point: OptionalData::Undefined,
+        };
+        assert_eq!(nom_temperature_info("23/ A3000"), Ok((" A3000", expected)));
+        assert_eq!(expected.to_string(), "23///");
+    }
 }
@@ -1,45 +1,188 @@
+use std::fmt::{self, Display};
+
+use jiff::Zoned;
 use nom::{
-    Parser, bytes::complete::tag, character::complete::space0, combinator::opt, multi::many1,
+    Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::char,
+    combinator::{map_res, opt, value},
+    error::{Error, ErrorKind},
     sequence::preceded,
 };
 
 use crate::{
-    obscuration::{
-        Cloud, PresentWeather, VerticalVisibility, Visibility, nom_cloud, nom_present_weather,
-        nom_vertical_visibility, nom_visibility,
-    },
-    wind::{Wind, nom_wind},
+    nato_mil_code::{NatoMilCode, nom_nato_mil_code},
+    optional_data::OptionalData,
+    taf::{ForecastConditions, nom_forecast_conditions},
+    units::timestamp::{Timestamp, resolve_hour_minute_after},
 };
 
-#[derive(Debug, Clone)]
-pub struct Trend {
-    pub wind: Option<Wind>,
-    pub visibility: Option<Visibility>,
-    pub nsw: bool,
-    pub expected_present_weather: Option<Vec<PresentWeather>>,
-    pub cloud_layers: Option<Vec<Cloud>>,
-    pub vertical_visibility: Option<VerticalVisibility>,
-    // TODO: Add more types that can come here.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrendGroup<S = String> {
+    pub kind: TrendKind,
+    pub from: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+    pub at: Option<Timestamp>,
+    pub conditions: ForecastConditions<S>,
+    pub nato_mil_code: Option<OptionalData<NatoMilCode, 3>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrendKind {
+    Becoming,
+    Temporary,
+}
+
+impl TrendGroup<&str> {
+    pub fn to_owned(&self) -> TrendGroup {
+        TrendGroup {
+            kind: self.kind,
+            from: self.from.clone(),
+            until: self.until.clone(),
+            at: self.at.clone(),
+            conditions: self.conditions.to_owned(),
+            nato_mil_code: self.nato_mil_code.clone(),
+        }
+    }
 }
 
-pub(crate) fn nom_becoming(input: &str) -> nom::IResult<&str, Trend> {
+impl<S: AsRef<str>> Display for TrendGroup<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)?;
+        let times = [("FM", &self.from), ("TL", &self.until), ("AT", &self.at)];
+        for (prefix, time) in times {
+            if let Some(time) = time {
+                write!(f, " {prefix}{}", time.zoned().strftime("%H%M"))?;
+            }
+        }
+        let conditions = self.conditions.to_string();
+        if !conditions.is_empty() {
+            write!(f, " {conditions}")?;
+        }
+        match &self.nato_mil_code {
+            Some(code) => write!(f, " {code}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for TrendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrendKind::Becoming => "BECMG",
+            TrendKind::Temporary => "TEMPO",
+        })
+    }
+}
+
+fn nom_trend_time<'a>(
+    input: &'a str,
+    prefix: &'static str,
+    reference: &Zoned,
+) -> nom::IResult<&'a str, Timestamp> {
+    let two_digits = || {
+        map_res(
+            take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+            str::parse::<i8>,
+        )
+    };
+    let (rest, (hour, minute)) =
+        preceded((char(' '), tag(prefix)), (two_digits(), two_digits())).parse(input)?;
+    let timestamp = resolve_hour_minute_after(reference, hour, minute)
+        .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Verify)))?;
+    Ok((rest, Timestamp::new(timestamp)))
+}
+
+/// Parses a single `BECMG`/`TEMPO` group including its `FM`/`TL`/`AT` times,
+/// which are resolved against the observation time in `reference`.
+pub(crate) fn nom_trend_group<'a>(
+    input: &'a str,
+    reference: &Zoned,
+) -> nom::IResult<&'a str, TrendGroup<&'a str>> {
     (
-        opt(preceded(space0, nom_wind)),
-        opt(preceded(space0, nom_visibility)),
-        opt(preceded(space0, tag("NSW"))).map(|s| s.is_some()),
-        opt(many1(preceded(space0, nom_present_weather))),
-        opt(many1(preceded(space0, nom_cloud))),
-        opt(preceded(space0, nom_vertical_visibility)),
+        alt((
+            value(TrendKind::Becoming, tag("BECMG")),
+            value(TrendKind::Temporary, tag("TEMPO")),
+        )),
+        opt(|i| nom_trend_time(i, "FM", reference)),
+        opt(|i| nom_trend_time(i, "TL", reference)),
+        opt(|i| nom_trend_time(i, "AT", reference)),
+        nom_forecast_conditions,
+        opt(preceded(
+            char(' '),
+            OptionalData::optional_field(nom_nato_mil_code),
+        )),
     )
         .map(
-            |(wind, visibility, nsw, expected_present_weather, cloud_layers, vv)| Trend {
-                wind,
-                visibility,
-                nsw,
-                cloud_layers,
-                expected_present_weather,
-                vertical_visibility: vv,
+            |(kind, from, until, at, conditions, nato_mil_code)| TrendGroup {
+                kind,
+                from,
+                until,
+                at,
+                conditions,
+                nato_mil_code,
             },
         )
         .parse(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::{civil::date, tz::TimeZone};
+
+    use crate::obscuration::{Visibility, VisibilityUnit};
+
+    fn observed() -> Zoned {
+        date(2025, 6, 16)
+            .at(23, 20, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_trend_times() {
+        let (rest, group) = nom_trend_group("BECMG FM2330 TL0100 3000 BR", &observed()).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(group.kind, TrendKind::Becoming);
+        assert_eq!(
+            group.from.unwrap().zoned().datetime(),
+            date(2025, 6, 16).at(23, 30, 0, 0)
+        );
+        assert_eq!(
+            group.until.unwrap().zoned().datetime(),
+            date(2025, 6, 17).at(1, 0, 0, 0)
+        );
+        assert_eq!(
+            group.conditions.visibility,
+            Some(Visibility {
+                value: VisibilityUnit::Meters(OptionalData::Data(3000)),
+                direction: None,
+                ndv: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_trend_until_midnight() {
+        let (_, group) = nom_trend_group("TEMPO TL2400 VRB15G25KT", &observed()).unwrap();
+        assert_eq!(group.kind, TrendKind::Temporary);
+        assert_eq!(
+            group.until.unwrap().zoned().datetime(),
+            date(2025, 6, 17).at(0, 0, 0, 0)
+        );
+        assert!(group.conditions.wind.is_some());
+    }
+
+    #[test]
+    fn test_trend_times_out_of_range() {
+        for time in ["FM2599", "TL2460", "AT9900"] {
+            let input = format!("BECMG {time} 3000");
+            let (_, group) = nom_trend_group(&input, &observed()).unwrap();
+            assert!(group.from.is_none() && group.until.is_none() && group.at.is_none());
+        }
+    }
+}
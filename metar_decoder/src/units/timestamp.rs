@@ -15,6 +15,8 @@ use nom::{
 };
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Timestamp {
     timestamp: Zoned,
 }
@@ -47,6 +49,14 @@ fn previous_month(year: i16, month: i8) -> (i16, i8) {
     }
 }
 
+fn next_month(year: i16, month: i8) -> (i16, i8) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
 fn build_candidate(
     cmp: &Zoned,
     year: i16,
@@ -78,13 +88,81 @@ fn get_date_form_fields(cmp: &Zoned, day: i8, hour: i8, minute: i8) -> Option<Zo
     None
 }
 
+/// Resolves a day/hour/minute group without month and year (TAF validity
+/// periods, change groups and temperature forecasts) to the candidate closest
+/// to `reference`. Hour 24 is accepted and means midnight at the end of `day`.
+pub(crate) fn resolve_day_time(reference: &Zoned, day: i8, hour: i8, minute: i8) -> Option<Zoned> {
+    let (hour, end_of_day) = match (hour, minute) {
+        (24, 0) => (0, true),
+        _ => (hour, false),
+    };
+    let (year, month) = (reference.year(), reference.month());
+    let (previous_year, previous) = previous_month(year, month);
+    let (next_year, next) = next_month(year, month);
+    [(previous_year, previous), (year, month), (next_year, next)]
+        .into_iter()
+        .filter_map(|(year, month)| build_candidate(reference, year, month, day, hour, minute))
+        .map(|mut candidate| {
+            if end_of_day {
+                candidate += jiff::SignedDuration::from_hours(24);
+            }
+            candidate
+        })
+        .min_by_key(|candidate| {
+            candidate
+                .timestamp()
+                .duration_since(reference.timestamp())
+                .abs()
+        })
+}
+
+/// Resolves an hour/minute group (METAR trend `FM`/`TL`/`AT` times) to the
+/// first matching time at or after `reference`. `2400` is midnight at the end
+/// of the day.
+pub(crate) fn resolve_hour_minute_after(reference: &Zoned, hour: i8, minute: i8) -> Option<Zoned> {
+    let (hour, end_of_day) = match (hour, minute) {
+        (24, 0) => (0, true),
+        _ => (hour, false),
+    };
+    let time = Time::new(hour, minute, 0, 0).ok()?;
+    let mut candidate = reference
+        .date()
+        .to_datetime(time)
+        .to_zoned(reference.time_zone().clone())
+        .ok()?;
+    if end_of_day || candidate.timestamp() < reference.timestamp() {
+        candidate += jiff::SignedDuration::from_hours(24);
+    }
+    Some(candidate)
+}
+
+/// Resolves a remark time (`PK WND`, `WSHFT`) to the last matching time at or
+/// before the observation in `reference`. Without an hour the time is in the
+/// observation hour, or the hour before when the minute has not passed yet.
+pub(crate) fn resolve_hour_minute_before(
+    reference: &Zoned,
+    hour: Option<i8>,
+    minute: i8,
+) -> Option<Zoned> {
+    let time = Time::new(hour.unwrap_or(reference.hour()), minute, 0, 0).ok()?;
+    let mut candidate = reference
+        .date()
+        .to_datetime(time)
+        .to_zoned(reference.time_zone().clone())
+        .ok()?;
+    if candidate.timestamp() > reference.timestamp() {
+        candidate -= jiff::SignedDuration::from_hours(if hour.is_some() { 24 } else { 1 });
+    }
+    Some(candidate)
+}
+
 pub(crate) fn nom_metar_timestamp(input: &str) -> IResult<&str, Timestamp> {
     let mut now = Zoned::now().with_time_zone(TimeZone::UTC);
     now += jiff::SignedDuration::from_hours(1);
     nom_metar_timestamp_with_zone(input, &mut now)
 }
 
-fn nom_metar_timestamp_with_zone<'a>(
+pub(crate) fn nom_metar_timestamp_with_zone<'a>(
     input: &'a str,
     refernce_time: &mut Zoned,
 ) -> IResult<&'a str, Timestamp> {
@@ -182,6 +260,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolve_day_time_next_month() {
+        let reference = date(2025, 6, 30)
+            .at(11, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap();
+        let expected = date(2025, 7, 1)
+            .at(6, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap();
+        assert_eq!(resolve_day_time(&reference, 1, 6, 0), Some(expected));
+    }
+
+    #[test]
+    fn test_resolve_day_time_hour_24() {
+        let reference = make_test_timestamp();
+        let expected = date(2025, 6, 29)
+            .at(0, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap();
+        assert_eq!(resolve_day_time(&reference, 28, 24, 0), Some(expected));
+    }
+
+    #[test]
+    fn test_resolve_hour_minute_before() {
+        let reference = make_test_timestamp();
+        let at = |day, hour, minute| {
+            date(2025, 6, day)
+                .at(hour, minute, 0, 0)
+                .to_zoned(TimeZone::UTC)
+                .unwrap()
+        };
+        assert_eq!(
+            resolve_hour_minute_before(&reference, Some(15), 45),
+            Some(at(28, 15, 45))
+        );
+        assert_eq!(
+            resolve_hour_minute_before(&reference, Some(23), 10),
+            Some(at(27, 23, 10))
+        );
+        assert_eq!(
+            resolve_hour_minute_before(&reference, None, 30),
+            Some(at(28, 15, 30))
+        );
+        assert_eq!(resolve_hour_minute_before(&reference, Some(77), 77), None);
+        assert_eq!(resolve_hour_minute_before(&reference, None, 99), None);
+    }
+
     #[test]
     fn test_display() {
         let mut r = make_test_timestamp();
@@ -11,6 +11,7 @@ use thiserror::Error;
 use crate::optional_data::OptionalNumber;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Track(pub OptionalNumber<3>);
 
 #[derive(Debug, Error, Clone)]
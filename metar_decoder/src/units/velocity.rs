@@ -8,6 +8,7 @@ use nom::{
 use crate::optional_data::OptionalNumber;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindVelocity {
     pub velocity: OptionalNumber<2>,
     pub gust: Option<OptionalNumber<2>>,
@@ -15,24 +16,58 @@ pub struct WindVelocity {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VelocityUnit {
     MetersPerSecond,
     Knots,
+    KilometersPerHour,
+}
+
+impl VelocityUnit {
+    /// How many knots one of this unit is.
+    pub const fn knots_per_unit(&self) -> f64 {
+        match self {
+            VelocityUnit::Knots => 1.0,
+            VelocityUnit::MetersPerSecond => 1.943_844,
+            VelocityUnit::KilometersPerHour => 0.539_957,
+        }
+    }
+
+    const fn code(&self) -> &'static str {
+        match self {
+            VelocityUnit::Knots => "KT",
+            VelocityUnit::MetersPerSecond => "MPS",
+            VelocityUnit::KilometersPerHour => "KMH",
+        }
+    }
 }
 
 impl WindVelocity {
+    /// The gust if reported, otherwise the mean speed, in the reported unit.
     pub fn get_max_wind_speed(&self) -> Option<u32> {
         self.gust.unwrap_or(self.velocity).to_option()
     }
+
+    /// The same wind with speed and gust converted to whole knots.
+    pub fn in_knots(&self) -> WindVelocity {
+        let factor = self.unit.knots_per_unit();
+        let convert = |speed: u32| (f64::from(speed) * factor).round() as u32;
+        WindVelocity {
+            velocity: self.velocity.map(convert),
+            gust: self.gust.map(|gust| gust.map(convert)),
+            unit: VelocityUnit::Knots,
+        }
+    }
 }
 
 pub(crate) fn nom_velocity(input: &str) -> nom::IResult<&str, WindVelocity> {
     let (rest, v) = OptionalNumber::optional_field(u32).parse(input)?;
     let (rest, gust) = opt(preceded(tag("G"), OptionalNumber::optional_field(u32))).parse(rest)?;
-    let (rest, unit) = alt((tag("KT"), tag("MPS"))).parse(rest)?;
+    let (rest, unit) = alt((tag("KT"), tag("MPS"), tag("KMH"))).parse(rest)?;
     let unit = match unit {
         "KT" => VelocityUnit::Knots,
         "MPS" => VelocityUnit::MetersPerSecond,
+        "KMH" => VelocityUnit::KilometersPerHour,
         _ => {
             return Err(nom::Err::Error(nom::error::Error::new(
                 input,
@@ -56,14 +91,7 @@ impl Display for WindVelocity {
         if let Some(gust) = self.gust {
             write!(f, "G{}", gust)?;
         }
-        write!(
-            f,
-            "{}",
-            match self.unit {
-                VelocityUnit::Knots => "KT",
-                VelocityUnit::MetersPerSecond => "MPS",
-            }
-        )
+        f.write_str(self.unit.code())
     }
 }
 
@@ -105,6 +133,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_kmh() {
+        setup_test(
+            "25KMH",
+            WindVelocity {
+                velocity: Data(25),
+                unit: VelocityUnit::KilometersPerHour,
+                gust: None,
+            },
+        );
+        assert_eq!(nom_velocity("25G40KMH").unwrap().1.to_string(), "25G40KMH");
+    }
+
+    #[test]
+    fn test_in_knots() {
+        let (_, mps) = nom_velocity("05G10MPS").unwrap();
+        assert_eq!(mps.in_knots().to_string(), "10G19KT");
+        let (_, kmh) = nom_velocity("37KMH").unwrap();
+        assert_eq!(kmh.in_knots().to_string(), "20KT");
+        let (_, knots) = nom_velocity("//KT").unwrap();
+        assert_eq!(knots.in_knots(), knots);
+    }
+
     #[test]
     fn test_invalid_input() {
         let result = nom_velocity("10XYZ");
@@ -1,3 +1,5 @@
+use std::fmt::{self, Display};
+
 use crate::units::{
     track::{Track, nom_track},
     velocity::{WindVelocity, nom_velocity},
@@ -13,6 +15,7 @@ use nom::{
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Wind {
     pub dir: WindDirection,
     pub speed: WindVelocity,
@@ -20,11 +23,31 @@ pub struct Wind {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindDirection {
     Heading(Track),
     Variable,
 }
 
+impl Display for Wind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.dir, self.speed)?;
+        match &self.varying {
+            Some((from, to)) => write!(f, " {from}V{to}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for WindDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindDirection::Heading(track) => track.fmt(f),
+            WindDirection::Variable => f.write_str("VRB"),
+        }
+    }
+}
+
 pub(crate) fn nom_variable_wind(input: &str) -> nom::IResult<&str, WindDirection> {
     value(WindDirection::Variable, tag("VRB")).parse(input)
 }
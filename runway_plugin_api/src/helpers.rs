@@ -34,12 +34,14 @@ use crate::RunwayInfo;
 /// use runway_plugin_api::{RunwayInfo, helpers::best_headwind};
 ///
 /// let runways = vec![
-///     RunwayInfo { identifier: "18".into(), heading: 180,
+///     RunwayInfo { identifier: "18".into(), heading: 180, heading_reference: None,
 ///                  headwind_kt: Some(8), tailwind_kt: Some(0),
-///                  crosswind_kt: Some(2), crosswind_direction: None },
-///     RunwayInfo { identifier: "36".into(), heading: 360,
+///                  crosswind_kt: Some(2), crosswind_direction: None,
+///                  true_bearing_deg: None, length_m: None },
+///     RunwayInfo { identifier: "36".into(), heading: 360, heading_reference: None,
 ///                  headwind_kt: Some(-8), tailwind_kt: Some(8),
-///                  crosswind_kt: Some(2), crosswind_direction: None },
+///                  crosswind_kt: Some(2), crosswind_direction: None,
+///                  true_bearing_deg: None, length_m: None },
 /// ];
 /// // 8 − (−8) = 16 > 2  →  "18" wins
 /// assert_eq!(best_headwind(&runways, 2).map(|r| r.identifier.as_str()), Some("18"));
@@ -85,12 +87,14 @@ pub fn best_headwind(runways: &[RunwayInfo], advantage_threshold_kt: i32) -> Opt
 /// use runway_plugin_api::{RunwayInfo, helpers::prefer_unless_tailwind};
 ///
 /// let runways = vec![
-///     RunwayInfo { identifier: "18".into(), heading: 180,
+///     RunwayInfo { identifier: "18".into(), heading: 180, heading_reference: None,
 ///                  headwind_kt: Some(-6), tailwind_kt: Some(6),
-///                  crosswind_kt: Some(1), crosswind_direction: None },
-///     RunwayInfo { identifier: "36".into(), heading: 360,
+///                  crosswind_kt: Some(1), crosswind_direction: None,
+///                  true_bearing_deg: None, length_m: None },
+///     RunwayInfo { identifier: "36".into(), heading: 360, heading_reference: None,
 ///                  headwind_kt: Some(6), tailwind_kt: Some(0),
-///                  crosswind_kt: Some(1), crosswind_direction: None },
+///                  crosswind_kt: Some(1), crosswind_direction: None,
+///                  true_bearing_deg: None, length_m: None },
 /// ];
 /// // Tailwind on "18" is 6 kt > 5 kt limit → switch to "36"
 /// assert_eq!(
@@ -150,12 +154,14 @@ pub fn prefer_unless_crosswind<'a>(
 /// use runway_plugin_api::{RunwayInfo, helpers::min_crosswind};
 ///
 /// let runways = vec![
-///     RunwayInfo { identifier: "18".into(), heading: 180,
+///     RunwayInfo { identifier: "18".into(), heading: 180, heading_reference: None,
 ///                  headwind_kt: Some(0), tailwind_kt: Some(0),
-///                  crosswind_kt: Some(18), crosswind_direction: None },
-///     RunwayInfo { identifier: "28".into(), heading: 280,
+///                  crosswind_kt: Some(18), crosswind_direction: None,
+///                  true_bearing_deg: None, length_m: None },
+///     RunwayInfo { identifier: "28".into(), heading: 280, heading_reference: None,
 ///                  headwind_kt: Some(4), tailwind_kt: Some(0),
-///                  crosswind_kt: Some(4), crosswind_direction: None },
+///                  crosswind_kt: Some(4), crosswind_direction: None,
+///                  true_bearing_deg: None, length_m: None },
 /// ];
 /// assert_eq!(min_crosswind(&runways).map(|r| r.identifier.as_str()), Some("28"));
 /// ```
@@ -195,10 +201,13 @@ mod tests {
         RunwayInfo {
             identifier: id.to_string(),
             heading,
+            heading_reference: None,
             headwind_kt: hw,
             tailwind_kt: tw,
             crosswind_kt: xw,
             crosswind_direction: None,
+            true_bearing_deg: None,
+            length_m: None,
         }
     }
 
@@ -17,7 +17,9 @@
 //! the wall clock ([`RunwaySelectionsRequest::timestamp_utc`] is the time).
 //!
 //! ATIS-derived runways are applied by the host itself; airports already
-//! decided by ATIS are not included in the request.
+//! decided by ATIS are not included in the request. The rest of what an
+//! airport's ATIS says (LVP, runway condition codes, closed runways, …) is
+//! sent as [`AirportSelectionRequest::atis`].
 
 pub mod helpers;
 
@@ -50,6 +52,47 @@ pub struct AirportSelectionRequest {
     /// METAR data, if available.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metar: Option<MetarData>,
+    /// One entry per online ATIS station of the airport whose runways the
+    /// host could not read. Empty when there is none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub atis: Vec<AtisData>,
+}
+
+/// What an ATIS says besides the runways in use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AtisData {
+    /// ATIS station callsign, e.g. "ENGM_D_ATIS".
+    pub callsign: String,
+    /// Information letter, e.g. "L". `None` if not found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letter: Option<String>,
+    /// True if low visibility procedures are in operation.
+    pub low_visibility_procedures: bool,
+    /// ILS category the LVP are for (1–3). `None` if not stated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lvp_category: Option<u8>,
+    /// Transition level as a flight level, e.g. 85. `None` if not stated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transition_level: Option<u16>,
+    #[serde(default)]
+    pub runway_conditions: Vec<RunwayConditionData>,
+    /// Runway identifiers the ATIS reports closed, e.g. "04L".
+    #[serde(default)]
+    pub closed_runways: Vec<String>,
+}
+
+/// A runway condition code (RWYCC) report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunwayConditionData {
+    /// Runway designator, e.g. "01L". `None` if the ATIS names no runway
+    /// for the codes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runway: Option<String>,
+    /// One code per third of the runway from the touchdown end, 6 (dry) to
+    /// 0.
+    pub codes: [u8; 3],
 }
 
 /// A single runway direction with pre-computed wind components from the current METAR.
@@ -60,8 +103,13 @@ pub struct AirportSelectionRequest {
 pub struct RunwayInfo {
     /// Runway identifier, e.g. "01L", "19R", "18"
     pub identifier: String,
-    /// Runway heading in degrees true (0–359)
+    /// Runway heading in degrees (0–359) that the wind components are
+    /// computed on. True unless `heading_reference` says otherwise.
     pub heading: u16,
+    /// What `heading` is referenced to. `None` from hosts that predate it,
+    /// which sent the sector file's magnetic heading.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_reference: Option<HeadingReference>,
     /// Max headwind in knots. Positive = headwind, negative = tailwind. `None` if no METAR.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headwind_kt: Option<i32>,
@@ -74,6 +122,27 @@ pub struct RunwayInfo {
     /// Direction the crosswind comes from relative to the runway centerline.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub crosswind_direction: Option<CrosswindDirection>,
+    /// Bearing from this threshold to the opposite one in degrees true, from
+    /// the sector file's threshold coordinates. `None` if they are unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_bearing_deg: Option<f64>,
+    /// Distance between the thresholds in metres. `None` if unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length_m: Option<u32>,
+}
+
+/// Where a runway heading comes from. METAR winds are true, so components
+/// on a `Magnetic` heading are off by the local magnetic variation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum HeadingReference {
+    /// True, from the bearing between the threshold coordinates.
+    TrueFromThresholds,
+    /// True, from the magnetic heading and the sector file's magnetic
+    /// variation.
+    TrueFromVariation,
+    /// Magnetic, uncorrected: neither thresholds nor variation are known.
+    Magnetic,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -94,14 +163,15 @@ pub struct MetarData {
 }
 
 /// Structured METAR content.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ParsedMetar {
     /// True if METAR reports CAVOK (no significant weather, visibility > 10 km).
     pub is_cavok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wind: Option<WindData>,
-    /// Prevailing visibility in metres. `None` if CAVOK or not reported.
+    /// Prevailing visibility in metres, statute miles converted. `None` if
+    /// CAVOK or not reported.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub visibility_meters: Option<u32>,
     #[serde(default)]
@@ -123,6 +193,64 @@ pub struct ParsedMetar {
     /// QNH in hPa. `None` if not reported or unreadable.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub qnh_hpa: Option<u32>,
+    /// Runway state groups, e.g. "R01L/290095" or "R19R/CLRD//".
+    #[serde(default)]
+    pub runway_states: Vec<RunwayStateData>,
+    /// True if the aerodrome is closed due to snow (SNOCLO).
+    #[serde(default)]
+    pub snow_closed: bool,
+    /// Runways with reported wind shear, e.g. "19R" from "WS R19R".
+    #[serde(default)]
+    pub wind_shear_runways: Vec<String>,
+    /// True if wind shear is reported on all runways ("WS ALL RWY").
+    #[serde(default)]
+    pub wind_shear_all_runways: bool,
+    /// Lowest broken/overcast layer or vertical visibility in feet. Layers
+    /// with unreadable coverage count as ceilings; an unreadable height is 0.
+    /// `None` if there is no ceiling.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ceiling_ft: Option<u32>,
+    /// `None` if visibility or ceiling height is unreadable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_category: Option<FlightCategory>,
+    /// Lowest of the prevailing and directional visibilities in metres,
+    /// statute miles converted. 9999 for CAVOK.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lowest_visibility_meters: Option<u32>,
+    /// Lowest readable RVR in metres.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lowest_rvr_meters: Option<u32>,
+    /// Relative humidity in percent, from the most precise temperature and
+    /// dew point available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative_humidity_percent: Option<f64>,
+    /// True if the temperature is 0 °C or below. `None` if unreadable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at_or_below_freezing: Option<bool>,
+    /// True if present weather has the freezing descriptor (FZDZ, FZRA, FZFG).
+    #[serde(default)]
+    pub freezing_weather: bool,
+    /// Pressure altitude at the aerodrome in feet. `None` if QNH or the
+    /// aerodrome elevation is unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure_altitude_ft: Option<i32>,
+    /// Density altitude at the aerodrome in feet. `None` if QNH, temperature
+    /// or the aerodrome elevation is unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub density_altitude_ft: Option<i32>,
+}
+
+/// FAA flight category derived from ceiling and visibility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum FlightCategory {
+    /// Ceiling below 500 ft or visibility below 1 SM.
+    Lifr,
+    /// Ceiling below 1000 ft or visibility below 3 SM.
+    Ifr,
+    /// Ceiling 1000–3000 ft or visibility 3–5 SM.
+    Mvfr,
+    Vfr,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +284,69 @@ pub struct RvrData {
     pub meters: Option<u32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunwayStateData {
+    /// Runway designator, e.g. "01L". "88" means all runways.
+    pub runway: String,
+    /// True if contamination has been cleared ("CLRD").
+    pub cleared: bool,
+    /// `None` if cleared or not reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit: Option<RunwayDeposit>,
+    /// `None` if cleared or not reported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contamination_extent: Option<ContaminationExtent>,
+    /// Deposit depth in millimetres. `None` if cleared, not reported, or
+    /// the runway is not operational. 400 means 40 cm or more.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_mm: Option<u16>,
+    /// True if the runway is reported as not operational (depth code 99).
+    pub not_operational: bool,
+    /// Braking action, either reported directly or as a friction coefficient.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub braking_action: Option<BrakingAction>,
+    /// Measured friction coefficient, e.g. 0.35. `None` if braking action
+    /// was reported in words or not at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub friction_coefficient: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RunwayDeposit {
+    ClearAndDry,
+    Damp,
+    WetOrWaterPatches,
+    RimeOrFrost,
+    DrySnow,
+    WetSnow,
+    Slush,
+    Ice,
+    CompactedSnow,
+    FrozenRuts,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ContaminationExtent {
+    UpTo10Percent,
+    UpTo25Percent,
+    UpTo50Percent,
+    UpTo100Percent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum BrakingAction {
+    Poor,
+    MediumPoor,
+    Medium,
+    MediumGood,
+    Good,
+    Unreliable,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CloudData {
@@ -412,12 +603,20 @@ and finally POSTs `/shutdown`.
     components(schemas(
         RunwaySelectionsRequest,
         AirportSelectionRequest,
+        AtisData,
+        RunwayConditionData,
         RunwayInfo,
+        HeadingReference,
         CrosswindDirection,
         MetarData,
         ParsedMetar,
+        FlightCategory,
         WindData,
         RvrData,
+        RunwayStateData,
+        RunwayDeposit,
+        ContaminationExtent,
+        BrakingAction,
         CloudData,
         CloudCoverage,
         WeatherPhenomenonData,
@@ -472,12 +671,16 @@ mod tests {
                 runways: vec![RunwayInfo {
                     identifier: "01L".into(),
                     heading: 7,
+                    heading_reference: None,
                     headwind_kt: Some(10),
                     tailwind_kt: Some(0),
                     crosswind_kt: Some(3),
                     crosswind_direction: Some(CrosswindDirection::Left),
+                    true_bearing_deg: None,
+                    length_m: None,
                 }],
                 metar: None,
+                atis: vec![],
             }],
         };
         let json = serde_json::to_string(&req).unwrap();
@@ -102,6 +102,73 @@ pub struct AreaConfig {
     /// Prefix of the EuroScope sector file (`.sct`) that belongs to this area,
     /// e.g. `ENOR` for the Polaris FIR.
     pub sector_file_prefix: Option<String>,
+    /// Where the host reads airports and runways from. Defaults to the
+    /// sector file.
+    pub runway_source: RunwaySource,
+    /// ATIS phrases this area adds to the host's built-in grammar, for local
+    /// phrasings of the runways in use.
+    pub atis: AtisGrammarConfig,
+}
+
+/// The `[runway_source]` table of `area.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RunwaySource {
+    /// The `[RUNWAY]` section of the area's `.sct` file.
+    #[default]
+    SectorFile,
+    /// OurAirports `airports.csv` and `runways.csv` exports, for areas
+    /// without a sector package. Relative paths are resolved against the
+    /// area directory.
+    OurAirports {
+        airports_csv: PathBuf,
+        runways_csv: PathBuf,
+        /// Only airports whose ident starts with one of these are loaded,
+        /// e.g. `["EN"]`. Empty loads every airport in the files.
+        #[serde(default)]
+        icao_prefixes: Vec<String>,
+    },
+}
+
+/// The `[atis]` table of `area.toml`. Both lists extend the host's built-in
+/// grammar rather than replacing it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AtisGrammarConfig {
+    pub phrases: Vec<AtisPhrase>,
+    pub bulletins: Vec<AtisBulletin>,
+}
+
+/// A case-insensitive regex naming the runways an ATIS puts in use.
+/// `{rwy}` stands for a runway identifier, and each runway is captured by a
+/// group named after its role: `arrival`, `departure` or `both`, optionally
+/// followed by a suffix to keep the names unique (`both_2`). For example
+/// `LANDING RUNWAY (?<arrival>{rwy}), TAKEOFF RUNWAY (?<departure>{rwy})`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AtisPhrase {
+    pub pattern: String,
+    /// A generic phrase such as `RUNWAY {rwy} IN USE`. Its runways take the
+    /// role of the bulletin when the ATIS is split into arrival and departure
+    /// bulletins, and otherwise only add runways no other phrase named.
+    /// Matches overlapping another phrase's are ignored.
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+/// A case-insensitive regex marking an ATIS as an arrival- or
+/// departure-only bulletin, e.g. `ARRIVAL INFORMATION`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AtisBulletin {
+    pub pattern: String,
+    pub role: AtisRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AtisRole {
+    Arrival,
+    Departure,
+    Both,
 }
 
 /// A profile within an area — typically a controller position (TWR, APP,
@@ -317,6 +384,54 @@ ENZV = 18
         assert!(parsed.ignore_airports.contains("ENQC"));
         assert_eq!(parsed.default_runways.get("ENGM").copied(), Some(1));
         assert_eq!(parsed.sector_file_prefix.as_deref(), Some("ENOR"));
+        assert_eq!(parsed.runway_source, RunwaySource::SectorFile);
+    }
+
+    #[test]
+    fn runway_source_reads_our_airports_paths() {
+        let raw = r#"
+[runway_source]
+kind = "our_airports"
+airports_csv = "data/airports.csv"
+runways_csv = "data/runways.csv"
+icao_prefixes = ["EN"]
+"#;
+        let parsed: AreaConfig = toml::from_str(raw).unwrap();
+        assert_eq!(
+            parsed.runway_source,
+            RunwaySource::OurAirports {
+                airports_csv: PathBuf::from("data/airports.csv"),
+                runways_csv: PathBuf::from("data/runways.csv"),
+                icao_prefixes: vec!["EN".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn atis_phrases_extend_from_area_toml() {
+        let raw = r#"
+[[atis.phrases]]
+pattern = "LANDING RUNWAY (?<arrival>{rwy})"
+
+[[atis.phrases]]
+pattern = "RUNWAY (?<both>{rwy}) ACTIVE"
+fallback = true
+
+[[atis.bulletins]]
+pattern = "ARRIVAL ATIS"
+role = "arrival"
+"#;
+        let parsed: AreaConfig = toml::from_str(raw).unwrap();
+        assert_eq!(parsed.atis.phrases.len(), 2);
+        assert!(!parsed.atis.phrases[0].fallback);
+        assert!(parsed.atis.phrases[1].fallback);
+        assert_eq!(
+            parsed.atis.bulletins,
+            [AtisBulletin {
+                pattern: "ARRIVAL ATIS".to_string(),
+                role: AtisRole::Arrival,
+            }]
+        );
     }
 
     #[test]
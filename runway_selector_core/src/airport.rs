@@ -7,7 +7,10 @@ use metar_decoder::{
 };
 
 use crate::{
-    runway::{Runway, RunwayDirection, RunwayUse},
+    atis::AtisInfo,
+    data_source::OnlineController,
+    geo::Coordinate,
+    runway::{HeadingReference, Runway, RunwayDirection, RunwayUse},
     util::diff_angle,
 };
 
@@ -32,8 +35,24 @@ impl RunwayInUseSource {
 pub struct Airport {
     pub icao: String,
     pub metar: Option<Metar>,
+    /// Aerodrome elevation in feet, when the data source provides it. Used
+    /// for pressure and density altitude.
+    pub elevation_ft: Option<i32>,
+    /// Aerodrome reference point from the sector file's `[AIRPORT]` section.
+    pub reference_point: Option<Coordinate>,
+    /// Tower frequency as the sector file writes it, e.g. `"118.300"`.
+    pub tower_frequency: Option<String>,
+    pub airspace_class: Option<String>,
+    /// Magnetic variation in degrees, east positive, from the sector file's
+    /// `[INFO]` section. Corrects runway headings whose thresholds are
+    /// unknown.
+    pub magnetic_variation: Option<f64>,
     pub runways: Vec<Runway>,
     pub runways_in_use: IndexMap<RunwayInUseSource, IndexMap<String, RunwayUse>>,
+    /// What each online ATIS station of the airport says, by callsign.
+    pub atis: IndexMap<String, AtisInfo>,
+    /// Controllers online at the airport, ATIS stations excluded.
+    pub controllers: Vec<OnlineController>,
     /// Machine-readable tags an area plugin attached to its selection,
     /// rendered in the HTML runway report to explain *why* runways were
     /// chosen. Empty when no plugin ran or none applied.
@@ -56,6 +75,24 @@ pub enum CrosswindDirection {
 }
 
 impl Airport {
+    /// An airport with no runways, METAR or selection yet.
+    pub fn new(icao: String) -> Self {
+        Airport {
+            icao,
+            metar: None,
+            elevation_ft: None,
+            reference_point: None,
+            tower_frequency: None,
+            airspace_class: None,
+            magnetic_variation: None,
+            runways: Vec::new(),
+            runways_in_use: IndexMap::new(),
+            atis: IndexMap::new(),
+            controllers: Vec::new(),
+            selection_tags: Vec::new(),
+        }
+    }
+
     pub fn runway_wind_components(
         &self,
         runway_direction: &RunwayDirection,
@@ -70,14 +107,30 @@ impl Airport {
         })
     }
 
+    /// The runways selected by the highest-priority source that made a
+    /// selection, as written to the `.rwy` file.
+    pub fn preferred_runways_in_use(&self) -> Option<&IndexMap<String, RunwayUse>> {
+        RunwayInUseSource::default_sort_order()
+            .iter()
+            .find_map(|source| self.runways_in_use.get(source))
+    }
+
+    /// The heading the wind components of `runway_direction` are computed
+    /// on, and what it is referenced to. See [`RunwayDirection::true_heading`].
+    pub fn runway_heading(&self, runway_direction: &RunwayDirection) -> (u32, HeadingReference) {
+        runway_direction.true_heading(self.magnetic_variation)
+    }
+
     pub fn runway_max_headwind(&self, runway_direction: &RunwayDirection) -> Option<i32> {
         let metar = self.metar.as_ref()?;
-        Self::calculate_max_headwind_from_wind(runway_direction, metar.wind.clone())
+        let (track, _) = self.runway_heading(runway_direction);
+        Self::calculate_max_headwind_from_wind(track, metar.wind.clone())
     }
 
     pub fn runway_max_tailwind(&self, runway_direction: &RunwayDirection) -> Option<i32> {
         let metar = self.metar.as_ref()?;
-        Self::calculate_max_tailwind_from_wind(runway_direction, metar.wind.clone())
+        let (track, _) = self.runway_heading(runway_direction);
+        Self::calculate_max_tailwind_from_wind(track, metar.wind.clone())
     }
 
     pub fn runway_max_crosswind(
@@ -85,15 +138,15 @@ impl Airport {
         runway_direction: &RunwayDirection,
     ) -> Option<(i32, CrosswindDirection)> {
         let metar = self.metar.as_ref()?;
-        Self::calculate_max_crosswind_from_wind(runway_direction, &metar.wind)
+        let (track, _) = self.runway_heading(runway_direction);
+        Self::calculate_max_crosswind_from_wind(track, &metar.wind)
     }
 
     fn calculate_max_crosswind_from_wind(
-        runway: &RunwayDirection,
+        track: u32,
         wind: &Wind,
     ) -> Option<(i32, CrosswindDirection)> {
         const EPSILON: f64 = 1e-9;
-        let track: u32 = runway.degrees as u32;
 
         let (factor, direction) =
             if let Some((Track(OptionalData::Data(start)), Track(OptionalData::Data(end)))) =
@@ -168,8 +221,7 @@ impl Airport {
         Self::scale_wind_speed(wind.speed, factor).map(|crosswind| (crosswind, direction))
     }
 
-    fn calculate_max_headwind_from_wind(runway: &RunwayDirection, wind: Wind) -> Option<i32> {
-        let track = runway.degrees as u32;
+    fn calculate_max_headwind_from_wind(track: u32, wind: Wind) -> Option<i32> {
         let factor =
             if let Some((Track(OptionalData::Data(start)), Track(OptionalData::Data(end)))) =
                 wind.varying
@@ -205,12 +257,8 @@ impl Airport {
         Self::scale_wind_speed(wind.speed, factor)
     }
 
-    fn calculate_max_tailwind_from_wind(runway: &RunwayDirection, wind: Wind) -> Option<i32> {
-        let opposite_runway = RunwayDirection {
-            degrees: ((runway.degrees as u32 + 180) % 360) as u16,
-            identifier: runway.identifier.clone(),
-        };
-        Self::calculate_max_headwind_from_wind(&opposite_runway, wind)
+    fn calculate_max_tailwind_from_wind(track: u32, wind: Wind) -> Option<i32> {
+        Self::calculate_max_headwind_from_wind((track + 180) % 360, wind)
             .map(|headwind| headwind.max(0))
     }
 
@@ -232,6 +280,7 @@ impl Airport {
 
     fn scale_wind_speed(speed: WindVelocity, factor: f64) -> Option<i32> {
         speed
+            .in_knots()
             .get_max_wind_speed()
             .map(|speed| (f64::from(speed) * factor).ceil() as i32)
     }
@@ -239,8 +288,14 @@ impl Airport {
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use super::{Airport, CrosswindDirection, RunwayDirection};
-    use crate::airports::tests::make_test_airport;
+    use metar_decoder::{
+        arbitrary::{WindSpec, wind_spec},
+        builder::MetarBuilder,
+    };
+    use proptest::{prop_assert, proptest};
+
+    use super::{Airport, CrosswindDirection, HeadingReference, Runway, RunwayDirection};
+    use crate::airports::tests::{make_test_airport, make_test_airport_with};
 
     fn runway_direction<'a>(airport: &'a Airport, identifier: &str) -> &'a RunwayDirection {
         airport
@@ -253,7 +308,7 @@ pub(crate) mod tests {
 
     #[test]
     fn test_runway_wind_component_api() {
-        let ap = make_test_airport("ENHV 081620Z AUTO 08008KT 9999 OVC006/// 08/07 Q1001");
+        let ap = make_test_airport("ENHV 081620Z AUTO 09008KT 9999 OVC006/// 08/07 Q1001");
         let runway_08 = runway_direction(&ap, "08");
         let runway_26 = runway_direction(&ap, "26");
 
@@ -275,14 +330,14 @@ pub(crate) mod tests {
     #[test]
     fn test_crosswind_direction_is_side_sensitive() {
         let runway_08_right = {
-            let airport = make_test_airport("ENHV 081620Z AUTO 17010KT 9999 OVC006/// 08/07 Q1001");
+            let airport = make_test_airport_with(MetarBuilder::new("ENHV").wind(170, 10).build());
             let runway_08 = runway_direction(&airport, "08");
             airport
                 .runway_max_crosswind(runway_08)
                 .map(|(_crosswind, direction)| direction)
         };
         let runway_08_left = {
-            let airport = make_test_airport("ENHV 081620Z AUTO 35010KT 9999 OVC006/// 08/07 Q1001");
+            let airport = make_test_airport_with(MetarBuilder::new("ENHV").wind(350, 10).build());
             let runway_08 = runway_direction(&airport, "08");
             airport
                 .runway_max_crosswind(runway_08)
@@ -292,4 +347,90 @@ pub(crate) mod tests {
         assert_eq!(runway_08_right, Some(CrosswindDirection::Right));
         assert_eq!(runway_08_left, Some(CrosswindDirection::Left));
     }
+
+    #[test]
+    fn test_wind_components_use_the_threshold_bearing() {
+        // ENHV's sector file gives 08 as 076° magnetic; its thresholds say
+        // the runway lies 091° true, almost straight into an easterly wind.
+        let airport = make_test_airport_with(MetarBuilder::new("ENHV").wind(90, 10).build());
+        let runway_08 = runway_direction(&airport, "08");
+        assert_eq!(
+            airport.runway_heading(runway_08),
+            (91, HeadingReference::Thresholds)
+        );
+        assert_eq!(airport.runway_max_headwind(runway_08), Some(10));
+        assert!(airport.runway_max_crosswind(runway_08).unwrap().0 <= 1);
+    }
+
+    #[test]
+    fn test_magnetic_heading_is_corrected_by_variation() {
+        let runway_36 = RunwayDirection {
+            degrees: 360,
+            identifier: "36".to_string(),
+            threshold: None,
+            true_bearing: None,
+        };
+        let mut airport = make_test_airport_with(MetarBuilder::new("ENAN").wind(10, 20).build());
+        airport.runways = vec![Runway::new([
+            runway_36,
+            RunwayDirection {
+                degrees: 180,
+                identifier: "18".to_string(),
+                threshold: None,
+                true_bearing: None,
+            },
+        ])];
+        let runway_36 = &airport.runways[0].runways[0];
+        assert_eq!(
+            airport.runway_heading(runway_36),
+            (0, HeadingReference::Magnetic)
+        );
+        assert!(airport.runway_max_crosswind(runway_36).unwrap().0 > 3);
+
+        airport.magnetic_variation = Some(10.0);
+        assert_eq!(
+            airport.runway_heading(runway_36),
+            (10, HeadingReference::Variation)
+        );
+        assert_eq!(airport.runway_max_headwind(runway_36), Some(20));
+        assert_eq!(airport.runway_max_crosswind(runway_36).unwrap().0, 0);
+    }
+
+    proptest! {
+        #[test]
+        fn steady_wind_components_add_up_to_the_wind(wind in wind_spec()) {
+            if wind.direction.is_none() {
+                return Ok(());
+            }
+            let steady = WindSpec { varying: None, ..wind };
+            let speed = f64::from(steady.gust.unwrap_or(steady.speed));
+            let airport = make_test_airport_with(steady.apply(MetarBuilder::new("ENHV")).build());
+            for runway in airport.runways.iter().flat_map(|runway| runway.runways.iter()) {
+                let components = airport.runway_wind_components(runway).unwrap();
+                let tailwind = airport.runway_max_tailwind(runway).unwrap();
+                // Each component is rounded up to whole knots.
+                let magnitude = f64::from(components.headwind).hypot(f64::from(components.crosswind));
+                prop_assert!((magnitude - speed).abs() <= 1.5, "{magnitude} vs {speed}");
+                prop_assert!(components.headwind <= 0 || tailwind == 0);
+            }
+        }
+
+        #[test]
+        fn varying_wind_never_improves_a_runway(wind in wind_spec()) {
+            let without_variation = WindSpec { varying: None, ..wind.clone() };
+            let steady = make_test_airport_with(without_variation.apply(MetarBuilder::new("ENHV")).build());
+            let varying = make_test_airport_with(wind.apply(MetarBuilder::new("ENHV")).build());
+            for runway in steady.runways.iter().flat_map(|runway| runway.runways.iter()) {
+                let (before, after) = (
+                    steady.runway_wind_components(runway).unwrap(),
+                    varying.runway_wind_components(runway).unwrap(),
+                );
+                prop_assert!(after.headwind >= before.headwind);
+                prop_assert!(after.crosswind >= before.crosswind);
+                prop_assert!(
+                    varying.runway_max_tailwind(runway) >= steady.runway_max_tailwind(runway)
+                );
+            }
+        }
+    }
 }
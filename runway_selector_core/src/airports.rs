@@ -1,6 +1,7 @@
 use askama::Template;
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
+use metar_decoder::plain_language::UnitSystem;
 use tracing::warn;
 
 use std::{
@@ -10,15 +11,20 @@ use std::{
 
 use crate::{
     airport::{Airport, CrosswindDirection, RunwayInUseSource, RunwayWindComponents},
-    atis::find_runway_in_use_from_atis,
+    atis::{AtisGrammar, AtisInfo},
+    data_source::DataSource,
     error::CoreResult,
-    metar::get_metars,
+    metar::{get_metars, supersedes},
+    ourairports::load_ourairports,
     runway::{RunwayDirection, RunwayUse},
-    sector_file::load_airports_from_sct_runway_section,
+    runway_check::{RunwayIssue, check_airports},
+    sector_file::{SectorInfo, load_sector_file},
 };
 
 pub struct Airports {
     pub airports: IndexMap<String, Airport>,
+    /// The `[INFO]` section of the first sector file loaded that has one.
+    pub sector_info: Option<SectorInfo>,
 }
 
 type AirportsConfigReportData =
@@ -57,6 +63,7 @@ impl Airports {
     pub fn new() -> Self {
         Self {
             airports: IndexMap::new(),
+            sector_info: None,
         }
     }
 
@@ -70,19 +77,63 @@ impl Airports {
         reader: &mut R,
         ignored_airports: &IndexSet<String>,
     ) -> CoreResult<()> {
-        let parsed_airports = load_airports_from_sct_runway_section(reader, ignored_airports)?;
+        let sector_file = load_sector_file(reader, ignored_airports)?;
+        if self.sector_info.is_none() {
+            self.sector_info = sector_file.info;
+        }
+        self.merge_airports(sector_file.airports);
+        Ok(())
+    }
 
-        for (icao, mut airport) in parsed_airports {
+    /// Load airports and runways from OurAirports CSV exports instead of a
+    /// sector file. See [`crate::ourairports`].
+    pub fn load_airports_from_ourairports<A: Read, R: Read>(
+        &mut self,
+        airports_csv: A,
+        runways_csv: R,
+        icao_prefixes: &[String],
+        ignored_airports: &IndexSet<String>,
+    ) -> CoreResult<()> {
+        let airports =
+            load_ourairports(airports_csv, runways_csv, icao_prefixes, ignored_airports)?;
+        self.merge_airports(airports);
+        Ok(())
+    }
+
+    /// Add `airports`, appending the runways of airports already known and
+    /// filling in fields they are missing.
+    fn merge_airports(&mut self, airports: IndexMap<String, Airport>) {
+        for (icao, mut airport) in airports {
             match self.airports.entry(icao) {
                 indexmap::map::Entry::Occupied(mut existing) => {
-                    existing.get_mut().runways.append(&mut airport.runways);
+                    let existing = existing.get_mut();
+                    existing.runways.append(&mut airport.runways);
+                    existing.elevation_ft = existing.elevation_ft.or(airport.elevation_ft);
+                    existing.reference_point = existing.reference_point.or(airport.reference_point);
+                    existing.tower_frequency =
+                        existing.tower_frequency.take().or(airport.tower_frequency);
+                    existing.airspace_class =
+                        existing.airspace_class.take().or(airport.airspace_class);
+                    existing.magnetic_variation =
+                        existing.magnetic_variation.or(airport.magnetic_variation);
                 }
                 indexmap::map::Entry::Vacant(vacant) => {
                     vacant.insert(airport);
                 }
             }
         }
-        Ok(())
+    }
+
+    /// Add the airports of another, separately selected set, e.g. from a
+    /// second sector file. Airports already known keep their own runways and
+    /// selection.
+    pub fn absorb(&mut self, other: Airports) {
+        if self.sector_info.is_none() {
+            self.sector_info = other.sector_info;
+        }
+        for (icao, airport) in other.airports {
+            self.airports.entry(icao).or_insert(airport);
+        }
     }
 
     /// Fetch METARs and attach them to known airports. Returns the underlying
@@ -91,38 +142,44 @@ impl Airports {
     /// (ATIS + defaults).
     pub async fn add_metars(
         &mut self,
+        source: &dyn DataSource,
         metar_urls: &[&str],
         ignore: &IndexSet<String>,
     ) -> CoreResult<()> {
-        let metars = get_metars(metar_urls, ignore).await?;
+        let metars = get_metars(source, metar_urls, ignore).await?;
         for metar in metars {
-            if let Some(airport) = self.airports.get_mut(&metar.icao) {
+            if let Some(airport) = self.airports.get_mut(&metar.icao)
+                && airport
+                    .metar
+                    .as_ref()
+                    .is_none_or(|current| supersedes(&metar, current))
+            {
                 airport.metar = Some(metar);
             }
         }
         Ok(())
     }
 
-    pub async fn read_atis_and_apply_runways(&mut self) -> CoreResult<()> {
-        let icaos = self.identifiers();
-        let v3_data = vatsim_utils::live_api::Vatsim::new()
-            .await?
-            .get_v3_data()
-            .await?;
-        let atis_entries = v3_data.atis;
-        for atis in atis_entries {
-            let icao = &atis.callsign[0..4];
-            if !icaos.contains(icao) {
+    /// Read the runways in use from the ATISes of known airports in
+    /// `source` with `grammar`, and keep the rest of what each ATIS says in
+    /// [`Airport::atis`].
+    pub async fn read_atis_and_apply_runways(
+        &mut self,
+        source: &dyn DataSource,
+        grammar: &AtisGrammar,
+    ) -> CoreResult<()> {
+        for atis in source.atis().await? {
+            if atis.lines.is_empty() {
                 continue;
             }
-            let Some(atis_lines) = atis.text_atis else {
+            let Some(airport) = self.airports.get_mut(atis.icao()) else {
                 continue;
             };
-            let Some(airport) = self.airports.get_mut(icao) else {
-                continue;
-            };
-            let text = atis_lines.into_iter().collect::<Vec<_>>().join(" ");
-            for (runway, runway_use) in find_runway_in_use_from_atis(&text) {
+            let text = atis.text();
+            airport
+                .atis
+                .insert(atis.callsign.clone(), AtisInfo::parse(&text));
+            for (runway, runway_use) in grammar.find_runways_in_use(&text) {
                 airport
                     .runways_in_use
                     .entry(RunwayInUseSource::Atis)
@@ -138,6 +195,20 @@ impl Airports {
         Ok(())
     }
 
+    /// Keep the controllers in `source` that are online at known airports in
+    /// [`Airport::controllers`].
+    pub async fn read_online_controllers(&mut self, source: &dyn DataSource) -> CoreResult<()> {
+        for controller in source.controllers().await? {
+            if controller.is_observer() {
+                continue;
+            }
+            if let Some(airport) = self.airports.get_mut(controller.station()) {
+                airport.controllers.push(controller);
+            }
+        }
+        Ok(())
+    }
+
     /// Fill in the `Default` source for any airport that still has no
     /// selection after the area plugin (and ATIS parser) have had their turn.
     /// Called by the host after [`Self::read_atis_and_apply_runways`] and the
@@ -153,7 +224,7 @@ impl Airports {
                         if airport.runways.iter().any(|rw| {
                             rw.runways
                                 .iter()
-                                .any(|dir| dir.identifier[0..2] == identifier)
+                                .any(|dir| dir.identifier.get(..2) == Some(identifier.as_str()))
                         }) {
                             v.insert([(identifier, RunwayUse::Both)].into());
                         } else {
@@ -172,6 +243,12 @@ impl Airports {
             .collect()
     }
 
+    /// Sanity-check findings for every airport's runways. See
+    /// [`crate::runway_check`].
+    pub fn runway_issues(&self) -> Vec<RunwayIssue> {
+        check_airports(self.airports.values())
+    }
+
     pub fn identifiers(&self) -> IndexSet<String> {
         self.airports.keys().cloned().collect()
     }
@@ -280,6 +357,14 @@ impl Airports {
             .unwrap_or_else(|| format!("{icao} No METAR"))
     }
 
+    fn metar_plain_text_for_airport(&self, icao: &str) -> String {
+        self.airports
+            .get(icao)
+            .and_then(|airport| airport.metar.as_ref())
+            .map(|metar| metar.plain_language(UnitSystem::Metric).join(" "))
+            .unwrap_or_default()
+    }
+
     fn runway_direction_for_identifier<'a>(
         airport: &'a Airport,
         runway_identifier: &str,
@@ -553,8 +638,14 @@ impl Airports {
         let view = self.build_runway_report_view(&report_data);
 
         // Render template
+        let runway_issues: Vec<String> = self
+            .runway_issues()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
         let tpl = RunwayReportTemplate {
             groups: &view.groups,
+            runway_issues: &runway_issues,
         };
 
         let html = tpl.render().map_err(io::Error::other)?;
@@ -575,9 +666,21 @@ impl Airports {
                 let airport = self.airports.get(icao);
                 let lines = Self::build_report_lines_for_row(airport, runways);
                 let metar = self.metar_text_for_airport(icao);
+                let metar_plain = self.metar_plain_text_for_airport(icao);
                 let tags = airport
                     .map(|a| a.selection_tags.clone())
                     .unwrap_or_default();
+                let atis = airport
+                    .map(|a| a.atis.values().map(AtisInfo::summary).join(" · "))
+                    .unwrap_or_default();
+                let controllers = airport
+                    .map(|a| {
+                        a.controllers
+                            .iter()
+                            .map(|c| format!("{} {}", c.callsign, c.frequency))
+                            .join(" · ")
+                    })
+                    .unwrap_or_default();
 
                 airports.push(AirportRunwayView {
                     icao: icao.clone(),
@@ -585,6 +688,9 @@ impl Airports {
                     lines,
                     tags,
                     metar,
+                    metar_plain,
+                    atis,
+                    controllers,
                 });
             }
 
@@ -632,6 +738,12 @@ pub struct AirportRunwayView {
     pub lines: Vec<AirportRunwayLineView>,
     pub tags: Vec<runway_plugin_api::SelectionTag>,
     pub metar: String,
+    /// The METAR decoded into English sentences, empty without a METAR.
+    pub metar_plain: String,
+    /// [`AtisInfo::summary`] of each online ATIS, empty without one.
+    pub atis: String,
+    /// The controllers online at the airport, e.g. `ENGM_TWR 118.300`.
+    pub controllers: String,
 }
 
 #[derive(Debug)]
@@ -648,6 +760,8 @@ pub struct AirportRunwayLineView {
 #[template(path = "runway_report.html")]
 struct RunwayReportTemplate<'a> {
     groups: &'a [RunwaySourceGroupView],
+    /// [`RunwayIssue`]s, rendered below the selections.
+    runway_issues: &'a [String],
 }
 
 #[cfg(test)]
@@ -678,20 +792,124 @@ pub(crate) mod tests {
         assert_eq!(ap.airports.len(), 50);
     }
 
+    #[test]
+    fn test_absorb_keeps_the_first_selection_of_shared_airports() {
+        let selection = |runway: &str| {
+            IndexMap::from([(
+                RunwayInUseSource::Default,
+                IndexMap::from([(runway.to_string(), RunwayUse::Both)]),
+            )])
+        };
+        let mut first = Airports::new();
+        let mut engm = Airport::new("ENGM".to_string());
+        engm.runways_in_use = selection("01L");
+        first.add_airport(engm);
+
+        let mut second = Airports::new();
+        let mut engm = Airport::new("ENGM".to_string());
+        engm.runways_in_use = selection("19R");
+        second.add_airport(engm);
+        second.add_airport(Airport::new("ESSA".to_string()));
+
+        first.absorb(second);
+        assert_eq!(first.airports.keys().collect::<Vec<_>>(), ["ENGM", "ESSA"]);
+        assert_eq!(
+            first.airports["ENGM"].preferred_runways_in_use(),
+            Some(&IndexMap::from([("01L".to_string(), RunwayUse::Both)]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metars_and_atis_from_a_recording() {
+        use crate::data_source::{AtisText, FixtureSource, OnlineController, Recording};
+
+        let mut airports = Airports::new();
+        let mut reader = std::io::Cursor::new(include_str!("../runway.test"));
+        airports
+            .load_airports_from_sector_file(&mut reader, &test_ignored_airports())
+            .unwrap();
+        let source = FixtureSource::new(Recording {
+            metars: [(
+                "https://metar.vatsim.net/EN".to_string(),
+                "ENGM 121020Z 19010KT CAVOK 10/M01 Q1020\nENZV 121020Z 18012KT CAVOK 10/M01 Q1020"
+                    .to_string(),
+            )]
+            .into(),
+            atis: vec![AtisText {
+                callsign: "ENGM_ATIS".to_string(),
+                frequency: "126.130".to_string(),
+                lines: vec![
+                    "OSLO GARDERMOEN INFORMATION ALFA ..".to_string(),
+                    "RUNWAY 19R IN USE".to_string(),
+                ],
+            }],
+            controllers: vec![
+                OnlineController {
+                    callsign: "ENGM_W_TWR".to_string(),
+                    frequency: "118.300".to_string(),
+                },
+                OnlineController {
+                    callsign: "ENGM_OBS".to_string(),
+                    frequency: "199.998".to_string(),
+                },
+                OnlineController {
+                    callsign: "ENOS_CTR".to_string(),
+                    frequency: "120.450".to_string(),
+                },
+            ],
+        });
+
+        airports
+            .add_metars(&source, &["https://metar.vatsim.net/EN"], &IndexSet::new())
+            .await
+            .unwrap();
+        airports
+            .read_atis_and_apply_runways(&source, AtisGrammar::builtin())
+            .await
+            .unwrap();
+        airports.read_online_controllers(&source).await.unwrap();
+
+        assert!(airports["ENZV"].metar.is_some());
+        let engm = &airports["ENGM"];
+        assert_eq!(
+            engm.runways_in_use[&RunwayInUseSource::Atis],
+            IndexMap::from([("19R".to_string(), RunwayUse::Both)])
+        );
+        assert_eq!(engm.atis["ENGM_ATIS"].letter, Some('A'));
+        assert_eq!(
+            engm.controllers
+                .iter()
+                .map(|c| c.callsign.as_str())
+                .collect::<Vec<_>>(),
+            ["ENGM_W_TWR"]
+        );
+    }
+
     pub(crate) fn make_test_airport(metar_str: &str) -> Airport {
-        let icao = metar_str[0..4].to_string();
+        make_test_airport_with(metar_str.parse().unwrap())
+    }
+
+    /// An airport from `runway.test` with the given METAR, e.g. one from
+    /// [`metar_decoder::builder::MetarBuilder`].
+    pub(crate) fn make_test_airport_with(metar: Metar) -> Airport {
         let mut ap = Airports::new();
         let mut reader = std::io::Cursor::new(include_str!("../runway.test"));
         let ignored = test_ignored_airports();
         ap.load_airports_from_sector_file(&mut reader, &ignored)
             .unwrap();
-        let airport = ap.airports.swap_remove(&icao).unwrap();
-        let metar: Metar = metar_str.parse().unwrap();
+        let airport = ap.airports.swap_remove(&metar.icao).unwrap();
         Airport {
             icao: airport.icao,
             metar: Some(metar),
+            elevation_ft: airport.elevation_ft,
+            reference_point: airport.reference_point,
+            tower_frequency: airport.tower_frequency,
+            airspace_class: airport.airspace_class,
+            magnetic_variation: airport.magnetic_variation,
             runways: airport.runways,
             runways_in_use: IndexMap::new(),
+            atis: IndexMap::new(),
+            controllers: Vec::new(),
             selection_tags: Vec::new(),
         }
     }
@@ -708,6 +926,7 @@ pub(crate) mod tests {
 
         Airports {
             airports: IndexMap::from([(airport.icao.clone(), airport)]),
+            sector_info: None,
         }
     }
 
@@ -822,11 +1041,63 @@ pub(crate) mod tests {
         let html = String::from_utf8(rendered).unwrap();
 
         assert!(html.contains("rowspan=\"2\">ENZV</td>"));
-        assert!(html.contains("rowspan=\"2\">ENZV 191650Z 30005KT CAVOK 08/04 Q1026 NOSIG</td>"));
+        assert!(html.contains(
+            "rowspan=\"2\">ENZV 191650Z 30005KT CAVOK 08/04 Q1026 NOSIG<div class=\"metar-plain\">"
+        ));
+        assert!(html.contains("Wind from 300° at 5 knots."));
         assert!(!html.contains("36\n28"));
         assert!(!html.contains("↓\n↓"));
     }
 
+    #[test]
+    fn test_report_html_lists_runway_issues() {
+        let mut airports = Airports::new();
+        let mut reader = std::io::Cursor::new(include_str!("../runway.test"));
+        airports
+            .load_airports_from_sector_file(&mut reader, &IndexSet::new())
+            .unwrap();
+
+        let mut rendered = Vec::new();
+        airports
+            .make_runway_report_html_with_writer(&mut rendered)
+            .unwrap();
+        let html = String::from_utf8(rendered).unwrap();
+
+        assert!(html.contains("Sector file runway issues"));
+        assert!(html.contains("5 issues"));
+        assert!(html.contains("<li>ENBM 09/27: 09 has heading 000</li>"));
+    }
+
+    #[test]
+    fn test_report_html_summarises_atis_and_controllers() {
+        let mut airports = Airports::new();
+        let mut engm = make_test_airport("ENGM 121020Z 01010KT CAVOK 10/M01 Q1020");
+        engm.atis.insert(
+            "ENGM_A_ATIS".to_string(),
+            AtisInfo::parse("OSLO GARDERMOEN ARRIVAL INFORMATION CHARLIE .. TRANSITION LEVEL 85"),
+        );
+        engm.atis.insert(
+            "ENGM_D_ATIS".to_string(),
+            AtisInfo::parse("OSLO GARDERMOEN DEPARTURE INFORMATION HOTEL .. RWY 19R CLOSED"),
+        );
+        engm.controllers.push(crate::data_source::OnlineController {
+            callsign: "ENGM_TWR".to_string(),
+            frequency: "118.300".to_string(),
+        });
+        airports.add_airport(engm);
+
+        let mut rendered = Vec::new();
+        airports
+            .make_runway_report_html_with_writer(&mut rendered)
+            .unwrap();
+        let html = String::from_utf8(rendered).unwrap();
+
+        assert!(
+            html.contains(r#"<div class="atis-info">ATIS C, TL 85 · ATIS H, RWY 19R closed</div>"#)
+        );
+        assert!(html.contains(r#"<div class="controllers-info">ENGM_TWR 118.300</div>"#));
+    }
+
     #[test]
     #[ignore = "writes a manual inspection artifact to /tmp"]
     fn write_issue20_demo_report() {
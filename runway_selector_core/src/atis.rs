@@ -1,87 +1,338 @@
+//! Runways in use and other operational information from the text of VATSIM
+//! ATISes.
+//!
+//! The runway phrases are data: the host ships a built-in grammar
+//! (`atis_grammar.toml`) and an area's `area.toml` can add its own under
+//! `[atis]`. The corpora in `atis_corpus/` hold real bulletins per FIR with
+//! the runways they should yield. The rest of the bulletin ([`AtisInfo`]) is
+//! read with fixed patterns.
+
 use indexmap::{IndexMap, map::Entry};
-use regex::Regex;
-use std::sync::LazyLock;
+use regex::{Captures, Regex};
+use runway_selector_area_config::AtisGrammarConfig;
+use std::{ops::Range, sync::LazyLock};
+
+use crate::{
+    error::{CoreError, CoreResult},
+    runway::RunwayUse,
+};
 
-use crate::runway::RunwayUse;
+/// What `{rwy}` stands for in a phrase.
+const RUNWAY_PATTERN: &str = "[0-9]{2}[LRC]?";
 
+static BUILTIN: LazyLock<AtisGrammar> = LazyLock::new(|| {
+    let config: AtisGrammarConfig = toml::from_str(include_str!("../atis_grammar.toml"))
+        .expect("the built-in ATIS grammar is valid TOML");
+    AtisGrammar::compile(&config).expect("the built-in ATIS grammar compiles")
+});
+
+/// The runways in use according to `atis`, read with the built-in grammar.
 pub fn find_runway_in_use_from_atis(atis: &str) -> IndexMap<String, RunwayUse> {
-    static SINGLE_POST: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\bRUNWAY ([0-9]{2}[LRC]*) IN USE\b").unwrap());
+    AtisGrammar::builtin().find_runways_in_use(atis)
+}
+
+#[derive(Debug, Clone)]
+pub struct AtisGrammar {
+    phrases: Vec<Phrase>,
+    bulletins: Vec<(Regex, RunwayUse)>,
+}
+
+#[derive(Debug, Clone)]
+struct Phrase {
+    regex: Regex,
+    /// The role of each named capture group, by group index.
+    roles: Vec<(usize, RunwayUse)>,
+    fallback: bool,
+}
 
-    static SINGLE_PRE: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\bRUNWAY IN USE ([0-9]{2}[LRC]*)\b").unwrap());
+impl AtisGrammar {
+    pub fn builtin() -> &'static AtisGrammar {
+        &BUILTIN
+    }
 
-    static ARR: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\bAPPROACH (?:RWY|RUNWAY) ([0-9]{2}[LRC]*)\b").unwrap());
+    /// The built-in grammar followed by an area's own phrases and bulletins.
+    pub fn extended(config: &AtisGrammarConfig) -> CoreResult<AtisGrammar> {
+        let mut grammar = Self::builtin().clone();
+        let extension = Self::compile(config)?;
+        grammar.phrases.extend(extension.phrases);
+        grammar.bulletins.extend(extension.bulletins);
+        Ok(grammar)
+    }
 
-    static DEP: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\bDEPARTURE RUNWAY ([0-9]{2}[LRC]*)\b").unwrap());
+    fn compile(config: &AtisGrammarConfig) -> CoreResult<AtisGrammar> {
+        let phrases = config
+            .phrases
+            .iter()
+            .map(|phrase| {
+                let regex = compile_pattern(&phrase.pattern)?;
+                let roles = regex
+                    .capture_names()
+                    .enumerate()
+                    .filter_map(|(index, name)| Some((index, name?)))
+                    .map(|(index, name)| Ok((index, group_role(&phrase.pattern, name)?)))
+                    .collect::<CoreResult<Vec<_>>>()?;
+                if roles.is_empty() {
+                    return Err(invalid_pattern(
+                        &phrase.pattern,
+                        "it names no arrival, departure or both group",
+                    ));
+                }
+                Ok(Phrase {
+                    regex,
+                    roles,
+                    fallback: phrase.fallback,
+                })
+            })
+            .collect::<CoreResult<_>>()?;
+        let bulletins = config
+            .bulletins
+            .iter()
+            .map(|bulletin| Ok((compile_pattern(&bulletin.pattern)?, bulletin.role.into())))
+            .collect::<CoreResult<_>>()?;
+        Ok(AtisGrammar { phrases, bulletins })
+    }
 
-    static MULTI: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"(?i)\bRUNWAYS ([0-9]{2}[LRC]*) AND ([0-9]{2}[LRC]*) IN USE\b").unwrap()
-    });
+    pub fn find_runways_in_use(&self, atis: &str) -> IndexMap<String, RunwayUse> {
+        let bulletin = self
+            .bulletins
+            .iter()
+            .find(|(regex, _)| regex.is_match(atis))
+            .map(|(_, runway_use)| *runway_use);
 
-    static ARR_INFO: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\bARRIVAL INFORMATION\b").unwrap());
-    static DEP_INFO: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\bDEPARTURE INFORMATION\b").unwrap());
-    static DEP_OR_APP_PREFIX: LazyLock<Regex> =
-        LazyLock::new(|| Regex::new(r"(?i)\b(?:DEPARTURE|APPROACH)\b").unwrap());
+        let mut runways = IndexMap::new();
+        let mut matched: Vec<Range<usize>> = Vec::new();
 
-    let is_arrival = ARR_INFO.is_match(atis);
-    let is_departure = DEP_INFO.is_match(atis);
+        // 1) The specific phrases, which say what each runway is used for
+        for phrase in self.phrases.iter().filter(|phrase| !phrase.fallback) {
+            for caps in phrase.regex.captures_iter(atis) {
+                matched.push(caps.get(0).unwrap().range());
+                for (runway, runway_use) in phrase.runways(&caps) {
+                    upsert(&mut runways, runway, runway_use);
+                }
+            }
+        }
 
-    let mut runways = IndexMap::new();
+        // 2) Generic phrases such as "RUNWAY XX IN USE" as fallback, BUT:
+        //    - interpret by bulletin type if split ATIS
+        //    - ignore matches that are part of a specific phrase, e.g.
+        //      "DEPARTURE RUNWAY 01L IN USE"
+        //    - if combined/unknown, do not override already-known specific info
+        for phrase in self.phrases.iter().filter(|phrase| phrase.fallback) {
+            for caps in phrase.regex.captures_iter(atis) {
+                let whole = caps.get(0).unwrap().range();
+                if matched
+                    .iter()
+                    .any(|span| span.start < whole.end && whole.start < span.end)
+                {
+                    continue;
+                }
+                for (runway, runway_use) in phrase.runways(&caps) {
+                    match bulletin {
+                        Some(bulletin_use) => upsert(&mut runways, runway, bulletin_use),
+                        None => {
+                            if !runways.contains_key(runway) {
+                                runways.insert(runway.to_string(), runway_use);
+                            }
+                        }
+                    }
+                }
+            }
+        }
 
-    // 1) Parse the most specific / unambiguous forms first
-    for c in MULTI.captures_iter(atis) {
-        upsert(&mut runways, &c[1], RunwayUse::Both);
-        upsert(&mut runways, &c[2], RunwayUse::Both);
+        runways
     }
+}
 
-    for c in SINGLE_PRE.captures_iter(atis) {
-        upsert(&mut runways, &c[1], RunwayUse::Both);
+impl Phrase {
+    fn runways<'a>(&'a self, caps: &'a Captures) -> impl Iterator<Item = (&'a str, RunwayUse)> {
+        self.roles.iter().filter_map(|(index, runway_use)| {
+            caps.get(*index)
+                .map(|runway| (runway.as_str(), *runway_use))
+        })
     }
+}
+
+fn compile_pattern(pattern: &str) -> CoreResult<Regex> {
+    let expanded = pattern.replace("{rwy}", RUNWAY_PATTERN);
+    Regex::new(&format!(r"(?i)\b(?:{expanded})\b"))
+        .map_err(|e| invalid_pattern(pattern, &e.to_string()))
+}
 
-    for c in ARR.captures_iter(atis) {
-        upsert(&mut runways, &c[1], RunwayUse::Arriving);
+/// `arrival`, `departure` or `both`, optionally followed by `_` and a suffix.
+fn group_role(pattern: &str, name: &str) -> CoreResult<RunwayUse> {
+    match name.split('_').next() {
+        Some("arrival") => Ok(RunwayUse::Arriving),
+        Some("departure") => Ok(RunwayUse::Departing),
+        Some("both") => Ok(RunwayUse::Both),
+        _ => Err(invalid_pattern(
+            pattern,
+            &format!("group {name:?} is not named arrival, departure or both"),
+        )),
     }
+}
 
-    for c in DEP.captures_iter(atis) {
-        upsert(&mut runways, &c[1], RunwayUse::Departing);
+fn invalid_pattern(pattern: &str, message: &str) -> CoreError {
+    CoreError::AtisPattern {
+        pattern: pattern.to_string(),
+        message: message.to_string(),
     }
+}
+
+/// What an ATIS says besides the runways in use.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AtisInfo {
+    /// The information letter, `L` for "INFORMATION LIMA".
+    pub letter: Option<char>,
+    /// Low visibility procedures are in operation.
+    pub low_visibility_procedures: bool,
+    /// The ILS category the LVP are for, 3 for "CATEGORY 3" or "CAT III".
+    pub lvp_category: Option<u8>,
+    pub transition_level: Option<u16>,
+    /// Runway condition codes, in the order given.
+    pub runway_conditions: Vec<RunwayCondition>,
+    pub closed_runways: Vec<String>,
+}
 
-    // 2) Generic "RUNWAY XX IN USE" as fallback, BUT:
-    //    - interpret by bulletin type if split ATIS
-    //    - ignore occurrences that are actually part of "DEPARTURE RUNWAY ..." or "APPROACH ... RUNWAY ..."
-    //    - if combined/unknown, do not override already-known specific info
-    for caps in SINGLE_POST.captures_iter(atis) {
-        let whole = caps.get(0).unwrap();
-        let rwy = &caps[1];
+/// A runway condition code (RWYCC) report, one code per third of the runway
+/// from the touchdown end, 6 for dry down to 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunwayCondition {
+    /// The runway named last before the codes, usually in an `RCR RWY 01L`
+    /// group. `None` when the ATIS names no runway before them.
+    pub runway: Option<String>,
+    pub codes: [u8; 3],
+}
 
-        // Look at a short window before the match to see if this "RUNWAY" is preceded by
-        // "DEPARTURE " or "APPROACH " (meaning it’s not a standalone "RUNWAY XX IN USE" statement).
-        let start = whole.start();
-        let window_start = start.saturating_sub(20);
-        let prefix = &atis[window_start..start];
+impl AtisInfo {
+    pub fn parse(atis: &str) -> AtisInfo {
+        static LETTER: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)\bINFORMATION ([A-Z]+)\b").unwrap());
+        static LVP: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"(?i)\b(?:LOW VISIBILITY PROCEDURES|LVP)(?: (?:CATEGORY|CAT) (I{1,3}|[123]))? (?:ARE |IS )?(?:IN (?:OPERATION|FORCE|PROGRESS)|ACTIVE)\b",
+            )
+            .unwrap()
+        });
+        static TRANSITION_LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?i)\bTRANSITION LEVEL (?:FL ?)?([0-9]{2,3})\b").unwrap()
+        });
+        static RWYCC: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"(?i)\bRWYCC ([0-6])[/ ]([0-6])[/ ]([0-6])\b").unwrap());
+        static RUNWAY: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(&format!(r"(?i)\b(?:RWY|RUNWAY) ({RUNWAY_PATTERN})\b")).unwrap()
+        });
+        static CLOSED: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(&format!(
+                r"(?i)\b(?:RWY|RUNWAY) ({RUNWAY_PATTERN})(?:/({RUNWAY_PATTERN}))? (?:IS )?(?:CLOSED|CLSD)\b"
+            ))
+            .unwrap()
+        });
 
-        if DEP_OR_APP_PREFIX.is_match(prefix) {
-            continue;
+        let letter = LETTER
+            .captures_iter(atis)
+            .find_map(|caps| phonetic_letter(&caps[1]));
+        let lvp = LVP.captures(atis);
+        let lvp_category = lvp
+            .as_ref()
+            .and_then(|caps| caps.get(1))
+            .and_then(
+                |category| match category.as_str().to_ascii_uppercase().as_str() {
+                    "1" | "I" => Some(1),
+                    "2" | "II" => Some(2),
+                    "3" | "III" => Some(3),
+                    _ => None,
+                },
+            );
+        let transition_level = TRANSITION_LEVEL
+            .captures(atis)
+            .and_then(|caps| caps[1].parse().ok());
+
+        let mut runway_conditions = Vec::new();
+        let mut previous_end = 0;
+        for caps in RWYCC.captures_iter(atis) {
+            let whole = caps.get(0).unwrap();
+            let runway = RUNWAY
+                .captures_iter(&atis[previous_end..whole.start()])
+                .last()
+                .map(|runway| runway[1].to_uppercase());
+            let code = |index: usize| caps[index].parse().unwrap();
+            runway_conditions.push(RunwayCondition {
+                runway,
+                codes: [code(1), code(2), code(3)],
+            });
+            previous_end = whole.end();
         }
 
-        if is_arrival {
-            upsert(&mut runways, rwy, RunwayUse::Arriving);
-        } else if is_departure {
-            upsert(&mut runways, rwy, RunwayUse::Departing);
-        } else {
-            // Combined/unknown: only use as fallback; never override specific info
-            if !runways.contains_key(rwy) {
-                runways.insert(rwy.to_string(), RunwayUse::Both);
+        let mut closed_runways = Vec::new();
+        for caps in CLOSED.captures_iter(atis) {
+            for runway in caps.iter().skip(1).flatten() {
+                let runway = runway.as_str().to_uppercase();
+                if !closed_runways.contains(&runway) {
+                    closed_runways.push(runway);
+                }
             }
         }
+
+        AtisInfo {
+            letter,
+            low_visibility_procedures: lvp.is_some(),
+            lvp_category,
+            transition_level,
+            runway_conditions,
+            closed_runways,
+        }
+    }
+
+    /// A one-line summary for the report, e.g.
+    /// `ATIS L, LVP CAT 3, TL 85, RWYCC 01L 3/3/3, RWY 19R closed`.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![match self.letter {
+            Some(letter) => format!("ATIS {letter}"),
+            None => "ATIS".to_string(),
+        }];
+        match (self.low_visibility_procedures, self.lvp_category) {
+            (true, Some(category)) => parts.push(format!("LVP CAT {category}")),
+            (true, None) => parts.push("LVP".to_string()),
+            (false, _) => {}
+        }
+        if let Some(level) = self.transition_level {
+            parts.push(format!("TL {level}"));
+        }
+        for condition in &self.runway_conditions {
+            let [first, second, third] = condition.codes;
+            parts.push(match &condition.runway {
+                Some(runway) => format!("RWYCC {runway} {first}/{second}/{third}"),
+                None => format!("RWYCC {first}/{second}/{third}"),
+            });
+        }
+        if !self.closed_runways.is_empty() {
+            parts.push(format!("RWY {} closed", self.closed_runways.join(", ")));
+        }
+        parts.join(", ")
     }
+}
 
-    runways
+/// `L` for `LIMA`, and single letters as they are.
+fn phonetic_letter(word: &str) -> Option<char> {
+    const ALPHABET: [&str; 26] = [
+        "ALFA", "BRAVO", "CHARLIE", "DELTA", "ECHO", "FOXTROT", "GOLF", "HOTEL", "INDIA",
+        "JULIETT", "KILO", "LIMA", "MIKE", "NOVEMBER", "OSCAR", "PAPA", "QUEBEC", "ROMEO",
+        "SIERRA", "TANGO", "UNIFORM", "VICTOR", "WHISKEY", "XRAY", "YANKEE", "ZULU",
+    ];
+    let word = word.to_ascii_uppercase();
+    let word = match word.as_str() {
+        "ALPHA" => "ALFA",
+        "JULIET" => "JULIETT",
+        "WHISKY" => "WHISKEY",
+        other => other,
+    };
+    if word.len() == 1 {
+        return word.chars().next();
+    }
+    ALPHABET
+        .contains(&word)
+        .then(|| word.chars().next())
+        .flatten()
 }
 
 fn upsert(map: &mut IndexMap<String, RunwayUse>, rwy: &str, new_use: RunwayUse) {
@@ -98,9 +349,150 @@ fn upsert(map: &mut IndexMap<String, RunwayUse>, rwy: &str, new_use: RunwayUse)
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
+    use runway_selector_area_config::{AtisPhrase, AtisRole};
+    use serde::Deserialize;
 
     use super::*;
 
+    #[derive(Deserialize)]
+    struct Corpus {
+        case: Vec<CorpusCase>,
+    }
+
+    #[derive(Deserialize)]
+    struct CorpusCase {
+        atis: String,
+        runways: IndexMap<String, AtisRole>,
+    }
+
+    #[test]
+    fn test_corpus_per_area() {
+        let corpora = [
+            ("ENOR", include_str!("../atis_corpus/ENOR.toml")),
+            ("ESAA", include_str!("../atis_corpus/ESAA.toml")),
+            ("EKDK", include_str!("../atis_corpus/EKDK.toml")),
+            ("EFIN", include_str!("../atis_corpus/EFIN.toml")),
+        ];
+        for (area, corpus) in corpora {
+            let corpus: Corpus = toml::from_str(corpus).unwrap();
+            for case in corpus.case {
+                let expected: IndexMap<_, RunwayUse> = case
+                    .runways
+                    .into_iter()
+                    .map(|(runway, role)| (runway, role.into()))
+                    .collect();
+                assert_eq!(
+                    find_runway_in_use_from_atis(&case.atis),
+                    expected,
+                    "{area}: {}",
+                    case.atis
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_area_phrases_extend_the_builtin_grammar() {
+        let atis = "SOMEWHERE INFORMATION ALFA .. RUNWAY 27 ACTIVE .. ARRIVALS EXPECT RUNWAY 33 .. QNH 1013";
+        assert!(find_runway_in_use_from_atis(atis).is_empty());
+
+        let config = AtisGrammarConfig {
+            phrases: vec![
+                AtisPhrase {
+                    pattern: "RUNWAY (?<both>{rwy}) ACTIVE".to_string(),
+                    fallback: true,
+                },
+                AtisPhrase {
+                    pattern: "ARRIVALS EXPECT RUNWAY (?<arrival>{rwy})".to_string(),
+                    fallback: false,
+                },
+            ],
+            bulletins: Vec::new(),
+        };
+        let grammar = AtisGrammar::extended(&config).unwrap();
+        let expected: IndexMap<_, _> = [
+            ("33".to_owned(), RunwayUse::Arriving),
+            ("27".to_owned(), RunwayUse::Both),
+        ]
+        .into();
+        assert_eq!(grammar.find_runways_in_use(atis), expected);
+        assert_eq!(
+            grammar.find_runways_in_use("RUNWAY IN USE 09"),
+            find_runway_in_use_from_atis("RUNWAY IN USE 09")
+        );
+    }
+
+    #[test]
+    fn test_phrases_must_name_their_roles() {
+        for pattern in [
+            "RUNWAY ({rwy}) IN USE",
+            "RUNWAY (?<landing>{rwy})",
+            "RUNWAY (",
+        ] {
+            let config = AtisGrammarConfig {
+                phrases: vec![AtisPhrase {
+                    pattern: pattern.to_string(),
+                    fallback: false,
+                }],
+                bulletins: Vec::new(),
+            };
+            assert!(
+                matches!(
+                    AtisGrammar::extended(&config),
+                    Err(CoreError::AtisPattern { .. })
+                ),
+                "{pattern}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_atis_info_from_engm_bulletins() {
+        let single = "OSLO GARDERMOEN INFORMATION LIMA .. TIME 1550 .. EXPECT ILS OR RNP APPROACH RUNWAY 01R .. DEPARTURE RUNWAY 01L IN USE .. RCR RWY 01L AT TIME 1427 .. RWYCC 3/3/3 .. 100 PERCENT 06 MM DRY SNOW .. RCR RWY 01R AT TIME 1429 .. RWYCC 5/4/3 .. 100 PERCENT 04 MM DRY SNOW .. TRANSITION LEVEL 85 .. ACKNOWLEDGE INFORMATION LIMA ON FIRST CONTACT.";
+        let info = AtisInfo::parse(single);
+        assert_eq!(info.letter, Some('L'));
+        assert!(!info.low_visibility_procedures);
+        assert_eq!(info.transition_level, Some(85));
+        assert_eq!(
+            info.runway_conditions,
+            [
+                RunwayCondition {
+                    runway: Some("01L".to_string()),
+                    codes: [3, 3, 3],
+                },
+                RunwayCondition {
+                    runway: Some("01R".to_string()),
+                    codes: [5, 4, 3],
+                },
+            ]
+        );
+        assert_eq!(
+            info.summary(),
+            "ATIS L, TL 85, RWYCC 01L 3/3/3, RWYCC 01R 5/4/3"
+        );
+
+        let lvp = "Oslo Gardermoen Information November .. time 0920 .. Expect ILS or RNP approach Runway 19R .. Departure runway 19L in use .. Transition level 80 .. Low visibility procedures Category 3 in operation .. Met Report .. wind calm .. Acknowledge information November on first contact.";
+        let info = AtisInfo::parse(lvp);
+        assert_eq!(info.letter, Some('N'));
+        assert!(info.low_visibility_procedures);
+        assert_eq!(info.lvp_category, Some(3));
+        assert_eq!(info.summary(), "ATIS N, LVP CAT 3, TL 80");
+    }
+
+    #[test]
+    fn test_atis_info_closed_runways_and_missing_fields() {
+        let atis = "HELSINKI-VANTAA INFORMATION ROMEO 1020Z .. ARR RWY 15 .. DEP RWY 22R .. RWY 04L/22R CLOSED .. RUNWAY 04R IS CLOSED .. LVP IN FORCE .. QNH 1017";
+        let info = AtisInfo::parse(atis);
+        assert_eq!(info.letter, Some('R'));
+        assert!(info.low_visibility_procedures);
+        assert_eq!(info.lvp_category, None);
+        assert_eq!(info.transition_level, None);
+        assert_eq!(info.closed_runways, ["04L", "22R", "04R"]);
+        assert_eq!(info.summary(), "ATIS R, LVP, RWY 04L, 22R, 04R closed");
+
+        assert_eq!(AtisInfo::parse("NOTHING USEFUL"), AtisInfo::default());
+    }
+
     #[test]
     fn test_find_runway_in_use_from_atis() {
         let text = "RUNWAY IN USE 19L";
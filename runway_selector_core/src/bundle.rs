@@ -0,0 +1,423 @@
+//! Capture-and-replay bundles: everything one sector file's selection run
+//! read, and what it selected, in one `.tar.gz`. A run that picked the wrong
+//! runway can then be reproduced after the METARs, the ATISes and the time
+//! of day have moved on.
+//!
+//! The archive holds:
+//!
+//! - `bundle.json`: when, by which version and for which sector file
+//!   ([`BundleManifest`])
+//! - `area.toml`: the active area's configuration, when there was one
+//! - `runways.sct`, or `airports.csv` and `runways.csv`: the runway data
+//!   ([`RunwayData`])
+//! - `data.json`: the METAR pages, ATISes and controllers read
+//!   ([`Recording`])
+//! - `plugins/<area>.json`: each area plugin's request and response
+//!   ([`PluginExchange`])
+//! - `selection.rwy`: the `ACTIVE_RUNWAY:` lines written
+
+use std::{
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use indexmap::{IndexMap, IndexSet};
+use jiff::{Timestamp, tz::TimeZone};
+use runway_plugin_api::{RunwaySelectionsRequest, RunwaySelectionsResponse};
+use runway_selector_area_config::AreaConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    airports::Airports,
+    data_source::Recording,
+    error::{CoreError, CoreResult},
+    sector_file::runway_sections,
+};
+
+const MANIFEST_FILE: &str = "bundle.json";
+const AREA_CONFIG_FILE: &str = "area.toml";
+const SECTOR_FILE: &str = "runways.sct";
+const AIRPORTS_CSV_FILE: &str = "airports.csv";
+const RUNWAYS_CSV_FILE: &str = "runways.csv";
+const RECORDING_FILE: &str = "data.json";
+const PLUGINS_DIR: &str = "plugins/";
+const SELECTION_FILE: &str = "selection.rwy";
+
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub manifest: BundleManifest,
+    pub area_config: Option<AreaConfig>,
+    pub runway_data: RunwayData,
+    pub recording: Recording,
+    pub plugins: Vec<PluginExchange>,
+    /// The `ACTIVE_RUNWAY:` lines, see [`crate::output::active_runway_lines`].
+    pub selection: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub host_version: String,
+    /// When the selection ran. Plugins are asked for this time again on
+    /// replay.
+    pub captured_at: Timestamp,
+    pub sector_file_prefix: String,
+    /// The area whose configuration is in `area.toml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+}
+
+/// The runways a selection run started from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunwayData {
+    /// The [`runway_sections`] of the sector file.
+    SectorFile(String),
+    /// The OurAirports exports, see [`crate::ourairports`].
+    OurAirports {
+        airports_csv: String,
+        runways_csv: String,
+    },
+}
+
+impl RunwayData {
+    pub fn read_sector_file(path: &Path) -> CoreResult<Self> {
+        Ok(RunwayData::SectorFile(runway_sections(&mut File::open(
+            path,
+        )?)?))
+    }
+
+    pub fn read_ourairports(airports_csv: &Path, runways_csv: &Path) -> CoreResult<Self> {
+        Ok(RunwayData::OurAirports {
+            airports_csv: fs::read_to_string(airports_csv)?,
+            runways_csv: fs::read_to_string(runways_csv)?,
+        })
+    }
+
+    /// Adds the airports to `airports`. `icao_prefixes` limits the
+    /// airports loaded from OurAirports data, as in
+    /// [`crate::RunwaySource::OurAirports`].
+    pub fn load_into(
+        &self,
+        airports: &mut Airports,
+        icao_prefixes: &[String],
+        ignored_airports: &IndexSet<String>,
+    ) -> CoreResult<()> {
+        match self {
+            RunwayData::SectorFile(text) => {
+                airports.load_airports_from_sector_file(&mut text.as_bytes(), ignored_airports)
+            }
+            RunwayData::OurAirports {
+                airports_csv,
+                runways_csv,
+            } => airports.load_airports_from_ourairports(
+                airports_csv.as_bytes(),
+                runways_csv.as_bytes(),
+                icao_prefixes,
+                ignored_airports,
+            ),
+        }
+    }
+}
+
+/// What the host sent one area plugin, and what came back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginExchange {
+    pub area: String,
+    pub version: String,
+    pub request: RunwaySelectionsRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<RunwaySelectionsResponse>,
+    /// Why there is no response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Bundle {
+    /// `<prefix>-<UTC time>.tar.gz`, e.g. `ENOR-20261017-142000Z.tar.gz`.
+    pub fn file_name(&self) -> String {
+        let time = self
+            .manifest
+            .captured_at
+            .to_zoned(TimeZone::UTC)
+            .strftime("%Y%m%d-%H%M%SZ");
+        format!("{}-{time}.tar.gz", self.manifest.sector_file_prefix)
+    }
+
+    pub fn write(&self, path: &Path) -> CoreResult<()> {
+        let gz = GzEncoder::new(File::create(path)?, Compression::default());
+        let mut builder = tar::Builder::new(gz);
+        let mtime = self.manifest.captured_at.as_second().max(0) as u64;
+        let mut add = |name: &str, contents: &[u8]| -> io::Result<()> {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            header.set_cksum();
+            builder.append_data(&mut header, name, contents)
+        };
+
+        add(MANIFEST_FILE, &serde_json::to_vec_pretty(&self.manifest)?)?;
+        if let Some(config) = &self.area_config {
+            let toml = toml::to_string(config).map_err(|e| CoreError::Bundle(e.to_string()))?;
+            add(AREA_CONFIG_FILE, toml.as_bytes())?;
+        }
+        match &self.runway_data {
+            RunwayData::SectorFile(text) => add(SECTOR_FILE, text.as_bytes())?,
+            RunwayData::OurAirports {
+                airports_csv,
+                runways_csv,
+            } => {
+                add(AIRPORTS_CSV_FILE, airports_csv.as_bytes())?;
+                add(RUNWAYS_CSV_FILE, runways_csv.as_bytes())?;
+            }
+        }
+        add(RECORDING_FILE, &serde_json::to_vec_pretty(&self.recording)?)?;
+        for exchange in &self.plugins {
+            add(
+                &format!("{PLUGINS_DIR}{}.json", exchange.area),
+                &serde_json::to_vec_pretty(exchange)?,
+            )?;
+        }
+        let mut selection = self.selection.join("\n");
+        selection.push('\n');
+        add(SELECTION_FILE, selection.as_bytes())?;
+
+        builder.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> CoreResult<Bundle> {
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(path)?));
+        let mut files = IndexMap::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents)?;
+            files.insert(name, contents);
+        }
+        Bundle::from_files(files)
+    }
+
+    fn from_files(mut files: IndexMap<String, String>) -> CoreResult<Bundle> {
+        let mut take = |name: &str| files.shift_remove(name);
+        let missing = |name: &str| CoreError::Bundle(format!("it has no {name}"));
+
+        let manifest = take(MANIFEST_FILE).ok_or_else(|| missing(MANIFEST_FILE))?;
+        let area_config = take(AREA_CONFIG_FILE)
+            .map(|toml| toml::from_str(&toml))
+            .transpose()
+            .map_err(|e| CoreError::AreaConfig(e.to_string()))?;
+        let runway_data = match (
+            take(SECTOR_FILE),
+            take(AIRPORTS_CSV_FILE),
+            take(RUNWAYS_CSV_FILE),
+        ) {
+            (Some(text), _, _) => RunwayData::SectorFile(text),
+            (None, Some(airports_csv), Some(runways_csv)) => RunwayData::OurAirports {
+                airports_csv,
+                runways_csv,
+            },
+            _ => return Err(missing(SECTOR_FILE)),
+        };
+        let recording = take(RECORDING_FILE).ok_or_else(|| missing(RECORDING_FILE))?;
+        let selection = take(SELECTION_FILE).ok_or_else(|| missing(SELECTION_FILE))?;
+        let plugins = files
+            .iter()
+            .filter(|(name, _)| name.starts_with(PLUGINS_DIR) && name.ends_with(".json"))
+            .map(|(_, exchange)| serde_json::from_str(exchange))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Bundle {
+            manifest: serde_json::from_str(&manifest)?,
+            area_config,
+            runway_data,
+            recording: serde_json::from_str(&recording)?,
+            plugins,
+            selection: selection
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// An airport whose runways differ between the recorded and the replayed
+/// selection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionDiff {
+    pub icao: String,
+    /// E.g. `01L dep`, in `.rwy` order.
+    pub recorded: Vec<String>,
+    pub replayed: Vec<String>,
+}
+
+impl Display for SelectionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |runways: &[String]| match runways {
+            [] => "none".to_string(),
+            runways => runways.join(", "),
+        };
+        write!(
+            f,
+            "{}: recorded {}; replayed {}",
+            self.icao,
+            list(&self.recorded),
+            list(&self.replayed)
+        )
+    }
+}
+
+/// The airports whose `ACTIVE_RUNWAY:` lines differ, in the order they
+/// first appear. The order of an airport's own lines does not matter.
+pub fn diff_selections(recorded: &[String], replayed: &[String]) -> Vec<SelectionDiff> {
+    let mut by_airport: IndexMap<&str, [Vec<String>; 2]> = IndexMap::new();
+    for (side, lines) in [recorded, replayed].into_iter().enumerate() {
+        for line in lines {
+            let mut fields = line.trim_start_matches("ACTIVE_RUNWAY:").split(':');
+            let (Some(icao), Some(runway), Some(flag)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let usage = match flag {
+                "1" => "dep",
+                "0" => "arr",
+                other => other,
+            };
+            by_airport.entry(icao).or_default()[side].push(format!("{runway} {usage}"));
+        }
+    }
+
+    by_airport
+        .into_iter()
+        .filter(|(_, [recorded, replayed])| {
+            let sorted = |runways: &Vec<String>| {
+                let mut runways = runways.clone();
+                runways.sort();
+                runways
+            };
+            sorted(recorded) != sorted(replayed)
+        })
+        .map(|(icao, [recorded, replayed])| SelectionDiff {
+            icao: icao.to_string(),
+            recorded,
+            replayed,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        airports::tests::make_test_airport, data_source::AtisText,
+        plugin_convert::airport_to_request,
+    };
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn round_trips_through_the_archive() {
+        let request = RunwaySelectionsRequest {
+            timestamp_utc: "2026-10-17T14:20:00Z".to_string(),
+            area_timezone: "Europe/Oslo".to_string(),
+            airports: vec![airport_to_request(&make_test_airport(
+                "ENGM 171420Z 19010KT CAVOK 10/M01 Q1020",
+            ))],
+        };
+        let bundle = Bundle {
+            manifest: BundleManifest {
+                host_version: "0.0.1".to_string(),
+                captured_at: "2026-10-17T14:20:00Z".parse().unwrap(),
+                sector_file_prefix: "ENOR".to_string(),
+                area: Some("enor".to_string()),
+            },
+            area_config: Some(AreaConfig {
+                metar_urls: vec!["https://metar.vatsim.net/EN".to_string()],
+                ..AreaConfig::default()
+            }),
+            runway_data: RunwayData::SectorFile(include_str!("../runway.test").to_string()),
+            recording: Recording {
+                metars: [(
+                    "https://metar.vatsim.net/EN".to_string(),
+                    "ENGM 171420Z 19010KT CAVOK 10/M01 Q1020".to_string(),
+                )]
+                .into(),
+                atis: vec![AtisText {
+                    callsign: "ENGM_ATIS".to_string(),
+                    frequency: "126.130".to_string(),
+                    lines: vec!["RUNWAY 19R IN USE".to_string()],
+                }],
+                controllers: Vec::new(),
+            },
+            plugins: vec![PluginExchange {
+                area: "enor".to_string(),
+                version: "0.1.0".to_string(),
+                request,
+                response: None,
+                error: Some("plugin exited".to_string()),
+            }],
+            selection: lines(&["ACTIVE_RUNWAY:ENGM:19R:1", "ACTIVE_RUNWAY:ENGM:19L:0"]),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(bundle.file_name());
+        assert!(path.ends_with("ENOR-20261017-142000Z.tar.gz"));
+        bundle.write(&path).unwrap();
+
+        let read = Bundle::read(&path).unwrap();
+        assert_eq!(read.manifest, bundle.manifest);
+        assert_eq!(
+            read.area_config.unwrap().metar_urls,
+            ["https://metar.vatsim.net/EN"]
+        );
+        assert_eq!(read.runway_data, bundle.runway_data);
+        assert_eq!(read.recording, bundle.recording);
+        assert_eq!(read.plugins.len(), 1);
+        assert_eq!(read.plugins[0].request.airports[0].icao, "ENGM");
+        assert_eq!(read.plugins[0].error.as_deref(), Some("plugin exited"));
+        assert_eq!(read.selection, bundle.selection);
+
+        let mut airports = Airports::new();
+        read.runway_data
+            .load_into(&mut airports, &[], &IndexSet::new())
+            .unwrap();
+        assert!(airports.airports.contains_key("ENGM"));
+    }
+
+    #[test]
+    fn diffs_selections_per_airport() {
+        let recorded = lines(&[
+            "ACTIVE_RUNWAY:ENGM:19R:1",
+            "ACTIVE_RUNWAY:ENGM:19L:0",
+            "ACTIVE_RUNWAY:ENZV:18:1",
+            "ACTIVE_RUNWAY:ENZV:18:0",
+            "ACTIVE_RUNWAY:ENBR:17:1",
+        ]);
+        let replayed = lines(&[
+            "ACTIVE_RUNWAY:ENGM:19L:0",
+            "ACTIVE_RUNWAY:ENGM:19R:1",
+            "ACTIVE_RUNWAY:ENZV:36:1",
+            "ACTIVE_RUNWAY:ENZV:36:0",
+            "ACTIVE_RUNWAY:ENVA:09:1",
+        ]);
+        let diffs: Vec<String> = diff_selections(&recorded, &replayed)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            diffs,
+            [
+                "ENZV: recorded 18 dep, 18 arr; replayed 36 dep, 36 arr",
+                "ENBR: recorded 17 dep; replayed none",
+                "ENVA: recorded none; replayed 09 dep",
+            ]
+        );
+        assert!(diff_selections(&recorded, &recorded).is_empty());
+    }
+}
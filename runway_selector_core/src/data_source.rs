@@ -0,0 +1,549 @@
+//! Where the host gets its live data from: METARs, ATISes and online
+//! controllers.
+//!
+//! [`HttpSource`] reads the VATSIM feeds, and is what a normal run uses.
+//! [`LocalSource`] reads files laid out like those feeds, and
+//! [`FixtureSource`] a [`Recording`] of everything a run read. The last two
+//! let the host run offline, in CI or against a staging feed.
+//! [`RecordingSource`] makes such a recording while reading another source.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+};
+
+use futures::future::{BoxFuture, FutureExt, try_join_all};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use vatsim_utils::models::V3ResponseData;
+
+use crate::{
+    error::{CoreError, CoreResult},
+    metar::get_metar_page,
+};
+
+/// The METAR file of a [`LocalSource`] directory, one report per line as the
+/// VATSIM METAR feed serves them.
+pub const LOCAL_METARS_FILE: &str = "metars.txt";
+/// The VATSIM data file of a [`LocalSource`] directory, in the format of
+/// `https://data.vatsim.net/v3/vatsim-data.json`.
+pub const LOCAL_VATSIM_DATA_FILE: &str = "vatsim-data.json";
+
+/// A source of METARs, ATISes and online controllers.
+pub trait DataSource: Send + Sync {
+    /// The METAR pages for `urls`, in order, one report per line. Sources
+    /// that are not split by URL return all their reports as one page.
+    fn metar_pages<'a>(&'a self, urls: &'a [&'a str]) -> BoxFuture<'a, CoreResult<Vec<String>>>;
+
+    /// Every ATIS online.
+    fn atis(&self) -> BoxFuture<'_, CoreResult<Vec<AtisText>>>;
+
+    /// Every controller online, ATIS stations excluded.
+    fn controllers(&self) -> BoxFuture<'_, CoreResult<Vec<OnlineController>>>;
+}
+
+/// An ATIS station and its text as VATSIM publishes it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtisText {
+    /// E.g. `ENGM_D_ATIS`.
+    pub callsign: String,
+    pub frequency: String,
+    /// The text, split into lines the way the controller client sent it.
+    pub lines: Vec<String>,
+}
+
+impl AtisText {
+    /// The ICAO code the callsign starts with.
+    pub fn icao(&self) -> &str {
+        self.callsign.get(..4).unwrap_or(&self.callsign)
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join(" ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnlineController {
+    /// E.g. `ENGM_TWR`.
+    pub callsign: String,
+    pub frequency: String,
+}
+
+impl OnlineController {
+    /// The part of the callsign before the first `_`, an ICAO code for
+    /// airport positions.
+    pub fn station(&self) -> &str {
+        self.callsign.split('_').next().unwrap_or_default()
+    }
+
+    /// Observers log in as controllers without a position.
+    pub fn is_observer(&self) -> bool {
+        self.callsign.ends_with("_OBS")
+    }
+}
+
+/// Everything a run read from its data source, for [`FixtureSource`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Recording {
+    /// METAR pages by the URL they were read from.
+    pub metars: IndexMap<String, String>,
+    pub atis: Vec<AtisText>,
+    pub controllers: Vec<OnlineController>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> CoreResult<Recording> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> CoreResult<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// The `data_source` setting of the host `config.toml`, and the value of
+/// `--data-source`: `http`, `local:<path>` or `fixture:<path>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DataSourceConfig {
+    /// The live VATSIM feeds.
+    #[default]
+    Http,
+    /// See [`LocalSource`].
+    Local { path: PathBuf },
+    /// A [`Recording`] saved as JSON.
+    Fixture { path: PathBuf },
+}
+
+impl DataSourceConfig {
+    pub fn open(&self) -> CoreResult<Box<dyn DataSource>> {
+        Ok(match self {
+            DataSourceConfig::Http => Box::new(HttpSource::new()),
+            DataSourceConfig::Local { path } => Box::new(LocalSource::new(path.clone())),
+            DataSourceConfig::Fixture { path } => Box::new(FixtureSource::load(path)?),
+        })
+    }
+}
+
+impl FromStr for DataSourceConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "http" => Ok(DataSourceConfig::Http),
+            Some(("local", path)) if !path.is_empty() => {
+                Ok(DataSourceConfig::Local { path: path.into() })
+            }
+            Some(("fixture", path)) if !path.is_empty() => {
+                Ok(DataSourceConfig::Fixture { path: path.into() })
+            }
+            _ => Err(format!(
+                "expected `http`, `local:<path>` or `fixture:<path>`, got `{s}`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DataSourceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSourceConfig::Http => write!(f, "http"),
+            DataSourceConfig::Local { path } => write!(f, "local:{}", path.display()),
+            DataSourceConfig::Fixture { path } => write!(f, "fixture:{}", path.display()),
+        }
+    }
+}
+
+/// The VATSIM METAR feed at the area's URLs, and the VATSIM v3 data feed for
+/// ATISes and controllers. The data feed is downloaded once per source.
+#[derive(Default)]
+pub struct HttpSource {
+    vatsim_data: OnceCell<V3ResponseData>,
+}
+
+impl HttpSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn vatsim_data(&self) -> CoreResult<&V3ResponseData> {
+        self.vatsim_data
+            .get_or_try_init(async || {
+                let data = vatsim_utils::live_api::Vatsim::new()
+                    .await?
+                    .get_v3_data()
+                    .await?;
+                Ok::<_, CoreError>(data)
+            })
+            .await
+    }
+}
+
+impl DataSource for HttpSource {
+    fn metar_pages<'a>(&'a self, urls: &'a [&'a str]) -> BoxFuture<'a, CoreResult<Vec<String>>> {
+        try_join_all(urls.iter().map(|url| get_metar_page(url))).boxed()
+    }
+
+    fn atis(&self) -> BoxFuture<'_, CoreResult<Vec<AtisText>>> {
+        async { Ok(atis_from_vatsim_data(self.vatsim_data().await?)) }.boxed()
+    }
+
+    fn controllers(&self) -> BoxFuture<'_, CoreResult<Vec<OnlineController>>> {
+        async { Ok(controllers_from_vatsim_data(self.vatsim_data().await?)) }.boxed()
+    }
+}
+
+/// Files laid out like the VATSIM feeds. `path` is either a directory with
+/// [`LOCAL_METARS_FILE`] and [`LOCAL_VATSIM_DATA_FILE`], or a single file:
+/// a `.json` file is read as VATSIM data and anything else as METARs. What
+/// is missing is empty.
+pub struct LocalSource {
+    metars: Option<PathBuf>,
+    vatsim_data: Option<PathBuf>,
+}
+
+impl LocalSource {
+    pub fn new(path: PathBuf) -> Self {
+        if path.is_dir() {
+            LocalSource {
+                metars: Some(path.join(LOCAL_METARS_FILE)),
+                vatsim_data: Some(path.join(LOCAL_VATSIM_DATA_FILE)),
+            }
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            LocalSource {
+                metars: None,
+                vatsim_data: Some(path),
+            }
+        } else {
+            LocalSource {
+                metars: Some(path),
+                vatsim_data: None,
+            }
+        }
+    }
+
+    fn vatsim_data(&self) -> CoreResult<Option<V3ResponseData>> {
+        match &self.vatsim_data {
+            Some(path) if path.exists() => {
+                Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+impl DataSource for LocalSource {
+    fn metar_pages<'a>(&'a self, _urls: &'a [&'a str]) -> BoxFuture<'a, CoreResult<Vec<String>>> {
+        async {
+            match &self.metars {
+                Some(path) if path.exists() => Ok(vec![fs::read_to_string(path)?]),
+                _ => Ok(Vec::new()),
+            }
+        }
+        .boxed()
+    }
+
+    fn atis(&self) -> BoxFuture<'_, CoreResult<Vec<AtisText>>> {
+        async {
+            Ok(self
+                .vatsim_data()?
+                .as_ref()
+                .map(atis_from_vatsim_data)
+                .unwrap_or_default())
+        }
+        .boxed()
+    }
+
+    fn controllers(&self) -> BoxFuture<'_, CoreResult<Vec<OnlineController>>> {
+        async {
+            Ok(self
+                .vatsim_data()?
+                .as_ref()
+                .map(controllers_from_vatsim_data)
+                .unwrap_or_default())
+        }
+        .boxed()
+    }
+}
+
+/// Replays a [`Recording`]. Asking for METARs from a URL the recording does
+/// not have is an error, so a replay cannot silently run on less data than
+/// the recorded run.
+pub struct FixtureSource {
+    recording: Recording,
+}
+
+impl FixtureSource {
+    pub fn new(recording: Recording) -> Self {
+        FixtureSource { recording }
+    }
+
+    pub fn load(path: &Path) -> CoreResult<Self> {
+        Ok(Self::new(Recording::load(path)?))
+    }
+}
+
+impl DataSource for FixtureSource {
+    fn metar_pages<'a>(&'a self, urls: &'a [&'a str]) -> BoxFuture<'a, CoreResult<Vec<String>>> {
+        async {
+            urls.iter()
+                .map(|url| {
+                    self.recording
+                        .metars
+                        .get(*url)
+                        .cloned()
+                        .ok_or_else(|| CoreError::NotRecorded(url.to_string()))
+                })
+                .collect()
+        }
+        .boxed()
+    }
+
+    fn atis(&self) -> BoxFuture<'_, CoreResult<Vec<AtisText>>> {
+        async { Ok(self.recording.atis.clone()) }.boxed()
+    }
+
+    fn controllers(&self) -> BoxFuture<'_, CoreResult<Vec<OnlineController>>> {
+        async { Ok(self.recording.controllers.clone()) }.boxed()
+    }
+}
+
+/// Reads through another source and keeps a [`Recording`] of everything
+/// read, for a capture bundle ([`crate::bundle`]).
+pub struct RecordingSource<'a> {
+    inner: &'a dyn DataSource,
+    recording: Mutex<Recording>,
+}
+
+impl<'a> RecordingSource<'a> {
+    pub fn new(inner: &'a dyn DataSource) -> Self {
+        RecordingSource {
+            inner,
+            recording: Mutex::default(),
+        }
+    }
+
+    pub fn into_recording(self) -> Recording {
+        self.recording
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, f: impl FnOnce(&mut Recording)) {
+        f(&mut self
+            .recording
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner));
+    }
+}
+
+impl DataSource for RecordingSource<'_> {
+    fn metar_pages<'a>(&'a self, urls: &'a [&'a str]) -> BoxFuture<'a, CoreResult<Vec<String>>> {
+        async {
+            let pages = self.inner.metar_pages(urls).await?;
+            // Sources not split by URL return one page for all of them; the
+            // other URLs are recorded as empty so a replay asks for the same.
+            self.record(|recording| {
+                for (index, url) in urls.iter().enumerate() {
+                    let page = pages.get(index).cloned().unwrap_or_default();
+                    recording.metars.insert(url.to_string(), page);
+                }
+            });
+            Ok(pages)
+        }
+        .boxed()
+    }
+
+    fn atis(&self) -> BoxFuture<'_, CoreResult<Vec<AtisText>>> {
+        async {
+            let atis = self.inner.atis().await?;
+            self.record(|recording| recording.atis = atis.clone());
+            Ok(atis)
+        }
+        .boxed()
+    }
+
+    fn controllers(&self) -> BoxFuture<'_, CoreResult<Vec<OnlineController>>> {
+        async {
+            let controllers = self.inner.controllers().await?;
+            self.record(|recording| recording.controllers = controllers.clone());
+            Ok(controllers)
+        }
+        .boxed()
+    }
+}
+
+fn atis_from_vatsim_data(data: &V3ResponseData) -> Vec<AtisText> {
+    data.atis
+        .iter()
+        .map(|atis| AtisText {
+            callsign: atis.callsign.clone(),
+            frequency: atis.frequency.clone(),
+            lines: atis.text_atis.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn controllers_from_vatsim_data(data: &V3ResponseData) -> Vec<OnlineController> {
+    data.controllers
+        .iter()
+        .map(|controller| OnlineController {
+            callsign: controller.callsign.clone(),
+            frequency: controller.frequency.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_command_line_form() {
+        assert_eq!("http".parse(), Ok(DataSourceConfig::Http));
+        assert_eq!(
+            "local:/tmp/feeds".parse(),
+            Ok(DataSourceConfig::Local {
+                path: "/tmp/feeds".into()
+            })
+        );
+        assert_eq!(
+            "fixture:C:\\runs\\engm.json".parse(),
+            Ok(DataSourceConfig::Fixture {
+                path: "C:\\runs\\engm.json".into()
+            })
+        );
+        assert!("local:".parse::<DataSourceConfig>().is_err());
+        assert!("ftp://example".parse::<DataSourceConfig>().is_err());
+        assert_eq!(
+            DataSourceConfig::Fixture {
+                path: "engm.json".into()
+            }
+            .to_string(),
+            "fixture:engm.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn local_directory_reads_metars_and_vatsim_data() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(LOCAL_METARS_FILE),
+            "ENGM 121020Z 01010KT CAVOK 10/M01 Q1020\n",
+        )
+        .unwrap();
+        let data = V3ResponseData {
+            atis: vec![vatsim_utils::models::Atis {
+                callsign: "ENGM_ATIS".to_string(),
+                frequency: "126.130".to_string(),
+                text_atis: Some(vec!["RUNWAY 01L".to_string(), "IN USE".to_string()]),
+                ..Default::default()
+            }],
+            controllers: vec![vatsim_utils::models::Controller {
+                callsign: "ENGM_TWR".to_string(),
+                frequency: "118.300".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        fs::write(
+            dir.path().join(LOCAL_VATSIM_DATA_FILE),
+            serde_json::to_string(&data).unwrap(),
+        )
+        .unwrap();
+
+        let source = DataSourceConfig::Local {
+            path: dir.path().to_path_buf(),
+        }
+        .open()
+        .unwrap();
+        let pages = source
+            .metar_pages(&["https://metar.vatsim.net/EN"])
+            .await
+            .unwrap();
+        assert_eq!(pages, ["ENGM 121020Z 01010KT CAVOK 10/M01 Q1020\n"]);
+        let atis = source.atis().await.unwrap();
+        assert_eq!(atis[0].icao(), "ENGM");
+        assert_eq!(atis[0].text(), "RUNWAY 01L IN USE");
+        assert_eq!(source.controllers().await.unwrap()[0].callsign, "ENGM_TWR");
+
+        let metars_only = LocalSource::new(dir.path().join(LOCAL_METARS_FILE));
+        assert!(metars_only.atis().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fixture_replays_a_recording() {
+        let recording = Recording {
+            metars: [(
+                "https://metar.vatsim.net/EN".to_string(),
+                "ENGM 121020Z 01010KT CAVOK 10/M01 Q1020".to_string(),
+            )]
+            .into(),
+            atis: vec![AtisText {
+                callsign: "ENGM_ATIS".to_string(),
+                frequency: "126.130".to_string(),
+                lines: vec!["RUNWAY 01L IN USE".to_string()],
+            }],
+            controllers: Vec::new(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.json");
+        recording.save(&path).unwrap();
+
+        let source = FixtureSource::load(&path).unwrap();
+        assert_eq!(source.recording, recording);
+        assert_eq!(
+            source
+                .metar_pages(&["https://metar.vatsim.net/EN"])
+                .await
+                .unwrap(),
+            ["ENGM 121020Z 01010KT CAVOK 10/M01 Q1020"]
+        );
+        assert!(matches!(
+            source.metar_pages(&["https://metar.vatsim.net/ES"]).await,
+            Err(CoreError::NotRecorded(url)) if url == "https://metar.vatsim.net/ES"
+        ));
+        assert_eq!(source.atis().await.unwrap(), recording.atis);
+    }
+
+    #[tokio::test]
+    async fn recording_source_records_what_was_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let metars = dir.path().join(LOCAL_METARS_FILE);
+        fs::write(&metars, "ENGM 121020Z 01010KT CAVOK 10/M01 Q1020\n").unwrap();
+        let local = LocalSource::new(metars);
+
+        let source = RecordingSource::new(&local);
+        let urls = ["https://metar.vatsim.net/EN", "https://metar.vatsim.net/ES"];
+        source.metar_pages(&urls).await.unwrap();
+        source.atis().await.unwrap();
+        let recording = source.into_recording();
+
+        assert_eq!(
+            recording.metars,
+            IndexMap::from([
+                (
+                    "https://metar.vatsim.net/EN".to_string(),
+                    "ENGM 121020Z 01010KT CAVOK 10/M01 Q1020\n".to_string()
+                ),
+                ("https://metar.vatsim.net/ES".to_string(), String::new()),
+            ])
+        );
+        assert!(recording.atis.is_empty());
+
+        let replay = FixtureSource::new(recording);
+        assert_eq!(
+            replay.metar_pages(&urls).await.unwrap().concat(),
+            "ENGM 121020Z 01010KT CAVOK 10/M01 Q1020\n"
+        );
+    }
+}
@@ -14,11 +14,21 @@ pub enum CoreError {
     #[error("HTTP error: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("Failed to parse METAR: {0}")]
-    MetarParse(#[from] nom::error::Error<String>),
+    MetarParse(#[from] metar_decoder::error::MetarParseError),
+    #[error("Failed to read JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The recording has no METARs from {0}")]
+    NotRecorded(String),
+    #[error("Invalid replay bundle: {0}")]
+    Bundle(String),
+    #[error("Failed to read CSV: {0}")]
+    Csv(#[from] csv::Error),
     #[error("Failed to decode sector file (tried UTF-8 and ISO-8859-1): {0}")]
     Encoding(String),
     #[error("Failed to parse area configuration: {0}")]
     AreaConfig(String),
+    #[error("Invalid ATIS phrase {pattern:?}: {message}")]
+    AtisPattern { pattern: String, message: String },
     #[error("Time error: {0}")]
     Time(#[from] jiff::Error),
     #[error("Async join error: {0}")]
@@ -0,0 +1,415 @@
+//! EuroScope sector extension (`.ese`) decoding: the `[POSITIONS]`,
+//! `[SIDSSTARS]` and `[AIRSPACE]` sections. Airspace is read as far as
+//! sector names, levels and owners; the sector lines themselves are skipped.
+
+use std::io::Read;
+
+use crate::{
+    airport::Airport,
+    airports::Airports,
+    error::CoreResult,
+    geo::Coordinate,
+    runway::RunwayUse,
+    sector_file::{read_with_encodings, sections},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProcedureKind {
+    Sid,
+    Star,
+}
+
+/// One `SID:` or `STAR:` line of `[SIDSSTARS]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Procedure {
+    pub kind: ProcedureKind,
+    pub airport: String,
+    pub runway: String,
+    pub name: String,
+    /// Fixes and airways in the order flown.
+    pub route: Vec<String>,
+}
+
+/// A controller position from `[POSITIONS]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub name: String,
+    pub radio_callsign: String,
+    pub frequency: String,
+    pub identifier: String,
+    pub prefix: String,
+    pub suffix: String,
+    /// The first and last squawk the position may assign.
+    pub squawk_range: Option<(u16, u16)>,
+    pub visibility_centers: Vec<Coordinate>,
+}
+
+/// A `SECTOR:` block of `[AIRSPACE]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sector {
+    pub name: String,
+    pub floor_ft: Option<u32>,
+    pub ceiling_ft: Option<u32>,
+    /// Position identifiers in order of priority.
+    pub owners: Vec<String>,
+    pub departure_airports: Vec<String>,
+    pub arrival_airports: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct EseFile {
+    pub positions: Vec<Position>,
+    pub procedures: Vec<Procedure>,
+    pub sectors: Vec<Sector>,
+}
+
+/// The procedures valid for one selected runway: SIDs when it is used for
+/// departures, STARs when it is used for arrivals.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunwayProcedures<'a> {
+    pub icao: &'a str,
+    pub runway: &'a str,
+    pub usage: RunwayUse,
+    pub sids: Vec<&'a Procedure>,
+    pub stars: Vec<&'a Procedure>,
+    /// Whether the `.ese` lists any procedures for the airport. Airports
+    /// without published procedures are never flagged as missing them.
+    pub airport_has_procedures: bool,
+}
+
+impl RunwayProcedures<'_> {
+    /// A selected runway with no procedures for its use, at an airport that
+    /// has procedures for other runways. Usually a typo in the selection or
+    /// a closed runway.
+    pub fn is_missing(&self) -> bool {
+        self.airport_has_procedures && self.sids.is_empty() && self.stars.is_empty()
+    }
+}
+
+impl EseFile {
+    /// Procedures for `runway` at `icao`. A runway given without its
+    /// parallel letter (`01`, as `default_runways` writes it) also matches
+    /// `01L` and `01R`.
+    pub fn procedures_for<'a>(
+        &'a self,
+        icao: &'a str,
+        runway: &'a str,
+    ) -> impl Iterator<Item = &'a Procedure> + 'a {
+        self.procedures.iter().filter(move |procedure| {
+            procedure.airport == icao
+                && (procedure.runway == runway
+                    || (runway.len() == 2 && procedure.runway.starts_with(runway)))
+        })
+    }
+
+    pub fn has_procedures(&self, icao: &str) -> bool {
+        self.procedures
+            .iter()
+            .any(|procedure| procedure.airport == icao)
+    }
+
+    /// The procedures for every runway in the preferred selection of each
+    /// airport, in airport order.
+    pub fn selected_runway_procedures<'a>(
+        &'a self,
+        airports: &'a Airports,
+    ) -> Vec<RunwayProcedures<'a>> {
+        airports
+            .airports
+            .values()
+            .flat_map(|airport| self.airport_runway_procedures(airport))
+            .collect()
+    }
+
+    fn airport_runway_procedures<'a>(
+        &'a self,
+        airport: &'a Airport,
+    ) -> impl Iterator<Item = RunwayProcedures<'a>> + 'a {
+        let airport_has_procedures = self.has_procedures(&airport.icao);
+        airport
+            .preferred_runways_in_use()
+            .into_iter()
+            .flatten()
+            .map(move |(runway, &usage)| {
+                let (sids, stars) = self
+                    .procedures_for(&airport.icao, runway)
+                    .filter(|procedure| match procedure.kind {
+                        ProcedureKind::Sid => usage != RunwayUse::Arriving,
+                        ProcedureKind::Star => usage != RunwayUse::Departing,
+                    })
+                    .partition(|procedure| procedure.kind == ProcedureKind::Sid);
+                RunwayProcedures {
+                    icao: &airport.icao,
+                    runway,
+                    usage,
+                    sids,
+                    stars,
+                    airport_has_procedures,
+                }
+            })
+    }
+}
+
+pub fn load_ese_file<R: Read>(reader: &mut R) -> CoreResult<EseFile> {
+    let ese_file = read_with_encodings(reader)?;
+    let sections = sections(&ese_file);
+    let section = |name: &str| sections.get(name).map(Vec::as_slice).unwrap_or_default();
+
+    Ok(EseFile {
+        positions: section("POSITIONS")
+            .iter()
+            .filter_map(|line| position(line))
+            .collect(),
+        procedures: section("SIDSSTARS")
+            .iter()
+            .filter_map(|line| procedure(line))
+            .collect(),
+        sectors: sectors(section("AIRSPACE")),
+    })
+}
+
+/// `SID:ENGM:01L:ADOPI3N:GM450 ADOPI`
+fn procedure(line: &str) -> Option<Procedure> {
+    let fields: Vec<_> = line.split(':').map(str::trim).collect();
+    let kind = match fields.first()?.to_ascii_uppercase().as_str() {
+        "SID" => ProcedureKind::Sid,
+        "STAR" => ProcedureKind::Star,
+        _ => return None,
+    };
+    let (airport, runway, name) = (fields.get(1)?, fields.get(2)?, fields.get(3)?);
+    if airport.is_empty() || runway.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some(Procedure {
+        kind,
+        airport: airport.to_string(),
+        runway: runway.to_string(),
+        name: name.to_string(),
+        route: fields
+            .get(4)
+            .map(|route| route.split_whitespace().map(String::from).collect())
+            .unwrap_or_default(),
+    })
+}
+
+/// Name, radio callsign, frequency, identifier, middle letter, prefix,
+/// suffix, two unused fields, first and last squawk, then up to four
+/// visibility centres.
+fn position(line: &str) -> Option<Position> {
+    let fields: Vec<_> = line.split(':').map(str::trim).collect();
+    if fields.len() < 7 {
+        return None;
+    }
+    let squawk = |index: usize| fields.get(index).and_then(|code| code.parse().ok());
+    Some(Position {
+        name: fields[0].to_string(),
+        radio_callsign: fields[1].to_string(),
+        frequency: fields[2].to_string(),
+        identifier: fields[3].to_string(),
+        prefix: fields[5].to_string(),
+        suffix: fields[6].to_string(),
+        squawk_range: squawk(9).zip(squawk(10)),
+        visibility_centers: fields
+            .get(11..)
+            .unwrap_or_default()
+            .chunks_exact(2)
+            .filter_map(|position| Coordinate::parse_sct(position[0], position[1]))
+            .collect(),
+    })
+}
+
+/// `SECTOR:name:floor:ceiling` starts a sector; the `OWNER:`, `DEPAPT:` and
+/// `ARRAPT:` lines after it belong to it.
+fn sectors(lines: &[&str]) -> Vec<Sector> {
+    let mut sectors: Vec<Sector> = Vec::new();
+    for line in lines {
+        let fields: Vec<_> = line.split(':').map(str::trim).collect();
+        let values = || {
+            fields[1..]
+                .iter()
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+        match fields[0].to_ascii_uppercase().as_str() {
+            "SECTOR" => sectors.push(Sector {
+                name: fields.get(1).copied().unwrap_or_default().to_string(),
+                floor_ft: fields.get(2).and_then(|floor| floor.parse().ok()),
+                ceiling_ft: fields.get(3).and_then(|ceiling| ceiling.parse().ok()),
+                ..Sector::default()
+            }),
+            "OWNER" => {
+                if let Some(sector) = sectors.last_mut() {
+                    sector.owners.extend(values());
+                }
+            }
+            "DEPAPT" => {
+                if let Some(sector) = sectors.last_mut() {
+                    sector.departure_airports.extend(values());
+                }
+            }
+            "ARRAPT" => {
+                if let Some(sector) = sectors.last_mut() {
+                    sector.arrival_airports.extend(values());
+                }
+            }
+            _ => {}
+        }
+    }
+    sectors
+}
+
+/// Sorted by procedure name, one line per selected runway, e.g.
+/// `ENGM 01L Dep: SIDs ADOPI3N, BAVAD3N`.
+pub fn describe(procedures: &RunwayProcedures<'_>) -> String {
+    let names = |list: &[&Procedure]| {
+        let mut names: Vec<_> = list.iter().map(|p| p.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        names.join(", ")
+    };
+    let mut parts = Vec::new();
+    if !procedures.sids.is_empty() {
+        parts.push(format!("SIDs {}", names(&procedures.sids)));
+    }
+    if !procedures.stars.is_empty() {
+        parts.push(format!("STARs {}", names(&procedures.stars)));
+    }
+    if parts.is_empty() {
+        parts.push("no procedures".to_string());
+    }
+    format!(
+        "{} {}{}: {}",
+        procedures.icao,
+        procedures.runway,
+        procedures.usage.report_suffix(),
+        parts.join("; ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{airport::RunwayInUseSource, airports::tests::make_test_airport};
+
+    const ESE: &str = concat!(
+        "[POSITIONS]\n",
+        "ENGM_TWR:Gardermoen Tower:118.300:GMT:T:ENGM:TWR:-:-:0401:0477:N060.11.39.000:E011.05.02.000\n",
+        "ENOS_APP:Oslo Approach:120.455:OSA:A:ENOS:APP:-:-\n",
+        "\n",
+        "[SIDSSTARS]\n",
+        "; Gardermoen\n",
+        "SID:ENGM:01L:ADOPI3N:GM450 ADOPI\n",
+        "SID:ENGM:01L:BAVAD3N:GM450 BAVAD\n",
+        "SID:ENGM:19R:ADOPI3S:GM410 ADOPI\n",
+        "STAR:ENGM:01R:ESEBA4N:ESEBA GM416\n",
+        "STAR:ENGM:19L:ESEBA4S:ESEBA GM420\n",
+        "STAR:ENBR:17:ASKOP1E:ASKOP BR017\n",
+        "SID:ENGM\n",
+        "\n",
+        "[AIRSPACE]\n",
+        "SECTORLINE:GM_TMA\n",
+        "COORD:N060.00.00.000:E011.00.00.000\n",
+        "SECTOR:OSLO_APP:0:19500\n",
+        "OWNER:OSA:OSN\n",
+        "BORDER:GM_TMA\n",
+        "DEPAPT:ENGM:ENRY\n",
+        "ARRAPT:ENGM\n",
+    );
+
+    fn ese() -> EseFile {
+        load_ese_file(&mut ESE.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn reads_positions_procedures_and_sectors() {
+        let ese = ese();
+
+        assert_eq!(ese.positions.len(), 2);
+        let tower = &ese.positions[0];
+        assert_eq!(tower.radio_callsign, "Gardermoen Tower");
+        assert_eq!(tower.identifier, "GMT");
+        assert_eq!(
+            (tower.prefix.as_str(), tower.suffix.as_str()),
+            ("ENGM", "TWR")
+        );
+        assert_eq!(tower.squawk_range, Some((401, 477)));
+        assert_eq!(tower.visibility_centers.len(), 1);
+        assert_eq!(ese.positions[1].squawk_range, None);
+
+        assert_eq!(ese.procedures.len(), 6);
+        let adopi = &ese.procedures[0];
+        assert_eq!(adopi.kind, ProcedureKind::Sid);
+        assert_eq!(adopi.route, ["GM450", "ADOPI"]);
+
+        assert_eq!(
+            ese.sectors,
+            [Sector {
+                name: "OSLO_APP".to_string(),
+                floor_ft: Some(0),
+                ceiling_ft: Some(19500),
+                owners: vec!["OSA".to_string(), "OSN".to_string()],
+                departure_airports: vec!["ENGM".to_string(), "ENRY".to_string()],
+                arrival_airports: vec!["ENGM".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn matches_runways_with_and_without_the_parallel_letter() {
+        let ese = ese();
+        let names = |runway| {
+            ese.procedures_for("ENGM", runway)
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("01L"), ["ADOPI3N", "BAVAD3N"]);
+        assert_eq!(names("01"), ["ADOPI3N", "BAVAD3N", "ESEBA4N"]);
+        assert!(names("1").is_empty());
+    }
+
+    #[test]
+    fn lists_procedures_for_the_selected_runways() {
+        let ese = ese();
+        let mut airports = Airports::new();
+        let mut engm = make_test_airport("ENGM 161220Z 01008KT CAVOK 08/04 Q1026");
+        engm.runways_in_use.insert(
+            RunwayInUseSource::Metar,
+            [
+                ("01L".to_string(), RunwayUse::Departing),
+                ("01R".to_string(), RunwayUse::Arriving),
+            ]
+            .into(),
+        );
+        airports.add_airport(engm);
+        let mut enbr = make_test_airport("ENBR 161220Z 35008KT CAVOK 08/04 Q1026");
+        enbr.runways_in_use.insert(
+            RunwayInUseSource::Default,
+            [("35".to_string(), RunwayUse::Both)].into(),
+        );
+        airports.add_airport(enbr);
+        let mut enzv = make_test_airport("ENZV 161220Z 18008KT CAVOK 08/04 Q1026");
+        enzv.runways_in_use.insert(
+            RunwayInUseSource::Default,
+            [("18".to_string(), RunwayUse::Both)].into(),
+        );
+        airports.add_airport(enzv);
+
+        let selected = ese.selected_runway_procedures(&airports);
+        let described: Vec<_> = selected.iter().map(describe).collect();
+        assert_eq!(
+            described,
+            [
+                "ENGM 01L Dep: SIDs ADOPI3N, BAVAD3N",
+                "ENGM 01R Arr: STARs ESEBA4N",
+                "ENBR 35: no procedures",
+                "ENZV 18: no procedures",
+            ]
+        );
+        let missing: Vec<_> = selected
+            .iter()
+            .filter(|p| p.is_missing())
+            .map(|p| p.icao)
+            .collect();
+        assert_eq!(missing, ["ENBR"]);
+    }
+}
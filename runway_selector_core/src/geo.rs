@@ -0,0 +1,117 @@
+//! Positions on the earth as sector files write them, and the great-circle
+//! distance and bearing between them.
+
+use std::fmt::{self, Display};
+
+/// Mean earth radius in metres (IUGG).
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// A WGS-84 position in decimal degrees, north and east positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinate {
+    /// Parses a sector file position such as `N060.11.06.000 E011.04.25.478`
+    /// (hemisphere, degrees, minutes, seconds and thousandths). Returns `None`
+    /// for anything else, such as the name of a fix, which sector files may
+    /// use in place of a position.
+    pub fn parse_sct(latitude: &str, longitude: &str) -> Option<Self> {
+        let latitude = parse_sct_angle(latitude, 'N', 'S', 90.0)?;
+        let longitude = parse_sct_angle(longitude, 'E', 'W', 180.0)?;
+        Some(Coordinate {
+            latitude,
+            longitude,
+        })
+    }
+
+    /// Great-circle distance in metres.
+    pub fn distance_m(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_dlat = (lat2 - lat1) / 2.0;
+        let half_dlon = (other.longitude - self.longitude).to_radians() / 2.0;
+        let a = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+    }
+
+    /// Initial great-circle bearing towards `other`, in degrees true
+    /// (0 inclusive to 360 exclusive).
+    pub fn bearing_to(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlon = (other.longitude - self.longitude).to_radians();
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        y.atan2(x).to_degrees().rem_euclid(360.0)
+    }
+}
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ns = if self.latitude < 0.0 { 'S' } else { 'N' };
+        let ew = if self.longitude < 0.0 { 'W' } else { 'E' };
+        write!(
+            f,
+            "{:.5}°{ns} {:.5}°{ew}",
+            self.latitude.abs(),
+            self.longitude.abs()
+        )
+    }
+}
+
+/// `N060.11.06.000` → 60.185. `positive` and `negative` are the hemisphere
+/// letters; values beyond `limit` degrees are rejected.
+fn parse_sct_angle(text: &str, positive: char, negative: char, limit: f64) -> Option<f64> {
+    let mut chars = text.chars();
+    let sign = match chars.next()?.to_ascii_uppercase() {
+        c if c == positive => 1.0,
+        c if c == negative => -1.0,
+        _ => return None,
+    };
+    let mut fields = chars.as_str().splitn(3, '.');
+    let degrees: u32 = fields.next()?.parse().ok()?;
+    let minutes: u32 = fields.next()?.parse().ok()?;
+    let seconds: f64 = fields.next()?.parse().ok()?;
+    if minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    let value = f64::from(degrees) + f64::from(minutes) / 60.0 + seconds / 3600.0;
+    (value <= limit).then_some(sign * value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sector_file_positions() {
+        let threshold = Coordinate::parse_sct("N060.11.06.000", "E011.04.25.478").unwrap();
+        assert!((threshold.latitude - 60.185).abs() < 1e-9);
+        assert!((threshold.longitude - 11.073744).abs() < 1e-6);
+
+        let west = Coordinate::parse_sct("S033.56.46.000", "W151.10.38.000").unwrap();
+        assert!(west.latitude < 0.0 && west.longitude < 0.0);
+
+        assert_eq!(Coordinate::parse_sct("GM", "GM"), None);
+        assert_eq!(
+            Coordinate::parse_sct("N060.61.00.000", "E011.00.00.000"),
+            None
+        );
+        assert_eq!(
+            Coordinate::parse_sct("N091.00.00.000", "E011.00.00.000"),
+            None
+        );
+    }
+
+    #[test]
+    fn distance_and_bearing_of_engm_01l() {
+        let south = Coordinate::parse_sct("N060.11.06.000", "E011.04.25.478").unwrap();
+        let north = Coordinate::parse_sct("N060.12.57.841", "E011.05.29.990").unwrap();
+        // The sector file's thresholds, 3600 m apart on the ground.
+        assert!((south.distance_m(&north) - 3600.0).abs() < 30.0);
+        assert!((south.bearing_to(&north) - 16.0).abs() < 0.1);
+        assert!((north.bearing_to(&south) - 196.0).abs() < 0.1);
+        assert_eq!(south.to_string(), "60.18500°N 11.07374°E");
+    }
+}
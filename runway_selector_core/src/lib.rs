@@ -3,9 +3,15 @@
 //! Core owns the host-side runtime concerns that do not depend on a specific
 //! FIR (Polaris, Stockholm, …):
 //!
-//! - sector file decoding ([`sector_file`])
+//! - sector file decoding ([`sector_file`]) and runway geometry ([`geo`])
+//! - runway sanity checks over the decoded sector file ([`runway_check`])
+//! - OurAirports CSV exports as an alternative runway source ([`ourairports`])
+//! - SIDs, STARs and positions from the sector extension file ([`ese`])
 //! - METAR fetching ([`metar`]) — VATSIM URL list passed by the caller
-//! - ATIS regex parsing ([`atis`])
+//! - the live-data sources METARs, ATISes and controllers are read from:
+//!   HTTP, local files or a recording ([`data_source`])
+//! - ATIS parsing with a built-in phrase grammar areas can extend ([`atis`])
+//! - capture-and-replay bundles of a selection run ([`bundle`])
 //! - runway wind component math ([`airport`])
 //! - the runway-source priority model ([`airport::RunwayInUseSource`])
 //! - the host-side converter that lowers parsed METARs and pre-computed wind
@@ -28,24 +34,32 @@
 pub mod airport;
 pub mod airports;
 pub mod atis;
+pub mod bundle;
+pub mod data_source;
 pub mod error;
+pub mod ese;
+pub mod geo;
 pub mod metar;
+pub mod ourairports;
 pub mod output;
 pub mod plugin_convert;
 pub mod runway;
+pub mod runway_check;
 pub mod sector_file;
 pub mod util;
 
 pub use airport::{Airport, CrosswindDirection, RunwayInUseSource, RunwayWindComponents};
 pub use airports::Airports;
 pub use error::{CoreError, CoreResult};
-pub use runway::{Runway, RunwayDirection, RunwayUse};
+pub use geo::Coordinate;
+pub use runway::{HeadingReference, Runway, RunwayDirection, RunwayUse};
 
 /// Re-export of the area-config crate for callers that already depend on
 /// `runway_selector_core`. New crates should depend on
 /// `runway_selector_area_config` directly.
 pub use runway_selector_area_config as area_config;
 pub use runway_selector_area_config::{
-    AreaConfig, AreaManifest, ProfileConfig, Runtime, TopLevelConfig, load_area_config,
-    load_area_manifest, load_profile_config, merge_local_overrides,
+    AreaConfig, AreaManifest, AtisGrammarConfig, ProfileConfig, Runtime, RunwaySource,
+    TopLevelConfig, load_area_config, load_area_manifest, load_profile_config,
+    merge_local_overrides,
 };
@@ -1,34 +1,81 @@
-use std::str::FromStr;
-
-use futures::future::try_join_all;
 use indexmap::IndexSet;
-use metar_decoder::metar::Metar;
+use metar_decoder::metar::{Metar, NilReport};
 use tracing_unwrap::ResultExt;
 
-use crate::error::CoreResult;
+use crate::{data_source::DataSource, error::CoreResult};
 
 const METAR_FETCH_RETRIES: u32 = 3;
 
-/// Fetch METARs from the supplied VATSIM URLs and parse them.
+/// Read METARs for the supplied VATSIM URLs from `source` and parse them.
 ///
 /// `urls` are area-specific (e.g. `https://metar.vatsim.net/EN` for the
-/// Norwegian FIR). `ignore` skips any ICAO present in the set — typically
-/// airports known to publish unparseable reports.
-pub async fn get_metars(urls: &[&str], ignore: &IndexSet<String>) -> CoreResult<Vec<Metar>> {
-    let pages = try_join_all(urls.iter().map(async |url| get_metars_from_url(url).await)).await?;
+/// Norwegian FIR). `ignore` skips any ICAO present in the set. Reports are
+/// parsed leniently, so an unknown group only drops that group and is logged
+/// instead of dropping the whole report.
+pub async fn get_metars(
+    source: &dyn DataSource,
+    urls: &[&str],
+    ignore: &IndexSet<String>,
+) -> CoreResult<Vec<Metar>> {
+    let pages = source.metar_pages(urls).await?;
 
     let values = pages
         .iter()
         .flat_map(|s| s.lines())
-        .filter(|line| !ignore.contains(&line[0..4]))
-        .map(Metar::from_str)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        // Lines too short to name a station are left for the parser to reject.
+        .filter(|line| {
+            station_of(line)
+                .get(..4)
+                .is_none_or(|icao| !ignore.contains(icao))
+        })
+        .filter(|line| match line.parse::<NilReport>() {
+            Ok(nil) => {
+                tracing::debug!(icao = %nil.icao, "Skipping NIL METAR");
+                false
+            }
+            Err(_) => true,
+        })
+        .map(Metar::parse_lenient)
         .filter_map(Result::ok_or_log)
+        .map(|parsed| {
+            if !parsed.unparsed.is_empty() {
+                let skipped = parsed
+                    .unparsed
+                    .iter()
+                    .map(|span| &span.text)
+                    .collect::<Vec<_>>();
+                tracing::warn!(
+                    icao = %parsed.metar.icao,
+                    raw = %parsed.metar.raw,
+                    ?skipped,
+                    "Skipped unknown METAR groups"
+                );
+            }
+            parsed.metar
+        })
         .collect();
     Ok(values)
 }
 
+/// The report without a leading `METAR`/`SPECI` keyword.
+fn station_of(line: &str) -> &str {
+    line.strip_prefix("METAR ")
+        .or_else(|| line.strip_prefix("SPECI "))
+        .unwrap_or(line)
+}
+
+/// Whether `candidate` should replace `current` for the same airport: the
+/// newer observation wins, and a SPECI wins over a METAR from the same time.
+pub fn supersedes(candidate: &Metar, current: &Metar) -> bool {
+    let key = |m: &Metar| (m.timestamp.zoned().timestamp(), m.report_type);
+    key(candidate) > key(current)
+}
+
+/// Download one page of the VATSIM METAR feed, retrying on failure.
 #[tracing::instrument]
-async fn get_metars_from_url(url: &str) -> CoreResult<String> {
+pub(crate) async fn get_metar_page(url: &str) -> CoreResult<String> {
     let client = reqwest::ClientBuilder::new()
         .timeout(std::time::Duration::from_secs(5))
         .build()
@@ -72,6 +119,10 @@ async fn get_metars_from_url(url: &str) -> CoreResult<String> {
 
 #[cfg(test)]
 mod tests {
+    use indexmap::IndexSet;
+
+    use super::{Metar, get_metars, station_of, supersedes};
+    use crate::data_source::{FixtureSource, Recording};
     use metar_decoder::{
         optional_data::OptionalData,
         units::{
@@ -213,4 +264,46 @@ mod tests {
     //         _ => panic!("Expected WindSpeed::Knot"),
     //     }
     // }
+
+    #[test]
+    fn newer_report_or_speci_supersedes() {
+        let metar: Metar = "ENGM 161220Z 01008KT CAVOK 08/04 Q1026".parse().unwrap();
+        let speci: Metar = "SPECI ENGM 161235Z 01008KT 0800 FG VV002 08/08 Q1026"
+            .parse()
+            .unwrap();
+        let same_time_speci: Metar = "SPECI ENGM 161220Z 01008KT 4000 BR BKN005 08/07 Q1026"
+            .parse()
+            .unwrap();
+        assert!(supersedes(&speci, &metar));
+        assert!(!supersedes(&metar, &speci));
+        assert!(supersedes(&same_time_speci, &metar));
+        assert!(!supersedes(&metar, &same_time_speci));
+    }
+
+    #[tokio::test]
+    async fn blank_and_short_lines_are_skipped() {
+        let url = "https://metar.vatsim.net/EN";
+        let source = FixtureSource::new(Recording {
+            metars: [(
+                url.to_string(),
+                "ENGM 161220Z 01008KT CAVOK 08/04 Q1026\n\nEN\nMETAR \nENZV 161220Z 18012KT CAVOK 10/05 Q1020\n"
+                    .to_string(),
+            )]
+            .into(),
+            ..Recording::default()
+        });
+        let ignore = IndexSet::from(["ENZV".to_string()]);
+        let metars = get_metars(&source, &[url], &ignore).await.unwrap();
+        assert_eq!(
+            metars.iter().map(|m| m.icao.as_str()).collect::<Vec<_>>(),
+            ["ENGM"]
+        );
+    }
+
+    #[test]
+    fn station_of_strips_report_keyword() {
+        assert_eq!(station_of("SPECI ENGM 161235Z"), "ENGM 161235Z");
+        assert_eq!(station_of("METAR ENGM 161235Z"), "ENGM 161235Z");
+        assert_eq!(station_of("ENGM 161235Z"), "ENGM 161235Z");
+    }
 }
@@ -0,0 +1,226 @@
+//! Airports and runways from the [OurAirports](https://ourairports.com/data/)
+//! `airports.csv` and `runways.csv` exports, for areas without a sector
+//! file and for headless testing.
+//!
+//! OurAirports gives true headings and threshold positions, so runway
+//! headings come out referenced to true north even without a magnetic
+//! variation. Closed runways and helipads are skipped.
+
+use std::io::Read;
+
+use indexmap::{IndexMap, IndexSet};
+use serde::Deserialize;
+
+use crate::{
+    airport::Airport,
+    error::CoreResult,
+    geo::Coordinate,
+    runway::{Runway, RunwayDirection},
+};
+
+/// The `airports.csv` columns used here.
+#[derive(Debug, Deserialize)]
+struct AirportRecord {
+    ident: String,
+    latitude_deg: Option<f64>,
+    longitude_deg: Option<f64>,
+    elevation_ft: Option<i32>,
+}
+
+/// The `runways.csv` columns used here. `le` is the low-numbered end, `he`
+/// the high-numbered one.
+#[derive(Debug, Deserialize)]
+struct RunwayRecord {
+    airport_ident: String,
+    closed: Option<u8>,
+    le_ident: String,
+    le_latitude_deg: Option<f64>,
+    le_longitude_deg: Option<f64>,
+    #[serde(rename = "le_heading_degT")]
+    le_heading_deg_t: Option<f64>,
+    he_ident: String,
+    he_latitude_deg: Option<f64>,
+    he_longitude_deg: Option<f64>,
+    #[serde(rename = "he_heading_degT")]
+    he_heading_deg_t: Option<f64>,
+}
+
+/// Airports with at least one open runway, in `runways.csv` order.
+/// `icao_prefixes` limits the airports loaded (all when empty), and
+/// airports in `ignored_airports` are left out.
+pub fn load_ourairports<A: Read, R: Read>(
+    airports_csv: A,
+    runways_csv: R,
+    icao_prefixes: &[String],
+    ignored_airports: &IndexSet<String>,
+) -> CoreResult<IndexMap<String, Airport>> {
+    let wanted = |icao: &str| {
+        !ignored_airports.contains(icao)
+            && (icao_prefixes.is_empty()
+                || icao_prefixes
+                    .iter()
+                    .any(|prefix| icao.starts_with(prefix.as_str())))
+    };
+
+    let mut airports = IndexMap::new();
+    for record in csv::Reader::from_reader(runways_csv).deserialize() {
+        let record: RunwayRecord = record?;
+        if record.closed == Some(1) || !wanted(&record.airport_ident) {
+            continue;
+        }
+        let Some(runway) = runway(&record) else {
+            continue;
+        };
+        airports
+            .entry(record.airport_ident.clone())
+            .or_insert_with(|| Airport::new(record.airport_ident.clone()))
+            .runways
+            .push(runway);
+    }
+
+    for record in csv::Reader::from_reader(airports_csv).deserialize() {
+        let record: AirportRecord = record?;
+        let Some(airport) = airports.get_mut(&record.ident) else {
+            continue;
+        };
+        airport.elevation_ft = record.elevation_ft;
+        airport.reference_point =
+            record
+                .latitude_deg
+                .zip(record.longitude_deg)
+                .map(|(latitude, longitude)| Coordinate {
+                    latitude,
+                    longitude,
+                });
+    }
+
+    Ok(airports)
+}
+
+fn runway(record: &RunwayRecord) -> Option<Runway> {
+    let low = direction(
+        &record.le_ident,
+        record.le_latitude_deg.zip(record.le_longitude_deg),
+    )?;
+    let high = direction(
+        &record.he_ident,
+        record.he_latitude_deg.zip(record.he_longitude_deg),
+    )?;
+    let mut runway = Runway::new([low, high]);
+    // Without both thresholds, fall back on the published true headings.
+    for (direction, heading) in runway
+        .runways
+        .iter_mut()
+        .zip([record.le_heading_deg_t, record.he_heading_deg_t])
+    {
+        if direction.true_bearing.is_none() {
+            direction.true_bearing = heading;
+        }
+    }
+    Some(runway)
+}
+
+/// A runway end whose `degrees` is the nominal magnetic heading implied by
+/// its identifier (`01L` → 010). OurAirports leaves some numbers unpadded
+/// (`9L`); they are padded to two digits as in sector files. `None` for
+/// helipads and other ends without a runway number.
+fn direction(identifier: &str, threshold: Option<(f64, f64)>) -> Option<RunwayDirection> {
+    let number = RunwayDirection::number(identifier)?;
+    let designator = identifier.trim_start_matches(|c: char| c.is_ascii_digit());
+    Some(RunwayDirection {
+        degrees: number * 10,
+        identifier: format!("{number:02}{designator}"),
+        threshold: threshold.map(|(latitude, longitude)| Coordinate {
+            latitude,
+            longitude,
+        }),
+        true_bearing: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runway::HeadingReference;
+
+    const AIRPORTS: &str = concat!(
+        "\"id\",\"ident\",\"type\",\"name\",\"latitude_deg\",\"longitude_deg\",\"elevation_ft\",\"continent\",\"iso_country\",\"iso_region\",\"municipality\",\"scheduled_service\",\"icao_code\",\"iata_code\",\"gps_code\",\"local_code\",\"home_link\",\"wikipedia_link\",\"keywords\"\n",
+        "2502,\"ENGM\",\"large_airport\",\"Oslo Airport, Gardermoen\",60.193901,11.1004,681,\"EU\",\"NO\",\"NO-32\",\"Oslo\",\"yes\",\"ENGM\",\"OSL\",\"ENGM\",,,,\n",
+        "2510,\"ENZV\",\"large_airport\",\"Stavanger Airport, Sola\",58.876701,5.63778,29,\"EU\",\"NO\",\"NO-11\",\"Stavanger\",\"yes\",\"ENZV\",\"SVG\",\"ENZV\",,,,\n",
+        "2511,\"ESSA\",\"large_airport\",\"Stockholm-Arlanda Airport\",59.651901,17.918600,137,\"EU\",\"SE\",\"SE-AB\",\"Stockholm\",\"yes\",\"ESSA\",\"ARN\",\"ESSA\",,,,\n",
+    );
+
+    const RUNWAYS: &str = concat!(
+        "\"id\",\"airport_ref\",\"airport_ident\",\"length_ft\",\"width_ft\",\"surface\",\"lighted\",\"closed\",\"le_ident\",\"le_latitude_deg\",\"le_longitude_deg\",\"le_elevation_ft\",\"le_heading_degT\",\"le_displaced_threshold_ft\",\"he_ident\",\"he_latitude_deg\",\"he_longitude_deg\",\"he_elevation_ft\",\"he_heading_degT\",\"he_displaced_threshold_ft\"\n",
+        "235400,2502,\"ENGM\",11811,148,\"ASP\",1,0,\"01L\",60.185,11.073744,,16,,\"19R\",60.216067,11.091664,,196,\n",
+        "235401,2502,\"ENGM\",11811,148,\"ASP\",1,0,\"01R\",60.175756,11.107783,,16,,\"19L\",60.201208,11.122486,,196,\n",
+        "235402,2502,\"ENGM\",100,100,\"ASP\",1,0,\"H1\",,,,,,\"\",,,,,\n",
+        "235403,2510,\"ENZV\",8383,148,\"ASP\",1,0,\"10\",,,,92,,\"28\",,,,272,\n",
+        "235404,2510,\"ENZV\",9394,148,\"ASP\",1,1,\"18\",,,,,,\"36\",,,,,\n",
+        "235405,2511,\"ESSA\",10830,148,\"ASP\",1,0,\"01L\",,,,,,\"19R\",,,,,\n",
+        "235406,2511,\"ESSA\",8202,148,\"ASP\",1,0,\"8\",,,,,,\"26\",,,,,\n",
+    );
+
+    #[test]
+    fn loads_open_runways_with_elevation_and_true_headings() {
+        let airports = load_ourairports(
+            AIRPORTS.as_bytes(),
+            RUNWAYS.as_bytes(),
+            &["EN".to_string()],
+            &IndexSet::new(),
+        )
+        .unwrap();
+
+        assert_eq!(airports.keys().collect::<Vec<_>>(), ["ENGM", "ENZV"]);
+        let engm = &airports["ENGM"];
+        assert_eq!(engm.elevation_ft, Some(681));
+        assert!(engm.reference_point.is_some());
+        assert_eq!(engm.runways.len(), 2);
+        let runway_01l = &engm.runways[0].runways[0];
+        assert_eq!(runway_01l.degrees, 10);
+        assert_eq!(
+            engm.runway_heading(runway_01l),
+            (16, HeadingReference::Thresholds)
+        );
+        assert!((3570..3630).contains(&engm.runways[0].length_m.unwrap()));
+
+        let enzv = &airports["ENZV"];
+        assert_eq!(enzv.elevation_ft, Some(29));
+        assert_eq!(enzv.runways.len(), 1);
+        let runway_28 = &enzv.runways[0].runways[1];
+        assert_eq!(enzv.runways[0].length_m, None);
+        assert_eq!(
+            enzv.runway_heading(runway_28),
+            (272, HeadingReference::Thresholds)
+        );
+    }
+
+    #[test]
+    fn applies_the_ignore_list() {
+        let airports = load_ourairports(
+            AIRPORTS.as_bytes(),
+            RUNWAYS.as_bytes(),
+            &[],
+            &["ENZV".to_string()].into(),
+        )
+        .unwrap();
+        assert_eq!(airports.keys().collect::<Vec<_>>(), ["ENGM", "ESSA"]);
+        assert_eq!(airports["ESSA"].runways[0].runways[0].true_bearing, None);
+    }
+
+    #[test]
+    fn pads_single_digit_runway_numbers() {
+        let airports = load_ourairports(
+            AIRPORTS.as_bytes(),
+            RUNWAYS.as_bytes(),
+            &["ESSA".to_string()],
+            &IndexSet::new(),
+        )
+        .unwrap();
+        let runway = &airports["ESSA"].runways[1];
+        assert_eq!(runway.runways[0].identifier, "08");
+        assert_eq!(runway.runways[0].degrees, 80);
+        assert_eq!(runway.runways[1].identifier, "26");
+        assert_eq!(direction("9L", None).unwrap().identifier, "09L");
+    }
+}
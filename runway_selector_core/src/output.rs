@@ -9,7 +9,7 @@ use std::{
 use itertools::Itertools;
 use tempfile::NamedTempFile;
 
-use crate::{airport::RunwayInUseSource, airports::Airports, error::CoreResult};
+use crate::{airports::Airports, error::CoreResult};
 
 /// Read the existing `.rwy` file at `rwy_path`, preserve its `ACTIVE_AIRPORT:`
 /// header block, and rewrite the file with that header followed by
@@ -19,9 +19,11 @@ use crate::{airport::RunwayInUseSource, airports::Airports, error::CoreResult};
 /// directory and `rename`d over the target on success, so a failure midway
 /// through writing leaves the original `.rwy` untouched.
 pub fn write_runways_to_rwy_file(rwy_path: &Path, airports: &Airports) -> CoreResult<()> {
-    let start_of_file = {
-        let mut existing = File::open(rwy_path)?;
-        read_active_airport(&mut existing)?
+    let start_of_file = match File::open(rwy_path) {
+        Ok(mut existing) => read_active_airport(&mut existing)?,
+        // Areas without a sector package start without a `.rwy`.
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
     };
 
     let parent = rwy_path.parent().unwrap_or_else(|| Path::new("."));
@@ -59,22 +61,34 @@ fn write_runway_file<T: Write>(
     start_of_file: &str,
 ) -> CoreResult<()> {
     let mut writer = BufWriter::new(rwy_file);
-    writeln!(writer, "{start_of_file}")?;
+    if !start_of_file.is_empty() {
+        writeln!(writer, "{start_of_file}")?;
+    }
+
+    for line in active_runway_lines(airports) {
+        writeln!(writer, "{line}")?;
+    }
+
+    Ok(())
+}
 
+/// The `ACTIVE_RUNWAY:` lines for the current `airports` selections, as
+/// written to the `.rwy` file.
+pub fn active_runway_lines(airports: &Airports) -> Vec<String> {
+    let mut lines = Vec::new();
     for airport in airports.airports.values() {
-        if let Some(selection) = RunwayInUseSource::default_sort_order()
-            .iter()
-            .find_map(|method| airport.runways_in_use.get(method))
-        {
+        if let Some(selection) = airport.preferred_runways_in_use() {
             for (runway, usage) in selection {
                 for flag in usage.active_runway_flags() {
-                    writeln!(writer, "ACTIVE_RUNWAY:{}:{}:{}", airport.icao, runway, flag)?;
+                    lines.push(format!(
+                        "ACTIVE_RUNWAY:{}:{}:{}",
+                        airport.icao, runway, flag
+                    ));
                 }
             }
         }
     }
-
-    Ok(())
+    lines
 }
 
 #[cfg(test)]
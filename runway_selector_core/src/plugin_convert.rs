@@ -3,40 +3,49 @@
 //!
 //! The host parses METARs locally with `metar_decoder` and pre-computes the
 //! per-runway wind components **once**, then ships both to area plugins as
-//! JSON. This module is the bridge; plugins never re-do wind trigonometry
-//! or METAR parsing.
+//! JSON, together with what the airport's ATISes say. This module is the
+//! bridge; plugins never re-do wind trigonometry, METAR or ATIS parsing.
 //!
-//! Fields the plugin contract intentionally omits (BECMG, TEMPO, NATO mil
+//! Fields the plugin contract intentionally omits (trend groups, NATO mil
 //! code, sea-surface indicator, directional visibility) are dropped on the
 //! floor here — no plugin currently needs them.
 
 use metar_decoder::{
+    derived::FlightCategory as DecodedFlightCategory,
     metar::Metar as DecodedMetar,
     obscuration::{
         Cloud as DecodedCloud, CloudCoverage as DecodedCloudCoverage,
         DescribedObscuration as DecodedDescribedObscuration, Obscuration as DecodedObscuration,
         PresentWeather as DecodedPresentWeather, Qualifier as DecodedQualifier,
-        VisibilityUnit as DecodedVisibilityUnit, WeatherIntensity as DecodedWeatherIntensity,
+        WeatherIntensity as DecodedWeatherIntensity,
+    },
+    runway_state::{
+        BrakingAction as DecodedBrakingAction, ContaminationExtent as DecodedContaminationExtent,
+        DepositDepth as DecodedDepositDepth, RunwayDeposit as DecodedRunwayDeposit,
+        RunwayState as DecodedRunwayState, RunwayStateReport as DecodedRunwayStateReport,
+        WindShear as DecodedWindShear,
     },
-    pressure::{PressureSingle as DecodedPressureSingle, PressureUnit as DecodedPressureUnit},
     wind::{Wind as DecodedWind, WindDirection as DecodedWindDirection},
 };
 
 use crate::airport::{Airport, CrosswindDirection, RunwayInUseSource};
-use crate::runway::{RunwayDirection, RunwayUse};
+use crate::atis::AtisInfo;
+use crate::runway::{HeadingReference, Runway, RunwayDirection, RunwayUse};
 
 use runway_plugin_api as api;
 
-const KNOTS_PER_METER_PER_SECOND: f64 = 1.943_844;
-
-pub fn metar_to_wire(m: &DecodedMetar) -> api::MetarData {
+/// `elevation_ft` is the aerodrome elevation used for pressure and density
+/// altitude; both are left out when it is unknown.
+pub fn metar_to_wire(m: &DecodedMetar, elevation_ft: Option<i32>) -> api::MetarData {
     api::MetarData {
         raw: m.raw.clone(),
-        parsed: Some(parsed_metar_to_wire(m)),
+        parsed: Some(parsed_metar_to_wire(m, elevation_ft)),
     }
 }
 
-fn parsed_metar_to_wire(m: &DecodedMetar) -> api::ParsedMetar {
+fn parsed_metar_to_wire(m: &DecodedMetar, elevation_ft: Option<i32>) -> api::ParsedMetar {
+    let lvp_minima = m.lvp_minima();
+
     let described = match &m.obscuration {
         DecodedObscuration::Cavok => None,
         DecodedObscuration::Described(d) => Some(d),
@@ -45,7 +54,7 @@ fn parsed_metar_to_wire(m: &DecodedMetar) -> api::ParsedMetar {
     api::ParsedMetar {
         is_cavok: described.is_none(),
         wind: wind_to_wire(&m.wind),
-        visibility_meters: described.and_then(visibility_meters),
+        visibility_meters: described.and_then(|d| d.visibility.in_meters()),
         rvr: described.map(rvr_to_wire).unwrap_or_default(),
         clouds: described.map(clouds_to_wire).unwrap_or_default(),
         vertical_visibility_hundreds_ft: described.and_then(|d| {
@@ -64,19 +73,43 @@ fn parsed_metar_to_wire(m: &DecodedMetar) -> api::ParsedMetar {
             .unwrap_or_default(),
         temperature_c: m.temperature.temp.to_option(),
         dew_point_c: m.temperature.dew_point.to_option(),
-        qnh_hpa: qnh_hpa(m),
+        qnh_hpa: m.pressure.in_hectopascals().map(|hpa| hpa.round() as u32),
+        runway_states: m.runway_states.iter().map(runway_state_to_wire).collect(),
+        snow_closed: m.snow_closed,
+        wind_shear_runways: m
+            .wind_shear
+            .iter()
+            .filter_map(|ws| match ws {
+                DecodedWindShear::Runway(runway) => Some(runway.clone()),
+                DecodedWindShear::AllRunways => None,
+            })
+            .collect(),
+        wind_shear_all_runways: m.wind_shear.contains(&DecodedWindShear::AllRunways),
+        ceiling_ft: lvp_minima.ceiling_ft,
+        flight_category: m.flight_category().map(flight_category_to_wire),
+        lowest_visibility_meters: lvp_minima.visibility_m,
+        lowest_rvr_meters: lvp_minima.rvr_m,
+        relative_humidity_percent: m.relative_humidity(),
+        at_or_below_freezing: m.at_or_below_freezing(),
+        freezing_weather: m.freezing_weather(),
+        pressure_altitude_ft: elevation_ft.and_then(|e| m.pressure_altitude_ft(e)),
+        density_altitude_ft: elevation_ft.and_then(|e| m.density_altitude_ft(e)),
+    }
+}
+
+fn flight_category_to_wire(c: DecodedFlightCategory) -> api::FlightCategory {
+    match c {
+        DecodedFlightCategory::Lifr => api::FlightCategory::Lifr,
+        DecodedFlightCategory::Ifr => api::FlightCategory::Ifr,
+        DecodedFlightCategory::Mvfr => api::FlightCategory::Mvfr,
+        DecodedFlightCategory::Vfr => api::FlightCategory::Vfr,
     }
 }
 
 fn wind_to_wire(w: &DecodedWind) -> Option<api::WindData> {
+    let speed = w.speed.in_knots();
     // Speed reported as "//" means we know nothing useful about the wind.
-    let speed_raw = w.speed.velocity.to_option()?;
-    let to_knots = |v: u32| match w.speed.unit {
-        metar_decoder::units::velocity::VelocityUnit::Knots => v,
-        metar_decoder::units::velocity::VelocityUnit::MetersPerSecond => {
-            (f64::from(v) * KNOTS_PER_METER_PER_SECOND).round() as u32
-        }
-    };
+    let speed_kt = speed.velocity.to_option()?;
 
     let (direction_degrees, is_variable) = match &w.dir {
         DecodedWindDirection::Variable => (None, true),
@@ -92,22 +125,13 @@ fn wind_to_wire(w: &DecodedWind) -> Option<api::WindData> {
     Some(api::WindData {
         direction_degrees,
         is_variable,
-        speed_kt: to_knots(speed_raw),
-        gust_kt: w.speed.gust.and_then(|g| g.to_option()).map(to_knots),
+        speed_kt,
+        gust_kt: speed.gust.and_then(|g| g.to_option()),
         variable_from_degrees,
         variable_to_degrees,
     })
 }
 
-fn visibility_meters(d: &DecodedDescribedObscuration) -> Option<u32> {
-    match &d.visibility.value {
-        DecodedVisibilityUnit::Meters(opt) => opt.to_option(),
-        // US statute-mile form: not converted; plugins that care can parse
-        // `raw`. No supported area reports statute miles today.
-        DecodedVisibilityUnit::StatuteMiles(_) => None,
-    }
-}
-
 fn rvr_to_wire(d: &DecodedDescribedObscuration) -> Vec<api::RvrData> {
     d.rvr
         .iter()
@@ -171,22 +195,77 @@ fn present_weather_to_wire(p: &DecodedPresentWeather) -> api::WeatherPhenomenonD
     }
 }
 
-fn qnh_hpa(m: &DecodedMetar) -> Option<u32> {
-    let reading_to_hpa = |r: &DecodedPressureSingle| {
-        let value = r.value.to_option()?;
-        Some(match r.unit {
-            DecodedPressureUnit::Hectopascals => value,
-            // Reported in hundredths of inHg (e.g. 2992 → 29.92 inHg).
-            DecodedPressureUnit::InchesOfMercury => {
-                (f64::from(value) / 100.0 * 33.863_886).round() as u32
+fn runway_state_to_wire(state: &DecodedRunwayState) -> api::RunwayStateData {
+    let mut wire = api::RunwayStateData {
+        runway: state.runway.clone(),
+        cleared: false,
+        deposit: None,
+        contamination_extent: None,
+        depth_mm: None,
+        not_operational: false,
+        braking_action: None,
+        friction_coefficient: None,
+    };
+    let braking = match &state.report {
+        DecodedRunwayStateReport::Cleared { braking } => {
+            wire.cleared = true;
+            braking
+        }
+        DecodedRunwayStateReport::Contaminated {
+            deposit,
+            extent,
+            depth,
+            braking,
+        } => {
+            wire.deposit = deposit.to_option().map(|d| match d {
+                DecodedRunwayDeposit::ClearAndDry => api::RunwayDeposit::ClearAndDry,
+                DecodedRunwayDeposit::Damp => api::RunwayDeposit::Damp,
+                DecodedRunwayDeposit::WetOrWaterPatches => api::RunwayDeposit::WetOrWaterPatches,
+                DecodedRunwayDeposit::RimeOrFrost => api::RunwayDeposit::RimeOrFrost,
+                DecodedRunwayDeposit::DrySnow => api::RunwayDeposit::DrySnow,
+                DecodedRunwayDeposit::WetSnow => api::RunwayDeposit::WetSnow,
+                DecodedRunwayDeposit::Slush => api::RunwayDeposit::Slush,
+                DecodedRunwayDeposit::Ice => api::RunwayDeposit::Ice,
+                DecodedRunwayDeposit::CompactedSnow => api::RunwayDeposit::CompactedSnow,
+                DecodedRunwayDeposit::FrozenRuts => api::RunwayDeposit::FrozenRuts,
+            });
+            wire.contamination_extent = extent.to_option().map(|e| match e {
+                DecodedContaminationExtent::UpTo10Percent => {
+                    api::ContaminationExtent::UpTo10Percent
+                }
+                DecodedContaminationExtent::UpTo25Percent => {
+                    api::ContaminationExtent::UpTo25Percent
+                }
+                DecodedContaminationExtent::UpTo50Percent => {
+                    api::ContaminationExtent::UpTo50Percent
+                }
+                DecodedContaminationExtent::UpTo100Percent => {
+                    api::ContaminationExtent::UpTo100Percent
+                }
+            });
+            match depth.to_option() {
+                Some(DecodedDepositDepth::Millimetres(mm)) => wire.depth_mm = Some(mm),
+                Some(DecodedDepositDepth::FortyCentimetresOrMore) => wire.depth_mm = Some(400),
+                Some(DecodedDepositDepth::RunwayNotOperational) => wire.not_operational = true,
+                None => {}
             }
-        })
+            braking
+        }
     };
-    m.pressure
-        .qnh
-        .as_ref()
-        .and_then(reading_to_hpa)
-        .or_else(|| m.pressure.altimeter.as_ref().and_then(reading_to_hpa))
+    let braking_action = |action| (Some(action), None);
+    (wire.braking_action, wire.friction_coefficient) = match braking.to_option() {
+        Some(DecodedBrakingAction::FrictionCoefficient(hundredths)) => {
+            (None, Some(f64::from(hundredths) / 100.0))
+        }
+        Some(DecodedBrakingAction::Poor) => braking_action(api::BrakingAction::Poor),
+        Some(DecodedBrakingAction::MediumPoor) => braking_action(api::BrakingAction::MediumPoor),
+        Some(DecodedBrakingAction::Medium) => braking_action(api::BrakingAction::Medium),
+        Some(DecodedBrakingAction::MediumGood) => braking_action(api::BrakingAction::MediumGood),
+        Some(DecodedBrakingAction::Good) => braking_action(api::BrakingAction::Good),
+        Some(DecodedBrakingAction::Unreliable) => braking_action(api::BrakingAction::Unreliable),
+        None => (None, None),
+    };
+    wire
 }
 
 // ─── Airport / RunwayInfo / Selection ────────────────────────────────────────
@@ -200,24 +279,61 @@ pub fn airport_to_request(airport: &Airport) -> api::AirportSelectionRequest {
     let runways = airport
         .runways
         .iter()
-        .flat_map(|rwy| rwy.runways.iter())
-        .map(|dir| runway_direction_to_wire(airport, dir))
+        .flat_map(|rwy| rwy.runways.iter().map(move |dir| (rwy, dir)))
+        .map(|(rwy, dir)| runway_direction_to_wire(airport, rwy, dir))
         .collect();
 
     api::AirportSelectionRequest {
         icao: airport.icao.clone(),
         runways,
-        metar: airport.metar.as_ref().map(metar_to_wire),
+        metar: airport
+            .metar
+            .as_ref()
+            .map(|m| metar_to_wire(m, airport.elevation_ft)),
+        atis: airport
+            .atis
+            .iter()
+            .map(|(callsign, info)| atis_to_wire(callsign, info))
+            .collect(),
+    }
+}
+
+fn atis_to_wire(callsign: &str, info: &AtisInfo) -> api::AtisData {
+    api::AtisData {
+        callsign: callsign.to_string(),
+        letter: info.letter.map(String::from),
+        low_visibility_procedures: info.low_visibility_procedures,
+        lvp_category: info.lvp_category,
+        transition_level: info.transition_level,
+        runway_conditions: info
+            .runway_conditions
+            .iter()
+            .map(|condition| api::RunwayConditionData {
+                runway: condition.runway.clone(),
+                codes: condition.codes,
+            })
+            .collect(),
+        closed_runways: info.closed_runways.clone(),
     }
 }
 
-fn runway_direction_to_wire(airport: &Airport, dir: &RunwayDirection) -> api::RunwayInfo {
+fn runway_direction_to_wire(
+    airport: &Airport,
+    runway: &Runway,
+    dir: &RunwayDirection,
+) -> api::RunwayInfo {
     let headwind = airport.runway_max_headwind(dir);
     let tailwind = airport.runway_max_tailwind(dir);
     let crosswind = airport.runway_max_crosswind(dir);
+    let (heading, reference) = airport.runway_heading(dir);
     api::RunwayInfo {
         identifier: dir.identifier.clone(),
-        heading: dir.degrees,
+        heading: heading as u16,
+        heading_reference: Some(match reference {
+            HeadingReference::Thresholds => api::HeadingReference::TrueFromThresholds,
+            HeadingReference::Variation => api::HeadingReference::TrueFromVariation,
+            HeadingReference::Magnetic => api::HeadingReference::Magnetic,
+        }),
         headwind_kt: headwind,
         tailwind_kt: tailwind,
         crosswind_kt: crosswind.map(|(magnitude, _)| magnitude.max(0)),
@@ -226,6 +342,8 @@ fn runway_direction_to_wire(airport: &Airport, dir: &RunwayDirection) -> api::Ru
             CrosswindDirection::Right => api::CrosswindDirection::Right,
             CrosswindDirection::Variable => api::CrosswindDirection::Variable,
         }),
+        true_bearing_deg: dir.true_bearing,
+        length_m: runway.length_m,
     }
 }
 
@@ -260,7 +378,70 @@ mod tests {
 
     fn parsed(raw: &str) -> api::ParsedMetar {
         let m = Metar::from_str(raw).unwrap();
-        parsed_metar_to_wire(&m)
+        parsed_metar_to_wire(&m, None)
+    }
+
+    #[test]
+    fn converts_derived_fields() {
+        let m = Metar::from_str("ENGM 111150Z 01008KT 0800 R01L/1000N FZFG BKN003 M02/M03 Q1003")
+            .unwrap();
+        let p = parsed_metar_to_wire(&m, Some(681));
+
+        assert_eq!(p.ceiling_ft, Some(300));
+        assert_eq!(p.flight_category, Some(api::FlightCategory::Lifr));
+        assert_eq!(p.lowest_visibility_meters, Some(800));
+        assert_eq!(p.lowest_rvr_meters, Some(1000));
+        assert_eq!(p.at_or_below_freezing, Some(true));
+        assert!(p.freezing_weather);
+        assert!(p.relative_humidity_percent.unwrap() > 90.0);
+        assert_eq!(p.pressure_altitude_ft, Some(962));
+        assert!(p.density_altitude_ft.unwrap() < 962);
+
+        let without_elevation = parsed_metar_to_wire(&m, None);
+        assert_eq!(without_elevation.pressure_altitude_ft, None);
+        assert_eq!(without_elevation.density_altitude_ft, None);
+    }
+
+    #[test]
+    fn ships_runway_geometry() {
+        let airport =
+            crate::airports::tests::make_test_airport("ENGM 111150Z 01008KT CAVOK 12/10 Q1026");
+        let request = airport_to_request(&airport);
+        let rwy_01l = request
+            .runways
+            .iter()
+            .find(|r| r.identifier == "01L")
+            .unwrap();
+        assert!((rwy_01l.true_bearing_deg.unwrap() - 16.0).abs() < 0.1);
+        assert!((3570..3630).contains(&rwy_01l.length_m.unwrap()));
+        assert_eq!(rwy_01l.heading, 16);
+        assert_eq!(
+            rwy_01l.heading_reference,
+            Some(api::HeadingReference::TrueFromThresholds)
+        );
+    }
+
+    #[test]
+    fn ships_atis_info() {
+        let mut airport =
+            crate::airports::tests::make_test_airport("ENGM 111150Z 01008KT CAVOK 12/10 Q1026");
+        assert!(airport_to_request(&airport).atis.is_empty());
+
+        airport.atis.insert(
+            "ENGM_ATIS".to_string(),
+            AtisInfo::parse(
+                "OSLO GARDERMOEN INFORMATION KILO .. RCR RWY 01L .. RWYCC 5/5/5 .. RWY 01R CLOSED .. LVP CAT II IN OPERATION .. TRANSITION LEVEL 85",
+            ),
+        );
+        let atis = &airport_to_request(&airport).atis[0];
+        assert_eq!(atis.callsign, "ENGM_ATIS");
+        assert_eq!(atis.letter.as_deref(), Some("K"));
+        assert!(atis.low_visibility_procedures);
+        assert_eq!(atis.lvp_category, Some(2));
+        assert_eq!(atis.transition_level, Some(85));
+        assert_eq!(atis.runway_conditions[0].runway.as_deref(), Some("01L"));
+        assert_eq!(atis.runway_conditions[0].codes, [5, 5, 5]);
+        assert_eq!(atis.closed_runways, ["01R"]);
     }
 
     #[test]
@@ -290,6 +471,29 @@ mod tests {
         assert_eq!(p.qnh_hpa, Some(1026));
     }
 
+    #[test]
+    fn converts_runway_state_and_wind_shear() {
+        let p = parsed(
+            "ENGM 161220Z 01008KT 9999 -SN BKN010 M02/M04 Q1002 WS R19R R01L/459335 R01R/CLRD95",
+        );
+        assert_eq!(p.wind_shear_runways, vec!["19R".to_string()]);
+        assert!(!p.wind_shear_all_runways);
+
+        let contaminated = &p.runway_states[0];
+        assert_eq!(contaminated.runway, "01L");
+        assert_eq!(contaminated.deposit, Some(api::RunwayDeposit::DrySnow));
+        assert_eq!(
+            contaminated.contamination_extent,
+            Some(api::ContaminationExtent::UpTo50Percent)
+        );
+        assert_eq!(contaminated.depth_mm, Some(150));
+        assert_eq!(contaminated.friction_coefficient, Some(0.35));
+
+        let cleared = &p.runway_states[1];
+        assert!(cleared.cleared);
+        assert_eq!(cleared.braking_action, Some(api::BrakingAction::Good));
+    }
+
     #[test]
     fn cavok_sets_flag_and_empties_weather() {
         let p = parsed("ENZV 011200Z 05010KT CAVOK 20/20 Q1013");
@@ -315,6 +519,20 @@ mod tests {
         assert_eq!(wind.speed_kt, 10); // 5 m/s ≈ 9.7 kt, rounded
     }
 
+    #[test]
+    fn kmh_wind_converts_to_knots() {
+        let p = parsed("UTTT 111150Z 25037G55KMH 9999 OVC009 05/03 Q1010");
+        let wind = p.wind.unwrap();
+        assert_eq!(wind.speed_kt, 20);
+        assert_eq!(wind.gust_kt, Some(30));
+    }
+
+    #[test]
+    fn statute_mile_visibility_converts_to_meters() {
+        let p = parsed("KJFK 111150Z 25006KT 1 1/2SM BR FEW050 22/12 A2992");
+        assert_eq!(p.visibility_meters, Some(2414));
+    }
+
     #[test]
     fn vertical_visibility_present() {
         let p = parsed("ENZV 111920Z 30010KT 4000 -DZ BR VV007 13/12 Q1027");
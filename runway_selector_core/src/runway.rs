@@ -1,12 +1,89 @@
+use runway_selector_area_config::AtisRole;
+
+use crate::geo::Coordinate;
+
 #[derive(Debug)]
 pub struct RunwayDirection {
     pub degrees: u16,
     pub identifier: String,
+    /// Threshold position, when the sector file gives one.
+    pub threshold: Option<Coordinate>,
+    /// Bearing from this threshold to the opposite one, in degrees true.
+    /// Set by [`Runway::new`] when both thresholds are known, or from the
+    /// published true heading by sources that have one.
+    pub true_bearing: Option<f64>,
+}
+
+/// What a runway heading is referenced to. METAR winds are true, so wind
+/// components are only exact on a true heading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadingReference {
+    /// True, from the bearing between the threshold coordinates or a
+    /// published true heading.
+    Thresholds,
+    /// True, from the magnetic heading and the sector file's magnetic
+    /// variation.
+    Variation,
+    /// The sector file's magnetic heading, uncorrected.
+    Magnetic,
+}
+
+impl RunwayDirection {
+    /// The runway number of the identifier, `09C` → 9. `None` for helipads
+    /// and other identifiers without one.
+    pub fn number(identifier: &str) -> Option<u16> {
+        let digits: String = identifier
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits
+            .parse()
+            .ok()
+            .filter(|number| (1..=36).contains(number))
+    }
+
+    /// The heading wind components are computed on, in whole degrees: the
+    /// threshold bearing, else the magnetic heading corrected by
+    /// `magnetic_variation` (degrees, east positive), else the magnetic
+    /// heading.
+    pub fn true_heading(&self, magnetic_variation: Option<f64>) -> (u32, HeadingReference) {
+        let (heading, reference) = match (self.true_bearing, magnetic_variation) {
+            (Some(bearing), _) => (bearing, HeadingReference::Thresholds),
+            (None, Some(variation)) => (
+                f64::from(self.degrees) + variation,
+                HeadingReference::Variation,
+            ),
+            (None, None) => (f64::from(self.degrees), HeadingReference::Magnetic),
+        };
+        (heading.round().rem_euclid(360.0) as u32, reference)
+    }
 }
 
 #[derive(Debug)]
 pub struct Runway {
     pub runways: [RunwayDirection; 2],
+    /// Distance between the thresholds in metres.
+    pub length_m: Option<u32>,
+}
+
+impl Runway {
+    /// A runway between two directions, with its length and true bearings
+    /// worked out from the thresholds when both are known. Thresholds at the
+    /// same position give a length of 0 and no bearing.
+    pub fn new(mut runways: [RunwayDirection; 2]) -> Self {
+        let length_m = match (runways[0].threshold, runways[1].threshold) {
+            (Some(first), Some(second)) => {
+                let length = first.distance_m(&second);
+                if length >= 1.0 {
+                    runways[0].true_bearing = Some(first.bearing_to(&second));
+                    runways[1].true_bearing = Some(second.bearing_to(&first));
+                }
+                Some(length.round() as u32)
+            }
+            _ => None,
+        };
+        Runway { runways, length_m }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,6 +93,16 @@ pub enum RunwayUse {
     Both,
 }
 
+impl From<AtisRole> for RunwayUse {
+    fn from(role: AtisRole) -> Self {
+        match role {
+            AtisRole::Arrival => Self::Arriving,
+            AtisRole::Departure => Self::Departing,
+            AtisRole::Both => Self::Both,
+        }
+    }
+}
+
 impl RunwayUse {
     pub fn merged_with(self, other: Self) -> Self {
         match (self, other) {
@@ -0,0 +1,241 @@
+//! Sanity checks over the runways read from a sector file. Sector files in
+//! the wild carry placeholder headings (`000 000`), thresholds copied onto
+//! each other and runways listed twice; each of these quietly skews the wind
+//! components, so they are reported instead.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    airport::Airport,
+    runway::{Runway, RunwayDirection},
+    util::diff_angle,
+};
+
+/// How far a reciprocal pair may be from 180° apart.
+const RECIPROCAL_TOLERANCE_DEG: u32 = 5;
+/// How far a heading may be from its identifier times ten. Identifiers are
+/// rounded to the nearest ten degrees and lag the drift of magnetic north,
+/// so this allows more than the 5° of rounding.
+const IDENTIFIER_TOLERANCE_DEG: u32 = 10;
+/// How far a heading corrected by the magnetic variation may be from the
+/// bearing between the thresholds.
+const THRESHOLD_TOLERANCE_DEG: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunwayIssueKind {
+    /// The sector file gives `000` as the heading, its placeholder for
+    /// unknown. Runways pointing north are written `360`.
+    ZeroHeading { identifier: String },
+    /// The two ends are not about 180° apart.
+    NotReciprocal { headings: [u16; 2] },
+    /// The heading does not match the identifier, e.g. `09` on `270`.
+    IdentifierMismatch { identifier: String, heading: u16 },
+    /// The heading, corrected to true, disagrees with the bearing between
+    /// the thresholds.
+    ThresholdMismatch {
+        identifier: String,
+        heading: u16,
+        true_bearing: f64,
+    },
+    /// The same pair of identifiers is listed more than once.
+    Duplicate,
+    /// Both thresholds are at the same position.
+    ZeroLength,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunwayIssue {
+    pub icao: String,
+    /// Both identifiers, e.g. `09/27`.
+    pub runway: String,
+    pub kind: RunwayIssueKind,
+}
+
+impl Display for RunwayIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: ", self.icao, self.runway)?;
+        match &self.kind {
+            RunwayIssueKind::ZeroHeading { identifier } => {
+                write!(f, "{identifier} has heading 000")
+            }
+            RunwayIssueKind::NotReciprocal { headings } => write!(
+                f,
+                "headings {:03} and {:03} are not reciprocal",
+                headings[0], headings[1]
+            ),
+            RunwayIssueKind::IdentifierMismatch {
+                identifier,
+                heading,
+            } => write!(f, "{identifier} has heading {heading:03}"),
+            RunwayIssueKind::ThresholdMismatch {
+                identifier,
+                heading,
+                true_bearing,
+            } => write!(
+                f,
+                "{identifier} has heading {heading:03} but its thresholds lie {true_bearing:03.0}° true"
+            ),
+            RunwayIssueKind::Duplicate => write!(f, "listed more than once"),
+            RunwayIssueKind::ZeroLength => write!(f, "both thresholds are at the same position"),
+        }
+    }
+}
+
+/// Every issue with the runways of `airport`, in `[RUNWAY]` order.
+pub fn check_airport(airport: &Airport) -> Vec<RunwayIssue> {
+    let mut issues = Vec::new();
+    for (index, runway) in airport.runways.iter().enumerate() {
+        let issue = |kind| RunwayIssue {
+            icao: airport.icao.clone(),
+            runway: runway_name(runway),
+            kind,
+        };
+        let duplicate = airport.runways[..index]
+            .iter()
+            .any(|earlier| same_identifiers(earlier, runway));
+        if duplicate {
+            issues.push(issue(RunwayIssueKind::Duplicate));
+            continue;
+        }
+        if runway.length_m == Some(0) {
+            issues.push(issue(RunwayIssueKind::ZeroLength));
+        }
+
+        let [first, second] = &runway.runways;
+        if first.degrees != 0
+            && second.degrees != 0
+            && diff_angle(first.degrees.into(), second.degrees.into())
+                < 180 - RECIPROCAL_TOLERANCE_DEG
+        {
+            issues.push(issue(RunwayIssueKind::NotReciprocal {
+                headings: [first.degrees, second.degrees],
+            }));
+        }
+        for direction in &runway.runways {
+            issues.extend(
+                check_direction(direction, airport.magnetic_variation)
+                    .into_iter()
+                    .map(issue),
+            );
+        }
+    }
+    issues
+}
+
+/// Checks every airport, in order.
+pub fn check_airports<'a>(airports: impl IntoIterator<Item = &'a Airport>) -> Vec<RunwayIssue> {
+    airports.into_iter().flat_map(check_airport).collect()
+}
+
+fn check_direction(
+    direction: &RunwayDirection,
+    magnetic_variation: Option<f64>,
+) -> Option<RunwayIssueKind> {
+    let identifier = direction.identifier.clone();
+    let heading = direction.degrees;
+    if heading == 0 {
+        return Some(RunwayIssueKind::ZeroHeading { identifier });
+    }
+    if let Some(number) = RunwayDirection::number(&identifier)
+        && diff_angle(u32::from(number) * 10 % 360, u32::from(heading) % 360)
+            > IDENTIFIER_TOLERANCE_DEG
+    {
+        return Some(RunwayIssueKind::IdentifierMismatch {
+            identifier,
+            heading,
+        });
+    }
+    if let (Some(true_bearing), Some(variation)) = (direction.true_bearing, magnetic_variation) {
+        let corrected = (f64::from(heading) + variation).round().rem_euclid(360.0) as u32;
+        let bearing = true_bearing.round().rem_euclid(360.0) as u32;
+        if diff_angle(corrected, bearing) > THRESHOLD_TOLERANCE_DEG {
+            return Some(RunwayIssueKind::ThresholdMismatch {
+                identifier,
+                heading,
+                true_bearing,
+            });
+        }
+    }
+    None
+}
+
+fn runway_name(runway: &Runway) -> String {
+    format!(
+        "{}/{}",
+        runway.runways[0].identifier, runway.runways[1].identifier
+    )
+}
+
+fn same_identifiers(a: &Runway, b: &Runway) -> bool {
+    let [a_first, a_second] = &a.runways;
+    let [b_first, b_second] = &b.runways;
+    (a_first.identifier == b_first.identifier && a_second.identifier == b_second.identifier)
+        || (a_first.identifier == b_second.identifier && a_second.identifier == b_first.identifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+
+    use super::*;
+    use crate::sector_file::load_sector_file;
+
+    fn issues(sector_file: &str) -> Vec<String> {
+        let sector = load_sector_file(&mut sector_file.as_bytes(), &IndexSet::new()).unwrap();
+        check_airports(sector.airports.values())
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn flags_the_placeholder_runways_in_the_test_sector_file() {
+        assert_eq!(
+            issues(include_str!("../runway.test")),
+            [
+                "ENAX 09C/27C: both thresholds are at the same position",
+                "ENBM 09/27: 09 has heading 000",
+                "ENBM 09/27: 27 has heading 000",
+                "ENJA 06/24: 06 has heading 000",
+                "ENJA 06/24: 24 has heading 000",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_pairs_identifiers_and_duplicates() {
+        assert_eq!(
+            issues(concat!(
+                "[RUNWAY]\n",
+                "09 27 090 250 N060.00.00.000 E011.00.00.000 N060.00.00.000 E011.01.00.000 ENXA\n",
+                "09 27 270 090 N060.00.00.000 E011.00.00.000 N060.00.00.000 E011.01.00.000 ENXB\n",
+                "27 09 270 090 N060.00.00.000 E011.00.00.000 N060.00.00.000 E011.01.00.000 ENXB\n",
+                "36 18 360 180 N060.00.00.000 E011.00.00.000 N060.01.00.000 E011.00.00.000 ENXC\n",
+            )),
+            [
+                "ENXA 09/27: headings 090 and 250 are not reciprocal",
+                "ENXA 09/27: 27 has heading 250",
+                "ENXB 09/27: 09 has heading 270",
+                "ENXB 09/27: 27 has heading 090",
+                "ENXB 27/09: listed more than once",
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_headings_with_the_thresholds_when_the_variation_is_known() {
+        let issues = issues(concat!(
+            "[INFO]\nTEST\nTEST_CTR\nENXA\nN060.00.00.000\nE011.00.00.000\n60\n30\n-3.0\n1\n",
+            "[RUNWAY]\n",
+            "01 19 010 190 N060.00.00.000 E011.00.00.000 N060.01.00.000 E011.00.00.000 ENXA\n",
+            "05 23 050 230 N060.00.00.000 E011.00.00.000 N060.00.30.000 E011.01.00.000 ENXA\n",
+        ));
+        assert_eq!(
+            issues,
+            [
+                "ENXA 01/19: 01 has heading 010 but its thresholds lie 000° true",
+                "ENXA 01/19: 19 has heading 190 but its thresholds lie 180° true",
+            ]
+        );
+    }
+}
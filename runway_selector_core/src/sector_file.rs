@@ -1,3 +1,7 @@
+//! EuroScope sector file (`.sct`) decoding: the `[INFO]`, `[AIRPORT]` and
+//! `[RUNWAY]` sections. Everything else (geography, navaids, SIDs and STARs
+//! drawn as lines) is skipped.
+
 use std::io::Read;
 
 use encoding::{
@@ -9,59 +13,163 @@ use indexmap::{IndexMap, IndexSet};
 use crate::{
     airport::Airport,
     error::{CoreError, CoreResult},
+    geo::Coordinate,
     runway::{Runway, RunwayDirection},
 };
 
-pub fn load_airports_from_sct_runway_section<R: Read>(
+/// The `[INFO]` section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorInfo {
+    pub name: String,
+    pub default_callsign: String,
+    pub default_airport: String,
+    pub center: Option<Coordinate>,
+    /// Magnetic variation in degrees, east positive. Sector files write
+    /// west variation as positive, so the sign is flipped when reading.
+    pub magnetic_variation: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct SectorFile {
+    pub info: Option<SectorInfo>,
+    /// Airports with at least one runway, in `[RUNWAY]` order.
+    pub airports: IndexMap<String, Airport>,
+}
+
+pub fn load_sector_file<R: Read>(
     reader: &mut R,
     ignored_airports: &IndexSet<String>,
-) -> CoreResult<IndexMap<String, Airport>> {
+) -> CoreResult<SectorFile> {
     let sct_file = read_with_encodings(reader)?;
+    let sections = sections(&sct_file);
+    let section = |name: &str| sections.get(name).map(Vec::as_slice).unwrap_or_default();
+
+    let mut airports = runways(section("RUNWAY"), ignored_airports)?;
+    for line in section("AIRPORT") {
+        let parts: Vec<_> = line.split_whitespace().collect();
+        if parts.len() < 4 {
+            continue;
+        }
+        let Some(airport) = airports.get_mut(parts[0]) else {
+            continue;
+        };
+        airport.tower_frequency = Some(parts[1].to_string());
+        airport.reference_point = Coordinate::parse_sct(parts[2], parts[3]);
+        airport.airspace_class = parts.get(4).map(|class| class.to_string());
+    }
+
+    let info = info(section("INFO"));
+    let magnetic_variation = info.as_ref().and_then(|info| info.magnetic_variation);
+    for airport in airports.values_mut() {
+        airport.magnetic_variation = magnetic_variation;
+    }
+
+    Ok(SectorFile { info, airports })
+}
+
+fn runways(
+    lines: &[&str],
+    ignored_airports: &IndexSet<String>,
+) -> CoreResult<IndexMap<String, Airport>> {
     let mut airports = IndexMap::new();
 
-    for line in sct_file
-        .lines()
-        .skip_while(|line| *line != "[RUNWAY]")
-        .skip(1)
-        .take_while(|line| !line.is_empty())
-    {
+    for line in lines {
         let parts: Vec<_> = line.split_whitespace().collect();
         if parts.len() < 5 {
             continue;
         }
 
-        let icao = parts[parts.len() - 1];
-        if ignored_airports.contains(icao) {
+        // The ICAO code may be followed by the airport's name.
+        let icao = parts.get(8).unwrap_or(&parts[parts.len() - 1]);
+        if ignored_airports.contains(*icao) {
             continue;
         }
 
-        let airport = airports.entry(icao.to_string()).or_insert_with(|| Airport {
-            icao: icao.to_string(),
-            metar: None,
-            runways: Vec::new(),
-            runways_in_use: IndexMap::new(),
-            selection_tags: Vec::new(),
-        });
-
-        let runway = Runway {
-            runways: [
-                RunwayDirection {
-                    degrees: parts[2].parse()?,
-                    identifier: parts[0].into(),
-                },
-                RunwayDirection {
-                    degrees: parts[3].parse()?,
-                    identifier: parts[1].into(),
-                },
-            ],
+        let airport = airports
+            .entry(icao.to_string())
+            .or_insert_with(|| Airport::new(icao.to_string()));
+
+        let threshold = |latitude: usize| {
+            parts
+                .get(latitude..=latitude + 1)
+                .and_then(|position| Coordinate::parse_sct(position[0], position[1]))
         };
-        airport.runways.push(runway);
+        airport.runways.push(Runway::new([
+            RunwayDirection {
+                degrees: parts[2].parse()?,
+                identifier: parts[0].into(),
+                threshold: threshold(4),
+                true_bearing: None,
+            },
+            RunwayDirection {
+                degrees: parts[3].parse()?,
+                identifier: parts[1].into(),
+                threshold: threshold(6),
+                true_bearing: None,
+            },
+        ]));
     }
 
     Ok(airports)
 }
 
-fn read_with_encodings<R: Read>(reader: &mut R) -> CoreResult<String> {
+/// Name, default callsign, default airport, centre latitude and longitude,
+/// nautical miles per degree of latitude and longitude, magnetic variation
+/// and scale, one per line.
+fn info(lines: &[&str]) -> Option<SectorInfo> {
+    let line = |index: usize| lines.get(index).copied().unwrap_or_default();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(SectorInfo {
+        name: line(0).to_string(),
+        default_callsign: line(1).to_string(),
+        default_airport: line(2).to_string(),
+        center: Coordinate::parse_sct(line(3), line(4)),
+        magnetic_variation: line(7).parse::<f64>().ok().map(|west| -west),
+    })
+}
+
+/// The sections [`load_sector_file`] reads, as a sector file of their own:
+/// everything the runway selection depends on, without the geography.
+pub fn runway_sections<R: Read>(reader: &mut R) -> CoreResult<String> {
+    let sct_file = read_with_encodings(reader)?;
+    let sections = sections(&sct_file);
+    let mut text = String::new();
+    for name in ["INFO", "AIRPORT", "RUNWAY"] {
+        if let Some(lines) = sections.get(name) {
+            text.push_str(&format!("[{name}]\n"));
+            for line in lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// The lines of each `[SECTION]`, keyed by upper-case name, without
+/// comments or blank lines. Lines before the first section (`#define`s)
+/// are dropped.
+pub(crate) fn sections(text: &str) -> IndexMap<String, Vec<&str>> {
+    let mut sections: IndexMap<String, Vec<&str>> = IndexMap::new();
+    let mut current = None;
+    for line in text.lines() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.trim().to_ascii_uppercase();
+            current = Some(sections.entry(name).or_default());
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    sections
+}
+
+pub(crate) fn read_with_encodings<R: Read>(reader: &mut R) -> CoreResult<String> {
     let mut buffer = Vec::new();
     reader.read_to_end(&mut buffer)?;
 
@@ -74,3 +182,100 @@ fn read_with_encodings<R: Read>(reader: &mut R) -> CoreResult<String> {
             .map_err(|_| CoreError::Encoding(e.to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(text: &str) -> SectorFile {
+        load_sector_file(&mut text.as_bytes(), &IndexSet::new()).unwrap()
+    }
+
+    #[test]
+    fn reads_info_airport_and_runway_sections() {
+        let sector = load(concat!(
+            "#define COLOR 255\n",
+            "[INFO]\n",
+            "ENOR FIR ; AIRAC 2510\n",
+            "ENOR_CTR\n",
+            "ENGM\n",
+            "N060.11.39.000\n",
+            "E011.05.02.000\n",
+            "60\n",
+            "30\n",
+            "-3.5\n",
+            "1\n",
+            "\n",
+            "[AIRPORT]\n",
+            "ENGM 118.300 N060.12.10.000 E011.05.02.000 D\n",
+            "ENZZ 118.000 N060.00.00.000 E011.00.00.000 D\n",
+            "[RUNWAY]\n",
+            "01L 19R 012 192 N060.11.06.000 E011.04.25.478 N060.12.57.841 E011.05.29.990 ENGM\n",
+            "\n",
+            "; closed\n",
+            "01R 19L 012 192 N060.10.32.721 E011.06.28.018 N060.12.04.348 E011.07.20.949 ENGM Gardermoen\n",
+            "09  27  090 270 GM GM GM GM ENXX\n",
+            "[VOR]\n",
+            "GM 116.100 N060.11.39.000 E011.05.02.000\n",
+        ));
+
+        let info = sector.info.unwrap();
+        assert_eq!(info.name, "ENOR FIR");
+        assert_eq!(info.default_callsign, "ENOR_CTR");
+        assert_eq!(info.default_airport, "ENGM");
+        assert!(info.center.is_some());
+        assert_eq!(info.magnetic_variation, Some(3.5));
+
+        assert_eq!(sector.airports.keys().collect::<Vec<_>>(), ["ENGM", "ENXX"]);
+        let engm = &sector.airports["ENGM"];
+        assert_eq!(engm.tower_frequency.as_deref(), Some("118.300"));
+        assert_eq!(engm.airspace_class.as_deref(), Some("D"));
+        assert!(engm.reference_point.is_some());
+        assert_eq!(engm.runways.len(), 2);
+        let runway = &engm.runways[0];
+        assert!((3570..3630).contains(&runway.length_m.unwrap()));
+        let [forward, reverse] = &runway.runways;
+        assert!((forward.true_bearing.unwrap() - 16.0).abs() < 0.1);
+        assert!((reverse.true_bearing.unwrap() - 196.0).abs() < 0.1);
+
+        let named = &sector.airports["ENXX"].runways[0];
+        assert_eq!(named.runways[0].threshold, None);
+        assert_eq!(named.length_m, None);
+        assert_eq!(named.runways[0].true_bearing, None);
+    }
+
+    #[test]
+    fn runway_sections_drop_everything_else() {
+        let whole = concat!(
+            "#define COLOR 255\n",
+            "[INFO]\nENOR FIR\nENOR_CTR\nENGM\nN060.11.39.000\nE011.05.02.000\n60\n30\n-3.5\n1\n",
+            "[VOR]\n",
+            "GM 116.100 N060.11.39.000 E011.05.02.000\n",
+            "[RUNWAY]\n",
+            "01L 19R 012 192 N060.11.06.000 E011.04.25.478 N060.12.57.841 E011.05.29.990 ENGM ; west\n",
+        );
+        let sections = runway_sections(&mut whole.as_bytes()).unwrap();
+        assert_eq!(
+            sections,
+            concat!(
+                "[INFO]\nENOR FIR\nENOR_CTR\nENGM\nN060.11.39.000\nE011.05.02.000\n60\n30\n-3.5\n1\n",
+                "[RUNWAY]\n",
+                "01L 19R 012 192 N060.11.06.000 E011.04.25.478 N060.12.57.841 E011.05.29.990 ENGM\n",
+            )
+        );
+        let (from_sections, from_whole) = (load(&sections), load(whole));
+        assert_eq!(from_sections.info, from_whole.info);
+        assert_eq!(from_sections.airports.len(), from_whole.airports.len());
+    }
+
+    #[test]
+    fn coincident_thresholds_have_no_bearing() {
+        let sector = load(
+            "[RUNWAY]\n09C 27C 090 270 N062.27.46.548 E006.18.44.640 N062.27.46.548 E006.18.44.640 ENAX\n",
+        );
+        assert!(sector.info.is_none());
+        let runway = &sector.airports["ENAX"].runways[0];
+        assert_eq!(runway.length_m, Some(0));
+        assert_eq!(runway.runways[1].true_bearing, None);
+    }
+}